@@ -0,0 +1,98 @@
+use std::{fs, io};
+use std::path::{Path, PathBuf};
+
+use devise::ext::SpanDiagnosticExt;
+use proc_macro2::TokenStream;
+
+use crate::http;
+use crate::http_codegen::{ContentType, Optional};
+
+/// A file to embed: its `/`-separated path relative to the embedded directory
+/// and its absolute path on disk.
+struct File {
+    path: String,
+    disk_path: PathBuf,
+}
+
+/// Recursively collects the files in `dir`, following symlinks. `ancestors`
+/// holds the canonical paths of the directories being collected, `dir`'s
+/// included, and is used to reject symlinks that lead back to one of them.
+fn collect(
+    root: &Path,
+    dir: &Path,
+    ancestors: &mut Vec<PathBuf>,
+    files: &mut Vec<File>,
+) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let disk_path = entry?.path();
+        if fs::metadata(&disk_path)?.is_dir() {
+            let canonical = disk_path.canonicalize()?;
+            if ancestors.contains(&canonical) {
+                let msg = format!("symlink cycle at `{}`", disk_path.display());
+                return Err(io::Error::new(io::ErrorKind::InvalidData, msg));
+            }
+
+            ancestors.push(canonical);
+            collect(root, &disk_path, ancestors, files)?;
+            ancestors.pop();
+            continue;
+        }
+
+        let relative = disk_path.strip_prefix(root).expect("entry is in root");
+        let segments = relative.iter()
+            .map(|segment| segment.to_str())
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(|| {
+                let msg = format!("file name is not valid UTF-8: {}", relative.display());
+                io::Error::new(io::ErrorKind::InvalidData, msg)
+            })?;
+
+        files.push(File { path: segments.join("/"), disk_path });
+    }
+
+    Ok(())
+}
+
+pub fn _macro(input: proc_macro::TokenStream) -> devise::Result<TokenStream> {
+    let lit: syn::LitStr = syn::parse(input)?;
+    let manifest_dir = std::env::var_os("CARGO_MANIFEST_DIR")
+        .ok_or_else(|| lit.span().error("`CARGO_MANIFEST_DIR` is not set"))?;
+
+    let root = Path::new(&manifest_dir).join(lit.value().trim_start_matches('/'));
+    let mut files = vec![];
+    root.canonicalize()
+        .and_then(|canonical| collect(&root, &root, &mut vec![canonical], &mut files))
+        .map_err(|e| {
+            lit.span()
+                .error(format!("failed to read directory `{}`: {}", root.display(), e))
+                .help("the path is relative to the crate root")
+        })?;
+
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    let mut embedded = vec![];
+    for File { path, disk_path } in files {
+        let contents = fs::read(&disk_path).map_err(|e| {
+            lit.span().error(format!("failed to read `{}`: {}", disk_path.display(), e))
+        })?;
+
+        let etag = format!("\"{:x}-{:x}\"", contents.len(), http::private::fnv1a(&contents));
+        let content_type = Optional(Path::new(&path).extension()
+            .and_then(|ext| http::ContentType::from_extension(&ext.to_string_lossy()))
+            .map(ContentType));
+
+        let disk_path = disk_path.to_string_lossy();
+        embedded.push(quote! {
+            ::rocket::fs::EmbeddedFile {
+                path: #path,
+                contents: ::core::include_bytes!(#disk_path),
+                content_type: #content_type,
+                etag: #etag,
+            }
+        });
+    }
+
+    Ok(quote!({
+        static FILES: &[::rocket::fs::EmbeddedFile] = &[#(#embedded),*];
+        ::rocket::fs::Embedded::from_files(FILES)
+    }))
+}
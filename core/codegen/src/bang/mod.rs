@@ -2,6 +2,7 @@ mod uri;
 mod uri_parsing;
 mod test_guide;
 mod export;
+mod embed;
 
 pub mod typed_stream;
 
@@ -77,3 +78,8 @@ pub fn typed_stream(input: proc_macro::TokenStream) -> TokenStream {
     typed_stream::_macro(input)
         .unwrap_or_else(|diag| diag.emit_as_item_tokens())
 }
+
+pub fn embed_macro(input: proc_macro::TokenStream) -> TokenStream {
+    embed::_macro(input)
+        .unwrap_or_else(|diag| diag.emit_as_expr_tokens())
+}
@@ -1484,6 +1484,13 @@ pub fn __typed_stream(input: TokenStream) -> TokenStream {
     emit!(bang::typed_stream(input))
 }
 
+/// Internal macro: `__embed!`.
+#[proc_macro]
+#[doc(hidden)]
+pub fn __embed(input: TokenStream) -> TokenStream {
+    emit!(bang::embed_macro(input))
+}
+
 /// Private Rocket internal macro: `internal_guide_tests!`.
 #[proc_macro]
 #[doc(hidden)]
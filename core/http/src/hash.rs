@@ -0,0 +1,34 @@
+/// Returns the 64-bit FNV-1a hash of `bytes`.
+///
+/// Unlike `DefaultHasher`, whose algorithm may change between Rust releases,
+/// the result is stable, so `ETag`s derived from it don't change, and thus
+/// don't invalidate client caches, when an application is rebuilt with a newer
+/// compiler.
+///
+/// # Example
+///
+/// ```rust
+/// # extern crate rocket;
+/// use rocket::http::private::fnv1a;
+///
+/// assert_eq!(fnv1a(b""), 0xcbf29ce484222325);
+/// assert_eq!(fnv1a(b"foobar"), 0x85944171f73967e8);
+/// ```
+pub fn fnv1a(bytes: &[u8]) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+    const PRIME: u64 = 0x100000001b3;
+
+    bytes.iter().fold(OFFSET_BASIS, |hash, &byte| (hash ^ byte as u64).wrapping_mul(PRIME))
+}
+
+#[cfg(test)]
+mod test {
+    use super::fnv1a;
+
+    #[test]
+    fn test_fnv1a() {
+        assert_eq!(fnv1a(b""), 0xcbf29ce484222325);
+        assert_eq!(fnv1a(b"a"), 0xaf63dc4c8601ec8c);
+        assert_eq!(fnv1a(b"foobar"), 0x85944171f73967e8);
+    }
+}
@@ -0,0 +1,75 @@
+/// Returns the q-value that `accept_encoding`, the comma-separated values of
+/// an `Accept-Encoding` header, gives the content coding identified by
+/// `is_coding`.
+///
+/// The q-value is that of the first coding for which `is_coding` returns
+/// `true`, else that of `*`, if listed, else `0`. Codings without a q-value
+/// have a q-value of `1`; codings with a malformed or out-of-range q-value are
+/// ignored. A q-value of `0` means the coding is not acceptable.
+///
+/// # Example
+///
+/// ```rust
+/// # extern crate rocket;
+/// use rocket::http::private::accept_encoding_qvalue;
+///
+/// let accept = "gzip;q=0.5, br, *;q=0.1";
+/// assert_eq!(accept_encoding_qvalue(accept, |c| c.eq_ignore_ascii_case("br")), 1.0);
+/// assert_eq!(accept_encoding_qvalue(accept, |c| c.eq_ignore_ascii_case("gzip")), 0.5);
+/// assert_eq!(accept_encoding_qvalue(accept, |c| c.eq_ignore_ascii_case("zstd")), 0.1);
+/// assert_eq!(accept_encoding_qvalue("br", |c| c.eq_ignore_ascii_case("zstd")), 0.0);
+/// ```
+pub fn accept_encoding_qvalue<F>(accept_encoding: &str, is_coding: F) -> f32
+    where F: Fn(&str) -> bool
+{
+    let mut wildcard = 0.0;
+    for item in accept_encoding.split(',') {
+        let mut params = item.split(';');
+        let coding = params.next().unwrap_or("").trim();
+        let qvalue = params
+            .filter_map(|param| param.split_once('='))
+            .find(|(key, _)| key.trim().eq_ignore_ascii_case("q"))
+            .map_or(Some(1.0), |(_, value)| value.trim().parse::<f32>().ok())
+            .filter(|q| (0.0..=1.0).contains(q));
+
+        let qvalue = match qvalue {
+            Some(qvalue) => qvalue,
+            None => continue,
+        };
+
+        if coding == "*" {
+            wildcard = qvalue;
+        } else if !coding.is_empty() && is_coding(coding) {
+            return qvalue;
+        }
+    }
+
+    wildcard
+}
+
+#[cfg(test)]
+mod test {
+    use super::accept_encoding_qvalue;
+
+    fn qvalue(accept_encoding: &str, coding: &str) -> f32 {
+        accept_encoding_qvalue(accept_encoding, |c| c.eq_ignore_ascii_case(coding))
+    }
+
+    #[test]
+    fn test_accept_encoding_qvalue() {
+        assert_eq!(qvalue("", "gzip"), 0.0);
+        assert_eq!(qvalue("gzip", "gzip"), 1.0);
+        assert_eq!(qvalue("GZIP; Q=0.3", "gzip"), 0.3);
+        assert_eq!(qvalue("br, gzip;q=0", "gzip"), 0.0);
+        assert_eq!(qvalue("gzip;q=0.2, gzip;q=0.9", "gzip"), 0.2);
+        assert_eq!(qvalue("br, *;q=0.4", "gzip"), 0.4);
+        assert_eq!(qvalue("*;q=0.4, gzip;q=0.1", "gzip"), 0.1);
+        assert_eq!(qvalue("*;q=0, gzip", "gzip"), 1.0);
+        assert_eq!(qvalue("*;q=0", "gzip"), 0.0);
+
+        // Malformed q-values are ignored.
+        assert_eq!(qvalue("gzip;q=2", "gzip"), 0.0);
+        assert_eq!(qvalue("gzip;q=nope, *;q=0.5", "gzip"), 0.5);
+        assert_eq!(qvalue("gzip;q=-1, gzip;q=0.5", "gzip"), 0.5);
+    }
+}
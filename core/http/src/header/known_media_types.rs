@@ -8,6 +8,8 @@ macro_rules! known_media_types {
         Text (is_text): "plain text", "text", "plain" ; "charset" => "utf-8",
         JSON (is_json): "JSON", "application", "json",
         MsgPack (is_msgpack): "MsgPack", "application", "msgpack",
+        CBOR (is_cbor): "CBOR", "application", "cbor",
+        NDJSON (is_ndjson): "NDJSON", "application", "x-ndjson",
         Form (is_form): "forms", "application", "x-www-form-urlencoded",
         JavaScript (is_javascript): "JavaScript", "text", "javascript",
         CSS (is_css): "CSS", "text", "css" ; "charset" => "utf-8",
@@ -68,6 +70,8 @@ macro_rules! known_extensions {
         "mjs" => JavaScript,
         "css" => CSS,
         "json" => JSON,
+        "cbor" => CBOR,
+        "ndjson" => NDJSON,
         "png" => PNG,
         "gif" => GIF,
         "bmp" => BMP,
@@ -122,6 +126,8 @@ macro_rules! known_shorthands {
         "text" => Text,
         "json" => JSON,
         "msgpack" => MsgPack,
+        "cbor" => CBOR,
+        "ndjson" => NDJSON,
         "form" => Form,
         "js" => JavaScript,
         "css" => CSS,
@@ -3,6 +3,7 @@ mod known_media_types;
 mod media_type;
 mod content_type;
 mod accept;
+pub(crate) mod accept_encoding;
 mod header;
 
 pub use self::content_type::ContentType;
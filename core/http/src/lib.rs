@@ -28,6 +28,8 @@ mod status;
 mod raw_str;
 mod parse;
 mod listener;
+mod proxy;
+mod hash;
 
 /// Case-preserving, ASCII case-insensitive string types.
 ///
@@ -45,7 +47,17 @@ pub mod private {
     pub use crate::parse::Indexed;
     pub use smallvec::{SmallVec, Array};
     pub use crate::listener::{TcpListener, Incoming, Listener, Connection, Certificates};
+    pub use crate::listener::{CertificateData, PeerCredentials, TlsInfo};
+    pub use crate::proxy::{ProxyListener, ProxyStream, ProxyHeader, IpRange};
+    pub use crate::header::accept_encoding::accept_encoding_qvalue;
+    pub use crate::hash::fnv1a;
     pub use cookie;
+
+    #[cfg(unix)]
+    pub use crate::listener::{UnixListener, UnixStream};
+
+    #[cfg(unix)]
+    pub use crate::listener::{activated_listeners, ActivatedListener};
 }
 
 #[doc(hidden)]
@@ -14,8 +14,13 @@ use tokio::net::TcpStream;
 use hyper::server::accept::Accept;
 use state::Storage;
 
+use crate::proxy::ProxyHeader;
+
 pub use tokio::net::TcpListener;
 
+#[cfg(unix)]
+pub use tokio::net::{UnixListener, UnixStream};
+
 /// A thin wrapper over raw, DER-encoded X.509 client certificate data.
 // NOTE: `rustls::Certificate` is exactly isomorphic to `CertificateData`.
 #[doc(inline)]
@@ -51,25 +56,95 @@ impl Certificates {
     }
 }
 
+/// Information about a connection's TLS session.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TlsInfo {
+    server_name: Option<String>,
+    alpn_protocol: Option<Vec<u8>>,
+}
+
+impl TlsInfo {
+    /// Sets the server name the client requested via SNI.
+    pub fn with_server_name(mut self, name: Option<String>) -> Self {
+        self.server_name = name;
+        self
+    }
+
+    /// Sets the application protocol negotiated via ALPN.
+    pub fn with_alpn_protocol(mut self, protocol: Option<Vec<u8>>) -> Self {
+        self.alpn_protocol = protocol;
+        self
+    }
+
+    /// The server name the client requested via SNI, if any.
+    pub fn server_name(&self) -> Option<&str> {
+        self.server_name.as_deref()
+    }
+
+    /// The application protocol negotiated via ALPN, if any, such as `h2`.
+    pub fn alpn_protocol(&self) -> Option<&[u8]> {
+        self.alpn_protocol.as_deref()
+    }
+}
+
+/// Credentials of the process on the remote end of a Unix domain socket.
+///
+/// Credentials are retrieved from the operating system (`SO_PEERCRED` or
+/// equivalent) when a connection is accepted and thus cannot be forged by the
+/// remote process.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PeerCredentials {
+    uid: u32,
+    gid: u32,
+    pid: Option<i32>,
+}
+
+impl PeerCredentials {
+    /// The effective user ID of the remote process.
+    pub fn uid(&self) -> u32 {
+        self.uid
+    }
+
+    /// The effective group ID of the remote process.
+    pub fn gid(&self) -> u32 {
+        self.gid
+    }
+
+    /// The process ID of the remote process, if the platform reports it.
+    pub fn pid(&self) -> Option<i32> {
+        self.pid
+    }
+}
+
 // TODO.async: 'Listener' and 'Connection' provide common enough functionality
 // that they could be introduced in upstream libraries.
-/// A 'Listener' yields incoming connections
+/// A `Listener` yields incoming connections.
+///
+/// Rocket implements `Listener` for TCP listeners, TLS listeners, and, on Unix,
+/// Unix domain socket listeners. Any other source of connections, such as a
+/// pre-bound socket or an in-memory channel of streams, can be served by
+/// implementing this trait and passing the listener to Rocket's `launch_on()`.
 pub trait Listener {
     /// The connection type returned by this listener.
     type Connection: Connection;
 
-    /// Return the actual address this listener bound to.
+    /// Return the actual address this listener bound to, if it has one.
     fn local_addr(&self) -> Option<SocketAddr>;
 
     /// Try to accept an incoming Connection if ready. This should only return
     /// an `Err` when a fatal problem occurs as Hyper kills the server on `Err`.
+    ///
+    /// Errors of kind `ConnectionRefused`, `ConnectionAborted`, and
+    /// `ConnectionReset` are considered to affect only the connection being
+    /// accepted; the next connection is accepted immediately. On all other
+    /// errors, Rocket waits briefly before trying to accept again.
     fn poll_accept(
         self: Pin<&mut Self>,
         cx: &mut Context<'_>
     ) -> Poll<io::Result<Self::Connection>>;
 }
 
-/// A 'Connection' represents an open connection to a client
+/// A `Connection` represents an open connection to a client.
 pub trait Connection: AsyncRead + AsyncWrite {
     /// The remote address, i.e. the client's socket address, if it is known.
     fn peer_address(&self) -> Option<SocketAddr>;
@@ -88,6 +163,34 @@ pub trait Connection: AsyncRead + AsyncWrite {
     /// Defaults to an empty vector to indicate that no certificates were
     /// presented.
     fn peer_certificates(&self) -> Option<Certificates> { None }
+
+    /// Credentials of the remote process, if the connection is local and the
+    /// operating system exposes them, as is the case for Unix domain sockets.
+    ///
+    /// Defaults to `None`.
+    fn peer_credentials(&self) -> Option<PeerCredentials> { None }
+
+    /// The PROXY protocol header the upstream sent ahead of the connection's
+    /// data, if any. See [`ProxyListener`](crate::private::ProxyListener).
+    ///
+    /// Defaults to `None`.
+    fn proxy_header(&self) -> Option<Arc<ProxyHeader>> { None }
+
+    /// Information about the connection's TLS session, if the connection is
+    /// secured by TLS and its handshake has completed.
+    ///
+    /// Defaults to `None`.
+    fn tls_info(&self) -> Option<TlsInfo> { None }
+
+    /// Drives any handshake the connection performs before exchanging data,
+    /// such as a TLS handshake, to completion. Information established by the
+    /// handshake, like [`Connection::tls_info()`], is available once this
+    /// returns `Ready(Ok(()))`. Reading or writing also drives the handshake.
+    ///
+    /// Defaults to returning `Ready(Ok(()))` immediately.
+    fn poll_handshake(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
 }
 
 pin_project_lite::pin_project! {
@@ -246,3 +349,165 @@ impl Connection for TcpStream {
         self.set_nodelay(true)
     }
 }
+
+impl Connection for tokio::io::DuplexStream {
+    /// Always `None`: in-memory streams have no remote address.
+    #[inline]
+    fn peer_address(&self) -> Option<SocketAddr> {
+        None
+    }
+
+    /// In-memory streams have no delay to disable; this is a no-op.
+    #[inline]
+    fn enable_nodelay(&self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// A listening socket inherited from a service manager via socket activation.
+#[cfg(unix)]
+#[derive(Debug)]
+pub enum ActivatedListener {
+    /// A TCP socket.
+    Tcp(TcpListener),
+    /// A Unix domain socket.
+    Unix(UnixListener),
+}
+
+/// Adopts the listening sockets passed to this process by a service manager,
+/// such as systemd, via the `LISTEN_PID`, `LISTEN_FDS`, and `LISTEN_FDNAMES`
+/// environment variables. See `sd_listen_fds(3)`.
+///
+/// Returns the sockets, with their names if any, in file descriptor order. If
+/// no sockets were passed to _this_ process, or if they were already adopted by
+/// a previous call, returns an empty vector. The sockets are marked
+/// close-on-exec so that child processes don't inherit them. The environment
+/// variables are left unchanged as modifying the environment is unsound while
+/// other threads may read it; since `LISTEN_PID` names this process, child
+/// processes ignore them. Must be called from within a tokio runtime.
+#[cfg(unix)]
+pub fn activated_listeners() -> io::Result<Vec<(Option<String>, ActivatedListener)>> {
+    use std::os::unix::io::RawFd;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    // The first passed file descriptor, per `sd_listen_fds(3)`.
+    const LISTEN_FDS_START: RawFd = 3;
+
+    // Whether the passed sockets have been adopted, and are thus owned.
+    static ADOPTED: AtomicBool = AtomicBool::new(false);
+
+    let invalid = |msg| io::Error::new(io::ErrorKind::InvalidInput, msg);
+    let vars = ["LISTEN_PID", "LISTEN_FDS", "LISTEN_FDNAMES"];
+    let [pid, fds, names] = vars.map(|var| std::env::var(var).ok());
+
+    let (pid, fds) = match (pid, fds) {
+        (Some(pid), Some(fds)) => (pid, fds),
+        _ => return Ok(vec![]),
+    };
+
+    // The variables may have been inherited from a parent process.
+    if pid.parse::<u32>().map_err(|_| invalid("invalid `LISTEN_PID`"))? != std::process::id() {
+        return Ok(vec![]);
+    }
+
+    if ADOPTED.swap(true, Ordering::AcqRel) {
+        return Ok(vec![]);
+    }
+
+    let count: RawFd = fds.parse().map_err(|_| invalid("invalid `LISTEN_FDS`"))?;
+    let names = names.unwrap_or_default();
+    let mut names = names.split(':');
+    (LISTEN_FDS_START..LISTEN_FDS_START.saturating_add(count)).map(|fd| {
+        let name = names.next().filter(|name| !name.is_empty()).map(String::from);
+        Ok((name, adopt(fd)?))
+    }).collect()
+}
+
+/// Takes ownership of the listening socket `fd` passed by a service manager.
+#[cfg(unix)]
+fn adopt(fd: std::os::unix::io::RawFd) -> io::Result<ActivatedListener> {
+    use std::mem::{size_of, zeroed};
+    use std::os::raw::{c_int, c_void};
+    use std::os::unix::io::FromRawFd;
+
+    fn check(result: c_int) -> io::Result<c_int> {
+        match result {
+            -1 => Err(io::Error::last_os_error()),
+            n => Ok(n),
+        }
+    }
+
+    let flags = check(unsafe { libc::fcntl(fd, libc::F_GETFD) })?;
+    check(unsafe { libc::fcntl(fd, libc::F_SETFD, flags | libc::FD_CLOEXEC) })?;
+
+    let mut listening: c_int = 0;
+    let mut len = size_of::<c_int>() as libc::socklen_t;
+    let value = &mut listening as *mut c_int as *mut c_void;
+    check(unsafe { libc::getsockopt(fd, libc::SOL_SOCKET, libc::SO_ACCEPTCONN, value, &mut len) })?;
+    if listening == 0 {
+        let msg = format!("passed file descriptor {} is not a listening socket", fd);
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, msg));
+    }
+
+    let mut addr: libc::sockaddr_storage = unsafe { zeroed() };
+    let mut len = size_of::<libc::sockaddr_storage>() as libc::socklen_t;
+    let sockaddr = &mut addr as *mut libc::sockaddr_storage as *mut libc::sockaddr;
+    check(unsafe { libc::getsockname(fd, sockaddr, &mut len) })?;
+
+    // SAFETY: The service manager passed ownership of `fd` to us, and `fd` is
+    // a listening socket of the family we convert it to.
+    match addr.ss_family as c_int {
+        libc::AF_INET | libc::AF_INET6 => {
+            let tcp = unsafe { std::net::TcpListener::from_raw_fd(fd) };
+            tcp.set_nonblocking(true)?;
+            Ok(ActivatedListener::Tcp(TcpListener::from_std(tcp)?))
+        }
+        libc::AF_UNIX => {
+            let unix = unsafe { std::os::unix::net::UnixListener::from_raw_fd(fd) };
+            unix.set_nonblocking(true)?;
+            Ok(ActivatedListener::Unix(UnixListener::from_std(unix)?))
+        }
+        family => {
+            let msg = format!("passed socket {} has unsupported family {}", fd, family);
+            Err(io::Error::new(io::ErrorKind::InvalidInput, msg))
+        }
+    }
+}
+
+#[cfg(unix)]
+impl Listener for UnixListener {
+    type Connection = UnixStream;
+
+    /// Always `None`: Unix domain sockets aren't bound to a `SocketAddr`.
+    #[inline]
+    fn local_addr(&self) -> Option<SocketAddr> {
+        None
+    }
+
+    #[inline]
+    fn poll_accept(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>
+    ) -> Poll<io::Result<Self::Connection>> {
+        (*self).poll_accept(cx).map_ok(|(stream, _addr)| stream)
+    }
+}
+
+#[cfg(unix)]
+impl Connection for UnixStream {
+    #[inline]
+    fn peer_address(&self) -> Option<SocketAddr> {
+        None
+    }
+
+    /// Unix domain sockets have no delay to disable; this is a no-op.
+    #[inline]
+    fn enable_nodelay(&self) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn peer_credentials(&self) -> Option<PeerCredentials> {
+        let cred = self.peer_cred().ok()?;
+        Some(PeerCredentials { uid: cred.uid(), gid: cred.gid(), pid: cred.pid() })
+    }
+}
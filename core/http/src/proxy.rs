@@ -0,0 +1,655 @@
+use std::fmt;
+use std::io;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::str::FromStr;
+use std::future::Future;
+use std::time::Duration;
+use std::task::{Context, Poll};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, ReadBuf};
+
+use crate::listener::{Connection, Listener, Certificates, PeerCredentials, TlsInfo};
+
+/// The signature that begins every PROXY protocol v2 header.
+const V2_SIGNATURE: &[u8; 12] = b"\r\n\r\n\0\r\nQUIT\n";
+
+/// The maximum length of a PROXY protocol v1 header, including the CRLF.
+const V1_MAX_LEN: usize = 107;
+
+/// How long a trusted upstream has to send the header before it's dropped,
+/// unless otherwise configured.
+const DEFAULT_HEADER_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A range of IP addresses in CIDR notation, such as `10.0.0.0/8`.
+///
+/// A bare address, such as `192.168.1.1`, is a range containing only that
+/// address.
+///
+/// # Example
+///
+/// ```rust
+/// # extern crate rocket;
+/// use rocket::listener::IpRange;
+///
+/// let range: IpRange = "10.0.0.0/8".parse().unwrap();
+/// assert!(range.contains("10.1.2.3".parse().unwrap()));
+/// assert!(!range.contains("11.0.0.1".parse().unwrap()));
+///
+/// let range: IpRange = "::1".parse().unwrap();
+/// assert!(range.contains("::1".parse().unwrap()));
+/// assert_eq!(range.to_string(), "::1/128");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct IpRange {
+    addr: IpAddr,
+    prefix: u8,
+}
+
+impl IpRange {
+    /// Returns a range of all addresses that share the first `prefix` bits of
+    /// `addr`, or `None` if `prefix` is longer than `addr`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # extern crate rocket;
+    /// use std::net::Ipv4Addr;
+    /// use rocket::listener::IpRange;
+    ///
+    /// let range = IpRange::new(Ipv4Addr::new(192, 168, 0, 0).into(), 16).unwrap();
+    /// assert!(range.contains(Ipv4Addr::new(192, 168, 42, 1).into()));
+    /// assert!(IpRange::new(Ipv4Addr::LOCALHOST.into(), 33).is_none());
+    /// ```
+    pub fn new(addr: IpAddr, prefix: u8) -> Option<IpRange> {
+        let max = if addr.is_ipv4() { 32 } else { 128 };
+        (prefix <= max).then(|| IpRange { addr, prefix })
+    }
+
+    /// Returns `true` if `addr` is in this range. IPv4-mapped IPv6 addresses
+    /// are considered equal to the IPv4 address they map.
+    pub fn contains(&self, addr: IpAddr) -> bool {
+        fn mask(bits: u32, prefix: u8) -> u128 {
+            match prefix {
+                0 => 0,
+                p => (!0u128 << (bits - p as u32)) & (!0u128 >> (128 - bits)),
+            }
+        }
+
+        let addr = match addr {
+            IpAddr::V6(v6) => match v6.octets() {
+                [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0xff, 0xff, a, b, c, d] => {
+                    IpAddr::V4(Ipv4Addr::new(a, b, c, d))
+                }
+                _ => addr,
+            },
+            v4 => v4,
+        };
+
+        match (self.addr, addr) {
+            (IpAddr::V4(net), IpAddr::V4(ip)) => {
+                let mask = mask(32, self.prefix);
+                (u32::from(net) as u128) & mask == (u32::from(ip) as u128) & mask
+            }
+            (IpAddr::V6(net), IpAddr::V6(ip)) => {
+                let mask = mask(128, self.prefix);
+                u128::from(net) & mask == u128::from(ip) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+impl FromStr for IpRange {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (addr, prefix) = match s.split_once('/') {
+            Some((addr, prefix)) => (addr, Some(prefix)),
+            None => (s, None),
+        };
+
+        let addr: IpAddr = addr.parse().map_err(|e| format!("invalid address: {}", e))?;
+        let prefix = match prefix {
+            Some(p) => p.parse().map_err(|e| format!("invalid prefix length: {}", e))?,
+            None if addr.is_ipv4() => 32,
+            None => 128,
+        };
+
+        IpRange::new(addr, prefix).ok_or_else(|| format!("prefix length {} is too long", prefix))
+    }
+}
+
+impl fmt::Display for IpRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.addr, self.prefix)
+    }
+}
+
+/// The information conveyed by a PROXY protocol header.
+///
+/// Both version 1 (text) and version 2 (binary) headers are supported. Only
+/// version 2 headers carry TLVs (type-length-values).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProxyHeader {
+    version: u8,
+    source: Option<SocketAddr>,
+    destination: Option<SocketAddr>,
+    tlvs: Vec<(u8, Vec<u8>)>,
+}
+
+impl ProxyHeader {
+    /// The TLV type for the ALPN protocol negotiated by the upstream.
+    pub const ALPN: u8 = 0x01;
+
+    /// The TLV type for the host name the client requested, such as the TLS
+    /// SNI value.
+    pub const AUTHORITY: u8 = 0x02;
+
+    /// The TLV type for TLS connection details.
+    pub const SSL: u8 = 0x20;
+
+    /// The protocol version of the header: `1` or `2`.
+    pub fn version(&self) -> u8 {
+        self.version
+    }
+
+    /// The address of the original client, if the upstream conveyed one.
+    ///
+    /// This is `None` for version 2 `LOCAL` headers, such as those sent by
+    /// health checks, and for `UNKNOWN` and non-IP addresses.
+    pub fn source(&self) -> Option<SocketAddr> {
+        self.source
+    }
+
+    /// The address the original client connected to, if the upstream conveyed
+    /// one.
+    pub fn destination(&self) -> Option<SocketAddr> {
+        self.destination
+    }
+
+    /// The value of the first TLV of type `kind`, if there is one.
+    pub fn tlv(&self, kind: u8) -> Option<&[u8]> {
+        self.tlvs.iter().find(|(k, _)| *k == kind).map(|(_, v)| &v[..])
+    }
+
+    /// All of the TLVs, as `(type, value)` pairs, in the order received.
+    pub fn tlvs(&self) -> impl Iterator<Item = (u8, &[u8])> {
+        self.tlvs.iter().map(|(k, v)| (*k, &v[..]))
+    }
+
+    /// The host name the client requested, typically the TLS SNI, if the
+    /// upstream conveyed a valid UTF-8 one.
+    pub fn authority(&self) -> Option<&str> {
+        self.tlv(Self::AUTHORITY).and_then(|v| std::str::from_utf8(v).ok())
+    }
+
+    /// The ALPN protocol negotiated by the upstream, if it conveyed one.
+    pub fn alpn(&self) -> Option<&[u8]> {
+        self.tlv(Self::ALPN)
+    }
+
+    /// Attempts to parse a header from the start of `buf`. Returns the header
+    /// and its length in bytes if `buf` contains a complete, valid header and
+    /// `None` if more bytes are needed.
+    fn parse(buf: &[u8]) -> io::Result<Option<(ProxyHeader, usize)>> {
+        if buf.len() >= V2_SIGNATURE.len() && buf.starts_with(V2_SIGNATURE) {
+            return Self::parse_v2(buf);
+        } else if V2_SIGNATURE.starts_with(buf) && !buf.is_empty() {
+            return Ok(None);
+        } else if buf.starts_with(b"PROXY ") {
+            return Self::parse_v1(buf);
+        } else if b"PROXY ".starts_with(buf) {
+            return Ok(None);
+        }
+
+        Err(invalid("missing PROXY protocol header"))
+    }
+
+    fn parse_v1(buf: &[u8]) -> io::Result<Option<(ProxyHeader, usize)>> {
+        let end = match buf.windows(2).take(V1_MAX_LEN - 1).position(|w| w == b"\r\n") {
+            Some(i) => i,
+            None if buf.len() < V1_MAX_LEN => return Ok(None),
+            None => return Err(invalid("PROXY v1 header is too long")),
+        };
+
+        let line = std::str::from_utf8(&buf[..end])
+            .map_err(|_| invalid("PROXY v1 header is not valid UTF-8"))?;
+
+        let parts: Vec<&str> = line.split(' ').collect();
+        let (source, destination) = match parts[..] {
+            ["PROXY", "UNKNOWN", ..] => (None, None),
+            ["PROXY", family @ ("TCP4" | "TCP6"), src, dst, sport, dport] => {
+                let ip = |s: &str| -> io::Result<IpAddr> {
+                    let ip: IpAddr = s.parse().map_err(|_| invalid("bad PROXY v1 address"))?;
+                    match (family, ip) {
+                        ("TCP4", IpAddr::V4(_)) | ("TCP6", IpAddr::V6(_)) => Ok(ip),
+                        _ => Err(invalid("PROXY v1 address doesn't match family")),
+                    }
+                };
+
+                let port = |s: &str| s.parse::<u16>().map_err(|_| invalid("bad PROXY v1 port"));
+                let source = SocketAddr::new(ip(src)?, port(sport)?);
+                let destination = SocketAddr::new(ip(dst)?, port(dport)?);
+                (Some(source), Some(destination))
+            }
+            _ => return Err(invalid("malformed PROXY v1 header")),
+        };
+
+        let header = ProxyHeader { version: 1, source, destination, tlvs: vec![] };
+        Ok(Some((header, end + 2)))
+    }
+
+    fn parse_v2(buf: &[u8]) -> io::Result<Option<(ProxyHeader, usize)>> {
+        if buf.len() < 16 {
+            return Ok(None);
+        }
+
+        let (ver_cmd, family) = (buf[12], buf[13]);
+        let len = u16::from_be_bytes([buf[14], buf[15]]) as usize;
+        if ver_cmd >> 4 != 2 {
+            return Err(invalid("unsupported PROXY protocol version"));
+        }
+
+        if buf.len() < 16 + len {
+            return Ok(None);
+        }
+
+        let payload = &buf[16..16 + len];
+        let mut header = ProxyHeader { version: 2, source: None, destination: None, tlvs: vec![] };
+        let addrs_len = match family >> 4 {
+            0x1 => 12,
+            0x2 => 36,
+            0x3 => 216,
+            _ => 0,
+        };
+
+        if payload.len() < addrs_len {
+            return Err(invalid("truncated PROXY v2 addresses"));
+        }
+
+        match ver_cmd & 0xF {
+            // LOCAL: the upstream is speaking for itself; there's no client.
+            0x0 => {},
+            0x1 => {
+                let port = |i: usize| u16::from_be_bytes([payload[i], payload[i + 1]]);
+                match family >> 4 {
+                    0x1 => {
+                        let ip = |i: usize| {
+                            let mut octets = [0u8; 4];
+                            octets.copy_from_slice(&payload[i..i + 4]);
+                            Ipv4Addr::from(octets)
+                        };
+
+                        header.source = Some(SocketAddr::new(ip(0).into(), port(8)));
+                        header.destination = Some(SocketAddr::new(ip(4).into(), port(10)));
+                    }
+                    0x2 => {
+                        let ip = |i: usize| {
+                            let mut octets = [0u8; 16];
+                            octets.copy_from_slice(&payload[i..i + 16]);
+                            Ipv6Addr::from(octets)
+                        };
+
+                        header.source = Some(SocketAddr::new(ip(0).into(), port(32)));
+                        header.destination = Some(SocketAddr::new(ip(16).into(), port(34)));
+                    }
+                    _ => {}
+                }
+            }
+            _ => return Err(invalid("unsupported PROXY v2 command")),
+        }
+
+        let mut tlvs = &payload[addrs_len..];
+        while !tlvs.is_empty() {
+            if tlvs.len() < 3 {
+                return Err(invalid("truncated PROXY v2 TLV"));
+            }
+
+            let (kind, len) = (tlvs[0], u16::from_be_bytes([tlvs[1], tlvs[2]]) as usize);
+            let value = tlvs.get(3..3 + len).ok_or_else(|| invalid("truncated PROXY v2 TLV"))?;
+            header.tlvs.push((kind, value.to_vec()));
+            tlvs = &tlvs[3 + len..];
+        }
+
+        Ok(Some((header, 16 + len)))
+    }
+}
+
+fn invalid(msg: &'static str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+type Pending<C> = Pin<Box<dyn Future<Output = io::Result<ProxyStream<C>>> + Send>>;
+
+/// A listener that reads PROXY protocol headers from trusted upstreams.
+///
+/// Connections from addresses in one of the trusted ranges _must_ begin with
+/// a valid PROXY protocol v1 or v2 header; connections that don't, or that
+/// don't send a complete header within the [header
+/// timeout](ProxyListener::header_timeout()), 10 seconds by default, are
+/// closed. The header's source address, if it has one, becomes the
+/// connection's remote address. Connections from any other address are served
+/// as they are, without reading a header.
+///
+/// Headers are read concurrently, so a slow upstream doesn't block accepting
+/// other connections.
+pub struct ProxyListener<L: Listener> {
+    listener: L,
+    trusted: Arc<[IpRange]>,
+    timeout: Option<Duration>,
+    pending: Vec<Pending<L::Connection>>,
+}
+
+impl<L: Listener> ProxyListener<L> {
+    /// Wraps `listener`, reading PROXY headers from connections whose peer
+    /// address is in one of the `trusted` ranges.
+    pub fn new<T: Into<Arc<[IpRange]>>>(listener: L, trusted: T) -> Self {
+        ProxyListener {
+            listener,
+            trusted: trusted.into(),
+            timeout: Some(DEFAULT_HEADER_TIMEOUT),
+            pending: vec![],
+        }
+    }
+
+    /// Sets how long a trusted upstream has to send a complete header. When
+    /// `None`, there's no time limit.
+    pub fn header_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.timeout = timeout;
+        self
+    }
+}
+
+impl<L> Listener for ProxyListener<L>
+    where L: Listener + Unpin, <L as Listener>::Connection: Send + Unpin + 'static
+{
+    type Connection = ProxyStream<L::Connection>;
+
+    fn local_addr(&self) -> Option<SocketAddr> {
+        self.listener.local_addr()
+    }
+
+    fn poll_accept(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>
+    ) -> Poll<io::Result<Self::Connection>> {
+        let this = self.get_mut();
+        loop {
+            match Pin::new(&mut this.listener).poll_accept(cx) {
+                Poll::Ready(Ok(io)) => {
+                    let trusted = io.peer_address()
+                        .map_or(false, |a| this.trusted.iter().any(|r| r.contains(a.ip())));
+
+                    if !trusted {
+                        let stream = ProxyStream { io, header: None, buffer: vec![], pos: 0 };
+                        return Poll::Ready(Ok(stream));
+                    }
+
+                    this.pending.push(Box::pin(ProxyStream::read_header(io, this.timeout)));
+                }
+                Poll::Ready(Err(e)) => return Poll::Ready(Err(e)),
+                Poll::Pending => break,
+            }
+        }
+
+        let mut i = 0;
+        while i < this.pending.len() {
+            match this.pending[i].as_mut().poll(cx) {
+                Poll::Ready(result) => {
+                    drop(this.pending.swap_remove(i));
+                    match result {
+                        Ok(stream) => return Poll::Ready(Ok(stream)),
+                        Err(e) => log::warn!("dropping PROXY protocol connection: {}", e),
+                    }
+                }
+                Poll::Pending => i += 1,
+            }
+        }
+
+        Poll::Pending
+    }
+}
+
+/// A connection whose remote address may have been conveyed by a PROXY
+/// protocol header.
+pub struct ProxyStream<C> {
+    io: C,
+    header: Option<Arc<ProxyHeader>>,
+    /// Bytes read past the header, yielded before reading from `io` again.
+    buffer: Vec<u8>,
+    pos: usize,
+}
+
+impl<C: AsyncRead + Unpin> ProxyStream<C> {
+    async fn read_header(mut io: C, timeout: Option<Duration>) -> io::Result<ProxyStream<C>> {
+        let read = async {
+            let mut buffer = Vec::with_capacity(V1_MAX_LEN);
+            loop {
+                if let Some((header, len)) = ProxyHeader::parse(&buffer)? {
+                    return Ok((header, buffer, len));
+                }
+
+                if io.read_buf(&mut buffer).await? == 0 {
+                    return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "no PROXY header"));
+                }
+            }
+        };
+
+        let (header, buffer, len) = match timeout {
+            Some(timeout) => tokio::time::timeout(timeout, read).await
+                .map_err(|_| io::Error::new(io::ErrorKind::TimedOut, "PROXY header timed out"))??,
+            None => read.await?,
+        };
+
+        Ok(ProxyStream { io, header: Some(Arc::new(header)), buffer, pos: len })
+    }
+}
+
+impl<C: Connection + Unpin> Connection for ProxyStream<C> {
+    fn peer_address(&self) -> Option<SocketAddr> {
+        // Per the spec, when no client address was conveyed, as is the case
+        // for `LOCAL` and `UNKNOWN`, the real connection's address is used.
+        self.header.as_ref()
+            .and_then(|header| header.source())
+            .or_else(|| self.io.peer_address())
+    }
+
+    fn enable_nodelay(&self) -> io::Result<()> {
+        self.io.enable_nodelay()
+    }
+
+    fn peer_certificates(&self) -> Option<Certificates> {
+        self.io.peer_certificates()
+    }
+
+    fn peer_credentials(&self) -> Option<PeerCredentials> {
+        self.io.peer_credentials()
+    }
+
+    fn proxy_header(&self) -> Option<Arc<ProxyHeader>> {
+        self.header.clone()
+    }
+
+    fn tls_info(&self) -> Option<TlsInfo> {
+        self.io.tls_info()
+    }
+
+    fn poll_handshake(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.io).poll_handshake(cx)
+    }
+}
+
+impl<C: AsyncRead + Unpin> AsyncRead for ProxyStream<C> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        if this.pos < this.buffer.len() {
+            let n = std::cmp::min(buf.remaining(), this.buffer.len() - this.pos);
+            buf.put_slice(&this.buffer[this.pos..this.pos + n]);
+            this.pos += n;
+            if this.pos == this.buffer.len() {
+                this.buffer = vec![];
+                this.pos = 0;
+            }
+
+            return Poll::Ready(Ok(()));
+        }
+
+        Pin::new(&mut this.io).poll_read(cx, buf)
+    }
+}
+
+impl<C: AsyncWrite + Unpin> AsyncWrite for ProxyStream<C> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.get_mut().io).poll_write(cx, buf)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().io).poll_flush(cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().io).poll_shutdown(cx)
+    }
+
+    fn poll_write_vectored(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[io::IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.get_mut().io).poll_write_vectored(cx, bufs)
+    }
+
+    fn is_write_vectored(&self) -> bool {
+        self.io.is_write_vectored()
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serde {
+    use std::fmt;
+    use super::*;
+
+    use serde_::ser::{Serialize, Serializer};
+    use serde_::de::{Deserialize, Deserializer, Error, Visitor, Unexpected};
+
+    impl Serialize for IpRange {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.collect_str(self)
+        }
+    }
+
+    struct DeVisitor;
+
+    impl<'de> Visitor<'de> for DeVisitor {
+        type Value = IpRange;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(formatter, "IP address or CIDR range string")
+        }
+
+        fn visit_str<E: Error>(self, v: &str) -> Result<Self::Value, E> {
+            IpRange::from_str(v).map_err(|_| E::invalid_value(Unexpected::Str(v), &self))
+        }
+    }
+
+    impl<'de> Deserialize<'de> for IpRange {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            deserializer.deserialize_str(DeVisitor)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(bytes: &[u8]) -> ProxyHeader {
+        let (header, len) = ProxyHeader::parse(bytes).unwrap().expect("complete header");
+        assert_eq!(len, bytes.len());
+        header
+    }
+
+    #[test]
+    fn test_ip_range() {
+        let range: IpRange = "192.168.0.0/16".parse().unwrap();
+        assert!(range.contains("192.168.255.1".parse().unwrap()));
+        assert!(range.contains("::ffff:192.168.0.1".parse().unwrap()));
+        assert!(!range.contains("192.169.0.1".parse().unwrap()));
+        assert!(!range.contains("::1".parse().unwrap()));
+
+        let all: IpRange = "0.0.0.0/0".parse().unwrap();
+        assert!(all.contains("8.8.8.8".parse().unwrap()));
+
+        let v6: IpRange = "fd00::/8".parse().unwrap();
+        assert!(v6.contains("fd12:3456::1".parse().unwrap()));
+        assert!(!v6.contains("fe80::1".parse().unwrap()));
+
+        assert!("10.0.0.0/33".parse::<IpRange>().is_err());
+        assert!("10.0.0/8".parse::<IpRange>().is_err());
+        assert!("::/129".parse::<IpRange>().is_err());
+    }
+
+    #[test]
+    fn test_parse_v1() {
+        let header = parse(b"PROXY TCP4 192.0.2.1 198.51.100.2 56324 443\r\n");
+        assert_eq!(header.version(), 1);
+        assert_eq!(header.source(), Some("192.0.2.1:56324".parse().unwrap()));
+        assert_eq!(header.destination(), Some("198.51.100.2:443".parse().unwrap()));
+
+        let header = parse(b"PROXY TCP6 2001:db8::1 2001:db8::2 1000 80\r\n");
+        assert_eq!(header.source(), Some("[2001:db8::1]:1000".parse().unwrap()));
+
+        let header = parse(b"PROXY UNKNOWN ffff::1 ffff::2 1 2\r\n");
+        assert_eq!(header.source(), None);
+
+        assert!(ProxyHeader::parse(b"PROXY TCP4 192.0.2.1").unwrap().is_none());
+        assert!(ProxyHeader::parse(b"PRO").unwrap().is_none());
+        assert!(ProxyHeader::parse(b"PROXY TCP4 ::1 ::2 1 2\r\n").is_err());
+        assert!(ProxyHeader::parse(b"PROXY TCP4 1.2.3.4 1.2.3.4 1\r\n").is_err());
+        assert!(ProxyHeader::parse(b"GET / HTTP/1.1\r\n").is_err());
+        assert!(ProxyHeader::parse(&[b'A'; 200]).is_err());
+        assert!(ProxyHeader::parse(&[b"PROXY ".as_slice(), &[b'A'; 200]].concat()).is_err());
+    }
+
+    #[test]
+    fn test_parse_v2() {
+        let mut bytes = V2_SIGNATURE.to_vec();
+        bytes.extend_from_slice(&[0x21, 0x11]);
+        let tlvs = [&[0x02, 0x00, 0x0b][..], b"example.com", &[0x01, 0x00, 0x02], b"h2"].concat();
+        bytes.extend_from_slice(&((12 + tlvs.len()) as u16).to_be_bytes());
+        bytes.extend_from_slice(&[203, 0, 113, 7, 10, 0, 0, 1]);
+        bytes.extend_from_slice(&[0xc3, 0x50, 0x01, 0xbb]);
+        bytes.extend_from_slice(&tlvs);
+
+        let header = parse(&bytes);
+        assert_eq!(header.version(), 2);
+        assert_eq!(header.source(), Some("203.0.113.7:50000".parse().unwrap()));
+        assert_eq!(header.destination(), Some("10.0.0.1:443".parse().unwrap()));
+        assert_eq!(header.authority(), Some("example.com"));
+        assert_eq!(header.alpn(), Some(&b"h2"[..]));
+        assert_eq!(header.tlvs().count(), 2);
+
+        for i in 0..bytes.len() {
+            assert!(ProxyHeader::parse(&bytes[..i]).unwrap().is_none());
+        }
+
+        // A `LOCAL` command, as sent by health checks, conveys no client.
+        let mut local = V2_SIGNATURE.to_vec();
+        local.extend_from_slice(&[0x20, 0x00, 0x00, 0x00]);
+        assert_eq!(parse(&local).source(), None);
+
+        let mut bad = bytes.clone();
+        bad[12] = 0x31;
+        assert!(ProxyHeader::parse(&bad).is_err());
+    }
+}
@@ -9,12 +9,18 @@ use tokio::net::{TcpListener, TcpStream};
 use tokio::io::{AsyncRead, AsyncWrite};
 use tokio_rustls::{Accept, TlsAcceptor, server::TlsStream as BareTlsStream};
 
-use crate::tls::util::{load_certs, load_private_key, load_ca_certs};
-use crate::listener::{Connection, Listener, Certificates};
+use crate::tls::util::load_ca_certs;
+use crate::tls::CertResolver;
+use crate::listener::{Connection, Listener, Certificates, TlsInfo};
+use crate::proxy::ProxyHeader;
 
-/// A TLS listener over TCP.
-pub struct TlsListener {
-    listener: TcpListener,
+/// A TLS listener over another listener, TCP by default.
+///
+/// The wrapped listener's connections carry the TLS session. Wrapping a
+/// `ProxyListener`, for instance, reads the PROXY protocol headers sent by
+/// TLS-passthrough load balancers ahead of the TLS handshake.
+pub struct TlsListener<L = TcpListener> {
+    listener: L,
     acceptor: TlsAcceptor,
 }
 
@@ -47,24 +53,23 @@ pub struct TlsListener {
 /// certificate data when we have a `Request` object, which implies we're
 /// receiving payload data, which implies the TLS handshake has finished, so the
 /// certificate data as seen by a Rocket application will always be "fresh".
-pub struct TlsStream {
-    remote: SocketAddr,
-    state: TlsState,
+pub struct TlsStream<C = TcpStream> {
+    remote: Option<SocketAddr>,
+    state: TlsState<C>,
     certs: Certificates,
 }
 
 /// State of `TlsStream`.
-pub enum TlsState {
+pub enum TlsState<C = TcpStream> {
     /// The TLS handshake is taking place. We don't have a full connection yet.
-    Handshaking(Accept<TcpStream>),
+    Handshaking(Accept<C>),
     /// TLS handshake completed successfully; we're getting payload data.
-    Streaming(BareTlsStream<TcpStream>),
+    Streaming(BareTlsStream<C>),
 }
 
 /// TLS as ~configured by `TlsConfig` in `rocket` core.
 pub struct Config<R> {
-    pub cert_chain: R,
-    pub private_key: R,
+    pub resolver: Arc<CertResolver>,
     pub ciphersuites: Vec<rustls::SupportedCipherSuite>,
     pub prefer_server_order: bool,
     pub ca_certs: Option<R>,
@@ -72,17 +77,27 @@ pub struct Config<R> {
 }
 
 impl TlsListener {
-    pub async fn bind<R>(addr: SocketAddr, mut c: Config<R>) -> io::Result<TlsListener>
+    pub async fn bind<R>(addr: SocketAddr, c: Config<R>) -> io::Result<TlsListener>
         where R: io::BufRead
     {
-        use rustls::server::{AllowAnyAuthenticatedClient, AllowAnyAnonymousOrAuthenticatedClient};
-        use rustls::server::{NoClientAuth, ServerSessionMemoryCache, ServerConfig};
+        let listener = TcpListener::bind(addr).await?;
+        TlsListener::from_listener(listener, c)
+    }
+}
 
-        let cert_chain = load_certs(&mut c.cert_chain)
-            .map_err(|e| io::Error::new(e.kind(), format!("bad TLS cert chain: {}", e)))?;
+impl<L> TlsListener<L> {
+    /// Serves TLS on the connections accepted by `listener`.
+    pub fn from_listener<R>(listener: L, c: Config<R>) -> io::Result<TlsListener<L>>
+        where R: io::BufRead
+    {
+        Ok(TlsListener { listener, acceptor: Self::acceptor(c)? })
+    }
 
-        let key = load_private_key(&mut c.private_key)
-            .map_err(|e| io::Error::new(e.kind(), format!("bad TLS private key: {}", e)))?;
+    fn acceptor<R>(mut c: Config<R>) -> io::Result<TlsAcceptor>
+        where R: io::BufRead
+    {
+        use rustls::server::{AllowAnyAuthenticatedClient, AllowAnyAnonymousOrAuthenticatedClient};
+        use rustls::server::{NoClientAuth, ServerSessionMemoryCache, ServerConfig};
 
         let client_auth = match c.ca_certs {
             Some(ref mut ca_certs) => match load_ca_certs(ca_certs) {
@@ -99,8 +114,7 @@ impl TlsListener {
             .with_safe_default_protocol_versions()
             .map_err(|e| io::Error::new(io::ErrorKind::Other, format!("bad TLS config: {}", e)))?
             .with_client_cert_verifier(client_auth)
-            .with_single_cert(cert_chain, key)
-            .map_err(|e| io::Error::new(io::ErrorKind::Other, format!("bad TLS config: {}", e)))?;
+            .with_cert_resolver(c.resolver);
 
         tls_config.ignore_client_order = c.prefer_server_order;
 
@@ -113,27 +127,28 @@ impl TlsListener {
         tls_config.ticketer = rustls::Ticketer::new()
             .map_err(|e| io::Error::new(io::ErrorKind::Other, format!("bad TLS ticketer: {}", e)))?;
 
-        let listener = TcpListener::bind(addr).await?;
-        let acceptor = TlsAcceptor::from(Arc::new(tls_config));
-        Ok(TlsListener { listener, acceptor })
+        Ok(TlsAcceptor::from(Arc::new(tls_config)))
     }
 }
 
-impl Listener for TlsListener {
-    type Connection = TlsStream;
+impl<L> Listener for TlsListener<L>
+    where L: Listener + Unpin, L::Connection: Unpin
+{
+    type Connection = TlsStream<L::Connection>;
 
     fn local_addr(&self) -> Option<SocketAddr> {
-        self.listener.local_addr().ok()
+        self.listener.local_addr()
     }
 
     fn poll_accept(
         self: Pin<&mut Self>,
         cx: &mut Context<'_>
     ) -> Poll<io::Result<Self::Connection>> {
-        match futures::ready!(self.listener.poll_accept(cx)) {
-            Ok((io, addr)) => Poll::Ready(Ok(TlsStream {
-                remote: addr,
-                state: TlsState::Handshaking(self.acceptor.accept(io)),
+        let this = self.get_mut();
+        match futures::ready!(Pin::new(&mut this.listener).poll_accept(cx)) {
+            Ok(io) => Poll::Ready(Ok(TlsStream {
+                remote: io.peer_address(),
+                state: TlsState::Handshaking(this.acceptor.accept(io)),
                 // These are empty and filled in after handshake is complete.
                 certs: Certificates::default(),
             })),
@@ -142,9 +157,9 @@ impl Listener for TlsListener {
     }
 }
 
-impl Connection for TlsStream {
+impl<C: Connection + Unpin> Connection for TlsStream<C> {
     fn peer_address(&self) -> Option<SocketAddr> {
-        Some(self.remote)
+        self.remote
     }
 
     fn enable_nodelay(&self) -> io::Result<()> {
@@ -164,15 +179,39 @@ impl Connection for TlsStream {
     fn peer_certificates(&self) -> Option<Certificates> {
         Some(self.certs.clone())
     }
+
+    fn proxy_header(&self) -> Option<Arc<ProxyHeader>> {
+        match &self.state {
+            TlsState::Handshaking(accept) => accept.get_ref().and_then(|io| io.proxy_header()),
+            TlsState::Streaming(stream) => stream.get_ref().0.proxy_header(),
+        }
+    }
+
+    fn tls_info(&self) -> Option<TlsInfo> {
+        let conn = match &self.state {
+            TlsState::Streaming(stream) => stream.get_ref().1,
+            TlsState::Handshaking(_) => return None,
+        };
+
+        let info = TlsInfo::default()
+            .with_server_name(conn.server_name().map(|name| name.to_string()))
+            .with_alpn_protocol(conn.alpn_protocol().map(|proto| proto.to_vec()));
+
+        Some(info)
+    }
+
+    fn poll_handshake(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.poll_accept_then(cx, |_, _| Poll::Ready(Ok(())))
+    }
 }
 
-impl TlsStream {
+impl<C: AsyncRead + AsyncWrite + Unpin> TlsStream<C> {
     fn poll_accept_then<F, T>(
         mut self: Pin<&mut Self>,
         cx: &mut Context<'_>,
         mut f: F
     ) -> Poll<io::Result<T>>
-        where F: FnMut(&mut BareTlsStream<TcpStream>, &mut Context<'_>) -> Poll<io::Result<T>>
+        where F: FnMut(&mut BareTlsStream<C>, &mut Context<'_>) -> Poll<io::Result<T>>
     {
         loop {
             match self.state {
@@ -186,7 +225,11 @@ impl TlsStream {
                             self.state = TlsState::Streaming(stream);
                         }
                         Err(e) => {
-                            log::warn!("tls handshake with {} failed: {}", self.remote, e);
+                            match self.remote {
+                                Some(remote) => log::warn!("tls handshake with {} failed: {}", remote, e),
+                                None => log::warn!("tls handshake failed: {}", e),
+                            }
+
                             return Poll::Ready(Err(e));
                         }
                     }
@@ -197,7 +240,7 @@ impl TlsStream {
     }
 }
 
-impl AsyncRead for TlsStream {
+impl<C: AsyncRead + AsyncWrite + Unpin> AsyncRead for TlsStream<C> {
     fn poll_read(
         self: Pin<&mut Self>,
         cx: &mut Context<'_>,
@@ -207,7 +250,7 @@ impl AsyncRead for TlsStream {
     }
 }
 
-impl AsyncWrite for TlsStream {
+impl<C: AsyncRead + AsyncWrite + Unpin> AsyncWrite for TlsStream<C> {
     fn poll_write(
         self: Pin<&mut Self>,
         cx: &mut Context<'_>,
@@ -1,8 +1,10 @@
 mod listener;
+mod resolver;
 
 #[cfg(feature = "mtls")]
 pub mod mtls;
 
 pub use rustls;
 pub use listener::{TlsListener, Config};
+pub use resolver::CertResolver;
 pub mod util;
@@ -0,0 +1,212 @@
+use std::io;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+use rustls::Certificate;
+use rustls::server::{ClientHello, ResolvesServerCert};
+use rustls::sign::{any_supported_type, CertifiedKey, SigningKey};
+
+use crate::tls::util::{load_certs, load_private_key};
+
+/// Selects a certificate for each TLS handshake by the client's SNI.
+///
+/// A resolver holds a default certificate and any number of certificates for
+/// specific server names. Names are matched case-insensitively, first exactly,
+/// then against wildcard names of the form `*.example.com`. Handshakes that
+/// don't send a server name, or send one without a match, are served the
+/// default certificate.
+///
+/// The certificates can be replaced at any time with [`CertResolver::set()`].
+/// Handshakes that begin after the call use the new certificates; established
+/// connections are unaffected.
+pub struct CertResolver {
+    certs: RwLock<Arc<Certs>>,
+}
+
+struct Certs {
+    default: Arc<CertifiedKey>,
+    sni: HashMap<String, Arc<CertifiedKey>>,
+}
+
+impl CertResolver {
+    /// Creates a resolver that serves the certificate paired with a matching
+    /// server name in `sni` or `default` otherwise.
+    pub fn new<I>(default: CertifiedKey, sni: I) -> Self
+        where I: IntoIterator<Item = (String, CertifiedKey)>
+    {
+        CertResolver { certs: RwLock::new(Certs::new(default, sni)) }
+    }
+
+    /// Loads a certificate chain and private key from PEM-encoded readers.
+    pub fn load<C, K>(mut cert_chain: C, mut private_key: K) -> io::Result<CertifiedKey>
+        where C: io::BufRead, K: io::BufRead
+    {
+        let cert_chain = load_certs(&mut cert_chain)
+            .map_err(|e| io::Error::new(e.kind(), format!("bad TLS cert chain: {}", e)))?;
+
+        let key = load_private_key(&mut private_key)
+            .and_then(|key| any_supported_type(&key).map_err(|e| {
+                io::Error::new(io::ErrorKind::Other, e.to_string())
+            }))
+            .map_err(|e| io::Error::new(e.kind(), format!("bad TLS private key: {}", e)))?;
+
+        check_key_matches(&cert_chain, &*key)
+            .map_err(|e| io::Error::new(e.kind(), format!("bad TLS cert/key pair: {}", e)))?;
+
+        Ok(CertifiedKey::new(cert_chain, key))
+    }
+
+    /// Replaces all of the certificates in `self` with `default` and the
+    /// server name to certificate pairs in `sni`.
+    pub fn set<I>(&self, default: CertifiedKey, sni: I)
+        where I: IntoIterator<Item = (String, CertifiedKey)>
+    {
+        let certs = Certs::new(default, sni);
+        *self.certs.write().unwrap_or_else(|e| e.into_inner()) = certs;
+    }
+
+    fn certs(&self) -> Arc<Certs> {
+        self.certs.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    fn resolve_name(&self, name: Option<&str>) -> Arc<CertifiedKey> {
+        let certs = self.certs();
+        let name = match name {
+            Some(name) => name.to_ascii_lowercase(),
+            None => return certs.default.clone(),
+        };
+
+        let wildcard = name.split_once('.').map(|(_, parent)| format!("*.{}", parent));
+        certs.sni.get(&name)
+            .or_else(|| wildcard.and_then(|w| certs.sni.get(&w)))
+            .unwrap_or(&certs.default)
+            .clone()
+    }
+}
+
+impl Certs {
+    fn new<I>(default: CertifiedKey, sni: I) -> Arc<Self>
+        where I: IntoIterator<Item = (String, CertifiedKey)>
+    {
+        let sni = sni.into_iter()
+            .map(|(name, key)| (name.to_ascii_lowercase(), Arc::new(key)))
+            .collect();
+
+        Arc::new(Certs { default: Arc::new(default), sni })
+    }
+}
+
+/// Checks that `key` is the private key of the leaf certificate in `chain` by
+/// signing a message with `key` and verifying the signature with the leaf.
+fn check_key_matches(chain: &[Certificate], key: &dyn SigningKey) -> io::Result<()> {
+    use rustls::SignatureScheme::*;
+
+    const MESSAGE: &[u8] = b"rocket: TLS certificate and key check";
+
+    let err = |msg: &str| io::Error::new(io::ErrorKind::InvalidData, msg.to_string());
+    let leaf = chain.first().ok_or_else(|| err("empty certificate chain"))?;
+    let leaf = webpki::EndEntityCert::try_from(leaf.0.as_slice())
+        .map_err(|e| err(&format!("invalid leaf certificate: {:?}", e)))?;
+
+    let signer = key.choose_scheme(&[
+            RSA_PSS_SHA256, ECDSA_NISTP256_SHA256, ECDSA_NISTP384_SHA384, ED25519
+        ])
+        .ok_or_else(|| err("unsupported private key type"))?;
+
+    let algorithm = match signer.scheme() {
+        RSA_PSS_SHA256 => &webpki::RSA_PSS_2048_8192_SHA256_LEGACY_KEY,
+        ECDSA_NISTP256_SHA256 => &webpki::ECDSA_P256_SHA256,
+        ECDSA_NISTP384_SHA384 => &webpki::ECDSA_P384_SHA384,
+        _ => &webpki::ED25519,
+    };
+
+    let signature = signer.sign(MESSAGE).map_err(|e| err(&e.to_string()))?;
+    leaf.verify_signature(algorithm, MESSAGE, &signature)
+        .map_err(|_| err("private key does not match the leaf certificate"))
+}
+
+impl ResolvesServerCert for CertResolver {
+    fn resolve(&self, client_hello: ClientHello<'_>) -> Option<Arc<CertifiedKey>> {
+        Some(self.resolve_name(client_hello.server_name()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    fn load(cert: &str, key: &str) -> CertifiedKey {
+        let dir = concat!(env!("CARGO_MANIFEST_DIR"), "/../../examples/tls/private/");
+        let cert_chain = std::fs::read(format!("{}{}", dir, cert)).unwrap();
+        let private_key = std::fs::read(format!("{}{}", dir, key)).unwrap();
+        CertResolver::load(Cursor::new(cert_chain), Cursor::new(private_key)).unwrap()
+    }
+
+    fn rsa() -> CertifiedKey {
+        load("rsa_sha256_cert.pem", "rsa_sha256_key.pem")
+    }
+
+    fn ecdsa() -> CertifiedKey {
+        load("ecdsa_nistp256_sha256_cert.pem", "ecdsa_nistp256_sha256_key_pkcs8.pem")
+    }
+
+    fn ed25519() -> CertifiedKey {
+        load("ed25519_cert.pem", "ed25519_key.pem")
+    }
+
+    #[test]
+    fn test_resolve_by_name() {
+        let resolver = CertResolver::new(rsa(), vec![]);
+        let resolve = |name| resolver.resolve_name(name).cert.clone();
+        assert_eq!(resolve(None), rsa().cert);
+        assert_eq!(resolve(Some("example.com")), rsa().cert);
+
+        resolver.set(rsa(), vec![
+            ("Example.COM".into(), ecdsa()),
+            ("*.example.com".into(), ed25519()),
+        ]);
+
+        assert_eq!(resolve(None), rsa().cert);
+        assert_eq!(resolve(Some("example.com")), ecdsa().cert);
+        assert_eq!(resolve(Some("EXAMPLE.com")), ecdsa().cert);
+        assert_eq!(resolve(Some("api.example.com")), ed25519().cert);
+        assert_eq!(resolve(Some("a.b.example.com")), rsa().cert);
+        assert_eq!(resolve(Some("example.org")), rsa().cert);
+    }
+
+    #[test]
+    fn test_load_rejects_bad_pairs() {
+        let dir = concat!(env!("CARGO_MANIFEST_DIR"), "/../../examples/tls/private/");
+        let cert_chain = std::fs::read(format!("{}rsa_sha256_cert.pem", dir)).unwrap();
+
+        let key = Cursor::new(cert_chain.clone());
+        assert!(CertResolver::load(Cursor::new(cert_chain.clone()), key).is_err());
+        assert!(CertResolver::load(Cursor::new(vec![]), Cursor::new(cert_chain)).is_err());
+    }
+
+    #[test]
+    fn test_load_rejects_mismatched_pairs() {
+        let dir = concat!(env!("CARGO_MANIFEST_DIR"), "/../../examples/tls/private/");
+        let read = |file: &str| Cursor::new(std::fs::read(format!("{}{}", dir, file)).unwrap());
+
+        let pairs = [
+            ("rsa_sha256_cert.pem", "ecdsa_nistp256_sha256_key_pkcs8.pem"),
+            ("ecdsa_nistp256_sha256_cert.pem", "ecdsa_nistp384_sha384_key_pkcs8.pem"),
+            ("ecdsa_nistp384_sha384_cert.pem", "ed25519_key.pem"),
+            ("ed25519_cert.pem", "rsa_sha256_key.pem"),
+            // Same algorithm, different key.
+            ("rsa_sha256_cert.pem", "ca_key.pem"),
+        ];
+
+        for (cert, key) in pairs {
+            match CertResolver::load(read(cert), read(key)) {
+                Ok(_) => panic!("{} and {} were accepted as a pair", cert, key),
+                Err(e) => assert!(e.to_string().contains("does not match"), "{}", e),
+            }
+        }
+
+        load("ecdsa_nistp384_sha384_cert.pem", "ecdsa_nistp384_sha384_key_sec1.pem");
+    }
+}
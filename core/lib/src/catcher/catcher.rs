@@ -1,10 +1,13 @@
 use std::fmt;
 use std::io::Cursor;
+use std::borrow::Cow;
 
 use crate::http::uri::Path;
-use crate::response::Response;
+use crate::response::{Response, Responder, Problem};
 use crate::request::Request;
-use crate::http::{Status, ContentType, uri};
+use crate::http::{Status, ContentType, RawStr, uri};
+use crate::config::CatcherFormat;
+use crate::response::problem::json_string;
 use crate::catcher::{Handler, BoxFuture};
 
 use yansi::Paint;
@@ -313,93 +316,91 @@ impl fmt::Debug for Catcher {
     }
 }
 
-macro_rules! html_error_template {
-    ($code:expr, $reason:expr, $description:expr) => (
-        concat!(
-r#"<!DOCTYPE html>
+fn html_error(code: u16, reason: &str, description: &str, id: &str) -> String {
+    format!(r#"<!DOCTYPE html>
 <html lang="en">
 <head>
     <meta charset="utf-8">
-    <title>"#, $code, " ", $reason, r#"</title>
+    <title>{code} {reason}</title>
 </head>
 <body align="center">
     <div role="main" align="center">
-        <h1>"#, $code, ": ", $reason, r#"</h1>
-        <p>"#, $description, r#"</p>
+        <h1>{code}: {reason}</h1>
+        <p>{description}</p>
         <hr />
     </div>
     <div role="contentinfo" align="center">
-        <small>Rocket</small>
+        <small>Rocket &middot; Request ID: {id}</small>
     </div>
 </body>
-</html>"#
-        )
-    )
-}
-
-macro_rules! json_error_template {
-    ($code:expr, $reason:expr, $description:expr) => (
-        concat!(
-r#"{
-  "error": {
-    "code": "#, $code, r#",
-    "reason": ""#, $reason, r#"",
-    "description": ""#, $description, r#""
-  }
-}"#
-        )
-    )
+</html>"#, code = code, reason = reason, description = description,
+        id = RawStr::new(id).html_escape())
 }
 
-// This is unfortunate, but the `{`, `}` above make it unusable for `format!`.
-macro_rules! json_error_fmt_template {
-    ($code:expr, $reason:expr, $description:expr) => (
-        concat!(
-r#"{{
+fn json_error(code: u16, reason: &str, description: &str, id: &str) -> String {
+    format!(r#"{{
   "error": {{
-    "code": "#, $code, r#",
-    "reason": ""#, $reason, r#"",
-    "description": ""#, $description, r#""
+    "code": {},
+    "reason": {},
+    "description": {},
+    "request_id": {}
   }}
-}}"#
-        )
-    )
+}}"#, code, json_string(reason), json_string(description), json_string(id))
 }
 
-macro_rules! default_handler_fn {
-    ($($code:expr, $reason:expr, $description:expr),+) => (
-        use std::borrow::Cow;
+fn plain_error(code: u16, reason: &str, description: &str, id: &str) -> String {
+    format!("{} {}: {}\nRequest ID: {}\n", code, reason, description, id)
+}
 
-        pub(crate) fn default_handler<'r>(
-            status: Status,
-            req: &'r Request<'_>
-        ) -> Response<'r> {
-            let preferred = req.accept().map(|a| a.preferred());
-            let (mime, text) = if preferred.map_or(false, |a| a.is_json()) {
-                let json: Cow<'_, str> = match status.code {
-                    $($code => json_error_template!($code, $reason, $description).into(),)*
-                    code => format!(json_error_fmt_template!("{}", "Unknown Error",
-                            "An unknown error has occurred."), code).into()
-                };
+/// Rocket's default error handler: responds with an error document in the
+/// format selected by [`Config::catcher_format`](crate::Config::catcher_format)
+/// that includes the status, its reason, a description, and the request's ID.
+pub(crate) fn default_handler<'r>(status: Status, req: &'r Request<'_>) -> Response<'r> {
+    let config = req.rocket().config();
+    let (reason, description) = describe(status);
+    let format = config.catcher_format.select(req);
+    if format == CatcherFormat::Json && config.problem_details {
+        let problem = match Problem::recorded(req) {
+            Some(problem) if problem.status() == status => problem.clone(),
+            _ => Problem::new(status).with_title(reason).with_detail(description),
+        };
 
-                (ContentType::JSON, json)
-            } else {
-                let html: Cow<'_, str> = match status.code {
-                    $($code => html_error_template!($code, $reason, $description).into(),)*
-                    code => format!(html_error_template!("{}", "Unknown Error",
-                            "An unknown error has occurred."), code, code).into(),
-                };
+        let problem = problem.with_extension("request_id", req.id());
+        if let Ok(response) = problem.respond_to(req) {
+            return response;
+        }
+    }
 
-                (ContentType::HTML, html)
-            };
+    let (content_type, body) = match format {
+        CatcherFormat::Json => {
+            (ContentType::JSON, json_error(status.code, reason, description, req.id()))
+        }
+        CatcherFormat::Plain => {
+            (ContentType::Plain, plain_error(status.code, reason, description, req.id()))
+        }
+        CatcherFormat::Html | CatcherFormat::Auto => {
+            (ContentType::HTML, html_error(status.code, reason, description, req.id()))
+        }
+    };
 
-            let mut r = Response::build().status(status).header(mime).finalize();
-            match text {
-                Cow::Owned(v) => r.set_sized_body(v.len(), Cursor::new(v)),
-                Cow::Borrowed(v) => r.set_sized_body(v.len(), Cursor::new(v)),
-            };
+    Response::build()
+        .status(status)
+        .header(content_type)
+        .sized_body(body.len(), Cursor::new(body))
+        .finalize()
+}
 
-            r
+macro_rules! default_handler_fn {
+    ($($code:expr, $reason:expr, $description:expr),+) => (
+        /// The reason and description of an error with status `status`.
+        fn describe(status: Status) -> (&'static str, &'static str) {
+            match status.code {
+                $($code => ($reason, $description),)*
+                _ => {
+                    let reason = status.reason().unwrap_or("Unknown Error");
+                    (reason, "An unknown error has occurred.")
+                }
+            }
         }
     )
 }
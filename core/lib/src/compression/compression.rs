@@ -0,0 +1,294 @@
+use crate::{Request, Response};
+use crate::data::{ByteUnit, ToByteUnit};
+use crate::fairing::{Fairing, Info, Kind};
+use crate::http::{Header, MediaType, Status};
+use crate::response::{self, Responder};
+use crate::compression::Encoding;
+use crate::compression::encoder::Compressor;
+
+/// A [`Fairing`] that compresses response bodies.
+///
+/// See the [module level docs](crate::compression) for an overview of when
+/// and how responses are compressed.
+///
+/// # Usage
+///
+/// Construct an instance with [`Compression::new()`] or
+/// [`Compression::default()`], optionally configure it, and attach it:
+///
+/// ```rust
+/// use rocket::compression::{Compression, Encoding};
+/// use rocket::data::ToByteUnit;
+/// use rocket::http::MediaType;
+///
+/// // Compress with all encodings, preferring `br`, then `zstd`, then `gzip`,
+/// // then `deflate`, responses of at least 1KiB.
+/// let compression = Compression::new();
+///
+/// // Only compress with `gzip`, even tiny responses, except CSV files.
+/// let compression = Compression::new()
+///     .with_encodings([Encoding::Gzip])
+///     .with_min_size(0.bytes())
+///     .exclude(MediaType::CSV);
+///
+/// rocket::build()
+///     // ...
+///     .attach(compression)
+/// # ;
+/// ```
+///
+/// # Negotiation
+///
+/// The encoding used is the enabled encoding with the highest q-value in the
+/// request's `Accept-Encoding` header. Ties are broken by the order in which
+/// encodings were enabled. Requests without an `Accept-Encoding` header
+/// receive uncompressed responses.
+///
+/// # Headers
+///
+/// A compressed response has its `Content-Encoding` set and any
+/// `Content-Length` removed. A strong `ETag`, which identifies the uncompressed
+/// representation, is made weak. Any `Accept-Ranges` header is removed: range
+/// requests are served from, and offsets refer to, the uncompressed
+/// representation, which is never compressed. Responses to `HEAD` requests
+/// receive the same headers as the equivalent `GET` response.
+pub struct Compression {
+    encodings: Vec<Encoding>,
+    min_size: ByteUnit,
+    excluded: Vec<MediaType>,
+}
+
+impl Default for Compression {
+    fn default() -> Self {
+        Compression::new()
+    }
+}
+
+impl Compression {
+    /// Returns a new `Compression` fairing which compresses responses of at
+    /// least 1KiB with any of [`Encoding::ALL`], in that order of preference.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::compression::Compression;
+    ///
+    /// let compression = Compression::new();
+    /// ```
+    pub fn new() -> Self {
+        Compression {
+            encodings: Encoding::ALL.to_vec(),
+            min_size: 1.kibibytes(),
+            excluded: vec![],
+        }
+    }
+
+    /// Sets the enabled encodings to `encodings`, in decreasing order of
+    /// preference. If `encodings` is empty, no responses are compressed.
+    /// **(default: [`Encoding::ALL`])**
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::compression::{Compression, Encoding};
+    ///
+    /// let compression = Compression::new()
+    ///     .with_encodings([Encoding::Zstd, Encoding::Gzip]);
+    /// ```
+    pub fn with_encodings<I>(mut self, encodings: I) -> Self
+        where I: IntoIterator<Item = Encoding>
+    {
+        self.encodings = vec![];
+        for encoding in encodings {
+            if !self.encodings.contains(&encoding) {
+                self.encodings.push(encoding);
+            }
+        }
+
+        self
+    }
+
+    /// Sets the minimum size of a body to compress. Bodies of a known size
+    /// smaller than `size` are sent uncompressed. Bodies of an unknown size,
+    /// such as streams, are always compressed. **(default: `1KiB`)**
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::compression::Compression;
+    /// use rocket::data::ToByteUnit;
+    ///
+    /// let compression = Compression::new().with_min_size(256.bytes());
+    /// ```
+    pub fn with_min_size(mut self, size: ByteUnit) -> Self {
+        self.min_size = size;
+        self
+    }
+
+    /// Excludes responses with a `Content-Type` of `media_type` from
+    /// compression. Parameters are ignored when matching. Already-compressed
+    /// formats are always excluded.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::compression::Compression;
+    /// use rocket::http::MediaType;
+    ///
+    /// let compression = Compression::new()
+    ///     .exclude(MediaType::CSV)
+    ///     .exclude(MediaType::new("application", "x-ndjson"));
+    /// ```
+    pub fn exclude<M: Into<MediaType>>(mut self, media_type: M) -> Self {
+        self.excluded.push(media_type.into());
+        self
+    }
+
+    /// Returns the enabled encodings in decreasing order of preference.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::compression::{Compression, Encoding};
+    ///
+    /// let compression = Compression::new().with_encodings([Encoding::Gzip]);
+    /// assert_eq!(compression.encodings(), &[Encoding::Gzip]);
+    /// ```
+    pub fn encodings(&self) -> &[Encoding] {
+        &self.encodings
+    }
+
+    /// Returns the minimum size of a body to compress.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::compression::Compression;
+    /// use rocket::data::ToByteUnit;
+    ///
+    /// let compression = Compression::new();
+    /// assert_eq!(compression.min_size(), 1.kibibytes());
+    /// ```
+    pub fn min_size(&self) -> ByteUnit {
+        self.min_size
+    }
+
+    fn is_excluded(&self, media_type: &MediaType) -> bool {
+        let (top, sub) = (media_type.top(), media_type.sub());
+        let precompressed = if top == "image" {
+            sub != "svg+xml"
+        } else if top == "audio" || top == "video" {
+            true
+        } else if top == "font" {
+            sub == "woff" || sub == "woff2"
+        } else if top == "application" {
+            PRECOMPRESSED_APPLICATION_TYPES.iter().any(|s| sub == *s)
+        } else {
+            false
+        };
+
+        precompressed || self.excluded.iter()
+            .any(|m| m.top() == top && m.sub() == sub)
+    }
+}
+
+/// Subtypes of `application` that are already compressed.
+const PRECOMPRESSED_APPLICATION_TYPES: &[&str] = &[
+    "zip", "gzip", "x-gzip", "zstd", "x-bzip2", "x-xz",
+    "x-7z-compressed", "vnd.rar", "x-rar-compressed",
+];
+
+/// Marks whether the route's response opted out of compression.
+struct OptOut(bool);
+
+#[crate::async_trait]
+impl Fairing for Compression {
+    fn info(&self) -> Info {
+        Info {
+            name: "Compression",
+            kind: Kind::Response | Kind::Singleton,
+        }
+    }
+
+    async fn on_response<'r>(&self, req: &'r Request<'_>, res: &mut Response<'r>) {
+        let status = res.status();
+        if status.class().is_informational()
+            || status == Status::NoContent
+            || status == Status::NotModified
+            || status == Status::PartialContent
+            || res.body().is_none()
+            || res.headers().contains("Content-Encoding")
+            || res.headers().contains("Content-Range")
+            || req.local_cache(|| OptOut(false)).0
+        {
+            return;
+        }
+
+        if res.content_type().map_or(false, |ct| self.is_excluded(ct.media_type())) {
+            return;
+        }
+
+        // The representation now depends on `Accept-Encoding`.
+        let varies = res.headers().get("Vary")
+            .flat_map(|v| v.split(','))
+            .map(|v| v.trim())
+            .any(|v| v == "*" || v.eq_ignore_ascii_case("Accept-Encoding"));
+
+        if !varies {
+            res.adjoin_header(Header::new("Vary", "Accept-Encoding"));
+        }
+
+        let accept = req.headers().get("Accept-Encoding").collect::<Vec<_>>().join(",");
+        let encoding = match Encoding::negotiate(&accept, &self.encodings) {
+            Some(encoding) => encoding,
+            None => return,
+        };
+
+        if let Some(size) = res.body_mut().size().await {
+            if size.bytes() < self.min_size {
+                return;
+            }
+        }
+
+        let body = res.body_mut().take();
+        let max_chunk_size = body.max_chunk_size();
+        res.set_streamed_body(Compressor::new(body, encoding));
+        res.body_mut().set_max_chunk_size(max_chunk_size);
+        res.set_header(Header::new("Content-Encoding", encoding.as_str()));
+        res.remove_header("Content-Length");
+        res.remove_header("Accept-Ranges");
+
+        if let Some(etag) = res.headers().get_one("ETag") {
+            if etag.starts_with('"') {
+                let weak = format!("W/{}", etag);
+                res.set_header(Header::new("ETag", weak));
+            }
+        }
+    }
+}
+
+/// A responder that prevents the [`Compression`] fairing from compressing the
+/// wrapped responder's response.
+///
+/// # Example
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// use rocket::compression::Uncompressed;
+///
+/// #[get("/token")]
+/// fn token() -> Uncompressed<String> {
+///     // Responses mixing secrets with attacker-controlled data may be
+///     // vulnerable to BREACH-style attacks when compressed.
+///     Uncompressed("secret".into())
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Uncompressed<R>(pub R);
+
+impl<'r, 'o: 'r, R: Responder<'r, 'o>> Responder<'r, 'o> for Uncompressed<R> {
+    fn respond_to(self, req: &'r Request<'_>) -> response::Result<'o> {
+        req.local_cache(|| OptOut(true));
+        self.0.respond_to(req)
+    }
+}
@@ -0,0 +1,160 @@
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use async_compression::Level;
+use async_compression::tokio::write::{BrotliEncoder, GzipEncoder, ZlibEncoder, ZstdEncoder};
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+use crate::compression::Encoding;
+
+/// Size of the buffer used to read from the uncompressed reader.
+const BUF_SIZE: usize = 8 * 1024;
+
+type Writer = dyn AsyncWrite + Unpin;
+
+enum Codec {
+    Brotli(Box<BrotliEncoder<Vec<u8>>>),
+    Zstd(ZstdEncoder<Vec<u8>>),
+    Gzip(GzipEncoder<Vec<u8>>),
+    Deflate(ZlibEncoder<Vec<u8>>),
+}
+
+macro_rules! with_codec {
+    ($codec:expr, $e:ident => $body:expr) => (
+        match $codec {
+            Codec::Brotli($e) => $body,
+            Codec::Zstd($e) => $body,
+            Codec::Gzip($e) => $body,
+            Codec::Deflate($e) => $body,
+        }
+    )
+}
+
+impl Codec {
+    fn new(encoding: Encoding) -> Codec {
+        match encoding {
+            // Brotli's default quality is far too slow for on-the-fly use.
+            Encoding::Brotli => {
+                let encoder = BrotliEncoder::with_quality(vec![], Level::Precise(4));
+                Codec::Brotli(Box::new(encoder))
+            }
+            Encoding::Zstd => Codec::Zstd(ZstdEncoder::new(vec![])),
+            Encoding::Gzip => Codec::Gzip(GzipEncoder::new(vec![])),
+            // HTTP's `deflate` is the zlib format, not raw deflate.
+            Encoding::Deflate => Codec::Deflate(ZlibEncoder::new(vec![])),
+        }
+    }
+
+    fn output(&mut self) -> &mut Vec<u8> {
+        with_codec!(self, e => e.get_mut())
+    }
+
+    /// Runs `f` against the encoder. The encoder writes into a `Vec`, so it
+    /// never blocks; `Pending` is thus never returned.
+    fn drive<T, F>(&mut self, f: F) -> io::Result<T>
+        where F: FnOnce(Pin<&mut Writer>, &mut Context<'_>) -> Poll<io::Result<T>>
+    {
+        let waker = futures::task::noop_waker();
+        let mut cx = Context::from_waker(&waker);
+        let poll = with_codec!(self, e => f(Pin::new(e as &mut Writer), &mut cx));
+        match poll {
+            Poll::Ready(result) => result,
+            Poll::Pending => Err(io::ErrorKind::WouldBlock.into()),
+        }
+    }
+
+    fn write_all(&mut self, mut buf: &[u8]) -> io::Result<()> {
+        while !buf.is_empty() {
+            let n = self.drive(|e, cx| e.poll_write(cx, buf))?;
+            if n == 0 {
+                return Err(io::ErrorKind::WriteZero.into());
+            }
+
+            buf = &buf[n..];
+        }
+
+        Ok(())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.drive(|e, cx| e.poll_flush(cx))
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        self.drive(|e, cx| e.poll_shutdown(cx))
+    }
+}
+
+/// An `AsyncRead` that compresses the data read from `R`.
+///
+/// Whenever `R` has no data ready, all data read so far is flushed through the
+/// encoder so that the client receives it without waiting for more input.
+pub(crate) struct Compressor<R> {
+    reader: R,
+    codec: Codec,
+    input: Box<[u8]>,
+    output: Vec<u8>,
+    pos: usize,
+    dirty: bool,
+    done: bool,
+}
+
+impl<R: AsyncRead + Unpin> Compressor<R> {
+    pub fn new(reader: R, encoding: Encoding) -> Self {
+        Compressor {
+            reader,
+            codec: Codec::new(encoding),
+            input: vec![0; BUF_SIZE].into_boxed_slice(),
+            output: vec![],
+            pos: 0,
+            dirty: false,
+            done: false,
+        }
+    }
+}
+
+impl<R: AsyncRead + Unpin> AsyncRead for Compressor<R> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>
+    ) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        loop {
+            if this.pos < this.output.len() {
+                let n = std::cmp::min(buf.remaining(), this.output.len() - this.pos);
+                buf.put_slice(&this.output[this.pos..(this.pos + n)]);
+                this.pos += n;
+                return Poll::Ready(Ok(()));
+            }
+
+            if this.done {
+                return Poll::Ready(Ok(()));
+            }
+
+            let mut input = ReadBuf::new(&mut this.input);
+            match Pin::new(&mut this.reader).poll_read(cx, &mut input) {
+                Poll::Ready(Ok(())) if input.filled().is_empty() => {
+                    this.codec.finish()?;
+                    this.done = true;
+                }
+                Poll::Ready(Ok(())) => {
+                    this.codec.write_all(input.filled())?;
+                    this.dirty = true;
+                }
+                Poll::Ready(Err(e)) => return Poll::Ready(Err(e)),
+                Poll::Pending if this.dirty => {
+                    this.codec.flush()?;
+                    this.dirty = false;
+                }
+                Poll::Pending => return Poll::Pending,
+            }
+
+            // Take whatever the encoder has produced, reusing our buffer.
+            this.output.clear();
+            this.pos = 0;
+            std::mem::swap(&mut this.output, this.codec.output());
+        }
+    }
+}
@@ -0,0 +1,105 @@
+use std::fmt;
+
+use crate::http::private::accept_encoding_qvalue;
+
+/// A content encoding supported by the [`Compression`](super::Compression)
+/// fairing.
+///
+/// # Example
+///
+/// ```rust
+/// use rocket::compression::Encoding;
+///
+/// assert_eq!(Encoding::parse("br"), Some(Encoding::Brotli));
+/// assert_eq!(Encoding::parse("X-GZIP"), Some(Encoding::Gzip));
+/// assert_eq!(Encoding::parse("compress"), None);
+///
+/// assert_eq!(Encoding::Zstd.as_str(), "zstd");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Encoding {
+    /// Brotli: `br`.
+    Brotli,
+    /// Zstandard: `zstd`.
+    Zstd,
+    /// Gzip: `gzip`.
+    Gzip,
+    /// Deflate, in the zlib format: `deflate`.
+    Deflate,
+}
+
+impl Encoding {
+    /// All supported encodings in Rocket's default order of preference.
+    pub const ALL: [Encoding; 4] = [
+        Encoding::Brotli,
+        Encoding::Zstd,
+        Encoding::Gzip,
+        Encoding::Deflate,
+    ];
+
+    /// Returns the encoding's content-coding token.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Encoding::Brotli => "br",
+            Encoding::Zstd => "zstd",
+            Encoding::Gzip => "gzip",
+            Encoding::Deflate => "deflate",
+        }
+    }
+
+    /// Parses a content-coding token, ignoring case. Returns `None` if the
+    /// coding isn't supported.
+    pub fn parse(coding: &str) -> Option<Encoding> {
+        let coding = coding.trim();
+        match () {
+            _ if coding.eq_ignore_ascii_case("br") => Some(Encoding::Brotli),
+            _ if coding.eq_ignore_ascii_case("zstd") => Some(Encoding::Zstd),
+            _ if coding.eq_ignore_ascii_case("gzip") => Some(Encoding::Gzip),
+            _ if coding.eq_ignore_ascii_case("x-gzip") => Some(Encoding::Gzip),
+            _ if coding.eq_ignore_ascii_case("deflate") => Some(Encoding::Deflate),
+            _ => None
+        }
+    }
+
+    /// Parses the codings listed in `values`, the values of a
+    /// `Content-Encoding` header, in the order they were applied, skipping
+    /// `identity`. Returns the first unsupported coding as an `Err`.
+    pub(crate) fn parse_applied<'a, I>(values: I) -> Result<Vec<Encoding>, &'a str>
+        where I: IntoIterator<Item = &'a str>
+    {
+        let mut encodings = vec![];
+        for coding in values.into_iter().flat_map(|v| v.split(',')).map(|c| c.trim()) {
+            if coding.is_empty() || coding.eq_ignore_ascii_case("identity") {
+                continue;
+            }
+
+            encodings.push(Encoding::parse(coding).ok_or(coding)?);
+        }
+
+        Ok(encodings)
+    }
+
+    /// Chooses the encoding in `enabled` that `accept_encoding`, the value of
+    /// an `Accept-Encoding` header, gives the highest q-value. Ties are broken
+    /// by the order of `enabled`. Codings without a q-value have a q-value of
+    /// `1`; unlisted codings take the q-value of `*`, if any, and are otherwise
+    /// unacceptable, as are codings with a q-value of `0`.
+    pub(crate) fn negotiate(accept_encoding: &str, enabled: &[Encoding]) -> Option<Encoding> {
+        let mut best: Option<(Encoding, f32)> = None;
+        for &encoding in enabled {
+            let is_coding = |coding: &str| Encoding::parse(coding) == Some(encoding);
+            let q = accept_encoding_qvalue(accept_encoding, is_coding);
+            if q > 0.0 && best.map_or(true, |(_, best_q)| q > best_q) {
+                best = Some((encoding, q));
+            }
+        }
+
+        best.map(|(encoding, _)| encoding)
+    }
+}
+
+impl fmt::Display for Encoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_str().fmt(f)
+    }
+}
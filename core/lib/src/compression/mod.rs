@@ -0,0 +1,69 @@
+//! Compression of outgoing responses.
+//!
+//! The [`Compression`] fairing compresses response bodies using the encoding
+//! the client prefers among those it advertises in its `Accept-Encoding`
+//! header. The following encodings are supported:
+//!
+//! | Encoding  | `Content-Encoding` | Variant               |
+//! |-----------|--------------------|-----------------------|
+//! | [Brotli]  | `br`               | [`Encoding::Brotli`]  |
+//! | [Zstd]    | `zstd`             | [`Encoding::Zstd`]    |
+//! | [Gzip]    | `gzip`             | [`Encoding::Gzip`]    |
+//! | [Deflate] | `deflate`          | [`Encoding::Deflate`] |
+//!
+//! [Brotli]: https://www.rfc-editor.org/rfc/rfc7932
+//! [Zstd]: https://www.rfc-editor.org/rfc/rfc8878
+//! [Gzip]: https://www.rfc-editor.org/rfc/rfc1952
+//! [Deflate]: https://www.rfc-editor.org/rfc/rfc1950
+//!
+//! Bodies are compressed as they are streamed to the client, so compression
+//! applies equally to sized bodies, [`ReaderStream`]s, and [`EventStream`]s.
+//! Whenever the body has no data ready, the compressed data written so far is
+//! flushed to the client; each server-sent event is thus delivered as soon as
+//! it is produced.
+//!
+//! This module is only available when the `compression` feature is enabled.
+//!
+//! [`ReaderStream`]: crate::response::stream::ReaderStream
+//! [`EventStream`]: crate::response::stream::EventStream
+//!
+//! # Usage
+//!
+//! Attach an instance of [`Compression`], configured as needed:
+//!
+//! ```rust
+//! # #[macro_use] extern crate rocket;
+//! use rocket::compression::{Compression, Encoding};
+//! use rocket::data::ToByteUnit;
+//!
+//! #[launch]
+//! fn rocket() -> _ {
+//!     let compression = Compression::new()
+//!         .with_encodings([Encoding::Zstd, Encoding::Gzip])
+//!         .with_min_size(512.bytes());
+//!
+//!     rocket::build().attach(compression)
+//! }
+//! ```
+//!
+//! A response is left uncompressed if any of the following hold:
+//!
+//!   * The response has no body or its status is `1xx`, `204`, or `304`.
+//!   * The response already has a `Content-Encoding` or `Content-Range`.
+//!   * The response's `Content-Type` is [excluded](Compression::exclude()).
+//!     Formats that are already compressed, such as most images, audio,
+//!     video, fonts, and archives, are excluded by default.
+//!   * The body's size is known and below the [minimum
+//!     size](Compression::with_min_size()).
+//!   * The client doesn't accept any of the enabled encodings.
+//!   * The route opted out by responding with [`Uncompressed`].
+//!
+//! Responses of a compressible type are sent with `Vary: Accept-Encoding`
+//! whether or not they were compressed.
+
+mod compression;
+mod encoding;
+mod encoder;
+
+pub use self::compression::{Compression, Uncompressed};
+pub use self::encoding::Encoding;
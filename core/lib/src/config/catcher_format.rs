@@ -0,0 +1,82 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::request::Request;
+use crate::response::Problem;
+
+/// The format of the error documents produced by Rocket's default catcher.
+///
+/// Every format includes the response's status code, the status' reason, a
+/// description of the error, and the [request's ID](Request::id()). When
+/// [`Config::problem_details`] is enabled, JSON documents are RFC 9457 problem
+/// documents instead; see [`Problem`] for details.
+///
+/// # Deserialization
+///
+/// A `CatcherFormat` deserializes from the lowercase name of its variant:
+/// `"auto"`, `"html"`, `"json"`, or `"plain"`.
+///
+/// # Example
+///
+/// ```rust
+/// # use rocket::figment::{Figment, providers::{Format, Toml}};
+/// use rocket::config::{Config, CatcherFormat};
+///
+/// // If these are the contents of `Rocket.toml`...
+/// # let toml = Toml::string(r#"
+/// [default]
+/// catcher_format = "json"
+/// # "#).nested();
+///
+/// // The config parses as follows:
+/// # let config = Config::from(Figment::from(Config::debug_default()).merge(toml));
+/// assert_eq!(config.catcher_format, CatcherFormat::Json);
+/// ```
+///
+/// [`Config::problem_details`]: crate::Config::problem_details
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CatcherFormat {
+    /// Negotiated from the request's `Accept` header: JSON if the request
+    /// prefers JSON, plain text if it prefers `text/plain`, and HTML otherwise.
+    Auto,
+    /// Always HTML: `text/html`.
+    Html,
+    /// Always JSON: `application/json`.
+    Json,
+    /// Always plain text: `text/plain`.
+    Plain,
+}
+
+impl CatcherFormat {
+    /// Returns the format to respond to `req` with, which is never `Auto`.
+    pub(crate) fn select(self, req: &Request<'_>) -> CatcherFormat {
+        if self != CatcherFormat::Auto {
+            return self;
+        }
+
+        match req.accept().map(|accept| accept.preferred().media_type()) {
+            Some(m) if m.is_json() || *m == Problem::MEDIA_TYPE => CatcherFormat::Json,
+            Some(m) if m.is_plain() => CatcherFormat::Plain,
+            _ => CatcherFormat::Html,
+        }
+    }
+}
+
+impl Default for CatcherFormat {
+    fn default() -> Self {
+        CatcherFormat::Auto
+    }
+}
+
+impl fmt::Display for CatcherFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CatcherFormat::Auto => "auto".fmt(f),
+            CatcherFormat::Html => "html".fmt(f),
+            CatcherFormat::Json => "json".fmt(f),
+            CatcherFormat::Plain => "plain".fmt(f),
+        }
+    }
+}
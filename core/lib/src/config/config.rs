@@ -6,17 +6,23 @@ use figment::value::{Map, Dict, magic::RelativePathBuf};
 use serde::{Deserialize, Serialize};
 use yansi::Paint;
 
-use crate::config::{LogLevel, Shutdown, Ident};
+use crate::config::{LogLevel, Shutdown, Ident, Endpoint, ProxyProtocol, CatcherFormat};
 use crate::request::{self, Request, FromRequest};
 use crate::http::uncased::Uncased;
-use crate::data::Limits;
+use crate::data::{Limits, ByteUnit};
 
 #[cfg(feature = "tls")]
 use crate::config::TlsConfig;
 
+#[cfg(feature = "http2")]
+use crate::config::Http2;
+
 #[cfg(feature = "secrets")]
 use crate::config::SecretKey;
 
+#[cfg(unix)]
+use crate::config::UnixConfig;
+
 /// Rocket server configuration.
 ///
 /// See the [module level docs](crate::config) as well as the [configuration
@@ -66,6 +72,11 @@ pub struct Config {
     #[serde(skip)]
     pub profile: Profile,
     /// IP address to serve on. **(default: `127.0.0.1`)**
+    ///
+    /// On Unix, an address of the form `unix:<path>`, such as
+    /// `unix:/run/app.sock`, may be configured instead. It is shorthand for a
+    /// [`unix`](Config::unix) configuration with that `path`, which must not
+    /// also be configured, and leaves `address` at its default.
     pub address: IpAddr,
     /// Port to serve on. **(default: `8000`)**
     pub port: u16,
@@ -91,18 +102,83 @@ pub struct Config {
     /// **(default: `"X-Real-IP"`)**
     #[serde(deserialize_with = "crate::config::ip_header::deserialize")]
     pub ip_header: Option<Uncased<'static>>,
+    /// The PROXY protocol configuration, if any. See [`ProxyProtocol`] for
+    /// details. **(default: `None`)**
+    pub proxy_protocol: Option<ProxyProtocol>,
     /// Streaming read size limits. **(default: [`Limits::default()`])**
     pub limits: Limits,
+    /// Whether to decode request bodies sent with a `Content-Encoding` of
+    /// `gzip`, `deflate`, `br`, or `zstd` before they reach request fairings
+    /// and data guards. `limits` then apply to the decoded size. Requests with
+    /// any other `Content-Encoding` receive a `415 Unsupported Media Type`
+    /// response. **(default: `false`)**
+    #[cfg(feature = "compression")]
+    #[cfg_attr(nightly, doc(cfg(feature = "compression")))]
+    #[serde(deserialize_with = "figment::util::bool_from_str_or_int")]
+    pub decompress: bool,
     /// Directory to store temporary files in. **(default:
     /// [`std::env::temp_dir()`])**
     #[serde(serialize_with = "RelativePathBuf::serialize_relative")]
     pub temp_dir: RelativePathBuf,
     /// Keep-alive timeout in seconds; disabled when `0`. **(default: `5`)**
     pub keep_alive: u32,
+    /// Seconds a client has to send a request's headers; disabled when `0`.
+    ///
+    /// The timer starts when a connection is accepted and, on kept-alive
+    /// HTTP/1 connections, again when the next request begins. If it expires
+    /// before the headers are complete, a client that sent part of a request
+    /// receives a `408 Request Timeout` response; an idle connection is closed.
+    /// The same limit bounds how long a trusted upstream has to send a PROXY
+    /// protocol header when [`Config::proxy_protocol`] is set. Disabled by
+    /// default; a value such as `10` protects against clients that hold
+    /// connections open without sending requests.
+    ///
+    /// **(default: `0`)**
+    pub header_timeout: u32,
+    /// Seconds to produce a response to a request, including reading its body,
+    /// before responding with `408 Request Timeout`; disabled when `0`.
+    /// Writing the response body is not limited. **(default: `0`)**
+    pub request_timeout: u32,
+    /// Maximum number of connections to serve at once; unlimited when `0`.
+    /// Connections accepted beyond the limit receive a `503 Service
+    /// Unavailable` response and are closed. **(default: `0`)**
+    pub max_connections: usize,
+    /// Maximum total size of a request's headers. Requests exceeding it receive
+    /// a `431 Request Header Fields Too Large` response. **(default: `64KiB`)**
+    pub max_header_size: ByteUnit,
+    /// Maximum number of headers in a request. Requests exceeding it receive a
+    /// `431 Request Header Fields Too Large` response. HTTP/1 requests with
+    /// more than 100 headers are always rejected. **(default: `100`)**
+    pub max_headers: usize,
+    /// Maximum number of requests to serve on one HTTP/1 connection before
+    /// closing it; unlimited when `0`. **(default: `0`)**
+    pub max_requests_per_connection: usize,
+    /// HTTP/2 connection tuning. **(default: [`Http2::default()`])**
+    #[cfg(feature = "http2")]
+    #[cfg_attr(nightly, doc(cfg(feature = "http2")))]
+    pub http2: Http2,
+    /// The Unix domain socket configuration, if any. When set, Rocket binds
+    /// a Unix domain socket instead of `address` and `port`, and neither TLS
+    /// nor `endpoints` may be configured. **(default: `None`)**
+    #[cfg(unix)]
+    #[cfg_attr(nightly, doc(cfg(unix)))]
+    pub unix: Option<UnixConfig>,
+    /// Whether to serve on the listening sockets passed by a service manager,
+    /// such as systemd, via socket activation instead of binding sockets.
+    /// Passed TCP sockets serve TLS if `tls` is configured. When no sockets
+    /// are passed, Rocket binds `address` and `port` as if this were `false`.
+    /// Neither `unix` nor `endpoints` may be configured. **(default: `false`)**
+    #[cfg(unix)]
+    #[cfg_attr(nightly, doc(cfg(unix)))]
+    #[serde(deserialize_with = "figment::util::bool_from_str_or_int")]
+    pub socket_activation: bool,
     /// The TLS configuration, if any. **(default: `None`)**
     #[cfg(feature = "tls")]
     #[cfg_attr(nightly, doc(cfg(feature = "tls")))]
     pub tls: Option<TlsConfig>,
+    /// Endpoints to serve on instead of `address`, `port`, and `tls`, if
+    /// any. See [`Endpoint`] for details. **(default: `[]`)**
+    pub endpoints: Vec<Endpoint>,
     /// The secret key for signing and encrypting. **(default: `0`)**
     ///
     /// _**Note:** This field _always_ serializes as a 256-bit array of `0`s to
@@ -113,6 +189,15 @@ pub struct Config {
     pub secret_key: SecretKey,
     /// Graceful shutdown configuration. **(default: [`Shutdown::default()`])**
     pub shutdown: Shutdown,
+    /// The format of the error documents produced by Rocket's default catcher.
+    /// See [`CatcherFormat`] for details. **(default: `auto`)**
+    pub catcher_format: CatcherFormat,
+    /// Whether Rocket's default catcher responds with an RFC 9457 problem
+    /// document, a [`Problem`](crate::response::Problem), in place of a JSON
+    /// error document. Errors from failing `Form` and `Json` data guards are
+    /// included in the document. **(default: `false`)**
+    #[serde(deserialize_with = "figment::util::bool_from_str_or_int")]
+    pub problem_details: bool,
     /// Max level to log. **(default: _debug_ `normal` / _release_ `critical`)**
     pub log_level: LogLevel,
     /// Whether to use colors and emoji when logging. **(default: `true`)**
@@ -188,14 +273,32 @@ impl Config {
             max_blocking: 512,
             ident: Ident::default(),
             ip_header: Some(Uncased::from_borrowed("X-Real-IP")),
+            proxy_protocol: None,
             limits: Limits::default(),
+            #[cfg(feature = "compression")]
+            decompress: false,
             temp_dir: std::env::temp_dir().into(),
             keep_alive: 5,
+            header_timeout: 0,
+            request_timeout: 0,
+            max_connections: 0,
+            max_header_size: ByteUnit::Kibibyte(64),
+            max_headers: 100,
+            max_requests_per_connection: 0,
+            #[cfg(feature = "http2")]
+            http2: Http2::default(),
+            #[cfg(unix)]
+            unix: None,
+            #[cfg(unix)]
+            socket_activation: false,
             #[cfg(feature = "tls")]
             tls: None,
+            endpoints: vec![],
             #[cfg(feature = "secrets")]
             secret_key: SecretKey::zero(),
             shutdown: Shutdown::default(),
+            catcher_format: CatcherFormat::Auto,
+            problem_details: false,
             log_level: LogLevel::Normal,
             cli_colors: true,
             __non_exhaustive: (),
@@ -278,11 +381,60 @@ impl Config {
     /// ```
     pub fn try_from<T: Provider>(provider: T) -> Result<Self> {
         let figment = Figment::from(provider);
+        #[cfg(unix)]
+        let figment = Self::unix_address(figment)?;
+
         let mut config = figment.extract::<Self>()?;
         config.profile = figment.profile().clone();
+        config.validate().map_err(|mut e| {
+            e.metadata = figment.find_metadata(&e.path.join(".")).cloned();
+            e.profile = Some(config.profile.clone());
+            e
+        })?;
+
         Ok(config)
     }
 
+    /// Maps an `address` of the form `unix:<path>` to a `unix` configuration
+    /// with that `path`. Like `unix.path`, a relative path configured in a
+    /// file is interpreted as relative to the file's directory.
+    #[cfg(unix)]
+    fn unix_address(figment: Figment) -> Result<Figment> {
+        use std::path::PathBuf;
+        use figment::{Source, value::Value};
+
+        let path = match figment.find_value(Self::ADDRESS) {
+            Ok(value) => match value.as_str().and_then(|v| v.strip_prefix("unix:")) {
+                Some(path) => PathBuf::from(path),
+                None => return Ok(figment),
+            },
+            Err(_) => return Ok(figment),
+        };
+
+        let metadata = figment.find_metadata(Self::ADDRESS).cloned();
+        // The default configuration contains an empty `unix`.
+        let unix = figment.find_value(Self::UNIX).ok();
+        if unix.map_or(false, |unix| !matches!(unix, Value::Empty(..))) {
+            let msg = format!("a `unix:` address cannot be combined with `{}`", Self::UNIX);
+            let mut error = figment::Error::from(msg).with_path(Self::ADDRESS);
+            error.metadata = metadata;
+            error.profile = Some(figment.profile().clone());
+            return Err(error);
+        }
+
+        let path = match metadata.as_ref().and_then(|m| m.source.as_ref()) {
+            Some(Source::File(file)) if path.is_relative() => match file.parent() {
+                Some(dir) => dir.join(path),
+                None => path,
+            },
+            _ => path,
+        };
+
+        Ok(figment
+            .merge(Serialized::global(Self::ADDRESS, Config::default().address))
+            .merge(Serialized::global(&format!("{}.path", Self::UNIX), path)))
+    }
+
     /// Extract a `Config` from `provider`, panicking if extraction fails.
     ///
     /// # Panics
@@ -364,6 +516,61 @@ impl Config {
         #[cfg(not(feature = "mtls"))] { false }
     }
 
+    /// Returns the path of the configured Unix domain socket, if any.
+    pub(crate) fn unix_socket(&self) -> Option<std::path::PathBuf> {
+        #[cfg(unix)] {
+            self.unix.as_ref().map(|unix| unix.path())
+        }
+
+        #[cfg(not(unix))] { None }
+    }
+
+    /// Returns `true` if socket activation is enabled.
+    pub(crate) fn socket_activation(&self) -> bool {
+        #[cfg(unix)] { self.socket_activation }
+        #[cfg(not(unix))] { false }
+    }
+
+    /// Checks for parameters that are individually valid but can't be served
+    /// together, returning an error for the offending parameter if any.
+    fn validate(&self) -> Result<()> {
+        fn conflict(key: &str, other: &str) -> figment::Error {
+            let msg = format!("`{}` cannot be combined with `{}`", key, other);
+            figment::Error::from(msg).with_path(key)
+        }
+
+        if self.unix_socket().is_some() {
+            if self.tls_enabled() {
+                return Err(conflict(Self::UNIX, Self::TLS));
+            }
+
+            if !self.endpoints.is_empty() {
+                return Err(conflict(Self::UNIX, Self::ENDPOINTS));
+            }
+        }
+
+        // Passed sockets replace, rather than supplement, configured ones.
+        if self.socket_activation() {
+            if self.unix_socket().is_some() {
+                return Err(conflict(Self::SOCKET_ACTIVATION, Self::UNIX));
+            }
+
+            if !self.endpoints.is_empty() {
+                return Err(conflict(Self::SOCKET_ACTIVATION, Self::ENDPOINTS));
+            }
+        }
+
+        if self.endpoints.iter().any(|e| e.tls_enabled() && e.https_redirect()) {
+            let msg = "a TLS-enabled endpoint cannot enable `https_redirect`";
+            return Err(figment::Error::from(msg.to_string()).with_path(Self::ENDPOINTS));
+        }
+
+        #[cfg(feature = "http2")]
+        self.http2.validate()?;
+
+        Ok(())
+    }
+
     #[cfg(feature = "secrets")]
     pub(crate) fn known_secret_key_used(&self) -> bool {
         const KNOWN_SECRET_KEYS: &'static [&'static str] = &[
@@ -384,8 +591,27 @@ impl Config {
         }
 
         launch_meta!("{}Configured for {}.", Paint::emoji("🔧 "), self.profile);
-        launch_meta_!("address: {}", bold(&self.address));
-        launch_meta_!("port: {}", bold(&self.port));
+        match self.unix_socket() {
+            _ if self.socket_activation() => launch_meta_!("socket activation: {}", bold("enabled")),
+            Some(path) => launch_meta_!("unix socket: {}", bold(path.display())),
+            None if !self.endpoints.is_empty() => {
+                for endpoint in &self.endpoints {
+                    match (endpoint.tls_enabled(), endpoint.https_redirect()) {
+                        (true, _) => launch_meta_!("endpoint: {} w/tls",
+                            bold(endpoint.socket_addr())),
+                        (false, true) => launch_meta_!("endpoint: {} w/https redirect",
+                            bold(endpoint.socket_addr())),
+                        (false, false) => launch_meta_!("endpoint: {}",
+                            bold(endpoint.socket_addr())),
+                    }
+                }
+            }
+            None => {
+                launch_meta_!("address: {}", bold(&self.address));
+                launch_meta_!("port: {}", bold(&self.port));
+            }
+        }
+
         launch_meta_!("workers: {}", bold(self.workers));
         launch_meta_!("max blocking threads: {}", bold(self.max_blocking));
         launch_meta_!("ident: {}", bold(&self.ident));
@@ -395,21 +621,66 @@ impl Config {
             None => launch_meta_!("IP header: {}", bold("disabled"))
         }
 
+        if let Some(ref proxy) = self.proxy_protocol {
+            let trusted = proxy.trusted().iter().map(|r| r.to_string()).collect::<Vec<_>>();
+            launch_meta_!("PROXY protocol from: {}", bold(trusted.join(", ")));
+        }
+
         launch_meta_!("limits: {}", bold(&self.limits));
+        #[cfg(feature = "compression")]
+        launch_meta_!("request decompression: {}", bold(self.decompress));
         launch_meta_!("temp dir: {}", bold(&self.temp_dir.relative().display()));
         launch_meta_!("http/2: {}", bold(cfg!(feature = "http2")));
 
+        #[cfg(feature = "http2")]
+        launch_meta_!("http/2 tuning: {}", bold(&self.http2));
+
         match self.keep_alive {
             0 => launch_meta_!("keep-alive: {}", bold("disabled")),
             ka => launch_meta_!("keep-alive: {}{}", bold(ka), bold("s")),
         }
 
+        match self.header_timeout {
+            0 => launch_meta_!("header timeout: {}", bold("disabled")),
+            t => launch_meta_!("header timeout: {}{}", bold(t), bold("s")),
+        }
+
+        match self.request_timeout {
+            0 => launch_meta_!("request timeout: {}", bold("disabled")),
+            t => launch_meta_!("request timeout: {}{}", bold(t), bold("s")),
+        }
+
+        match self.max_connections {
+            0 => launch_meta_!("max connections: {}", bold("unlimited")),
+            n => launch_meta_!("max connections: {}", bold(n)),
+        }
+
+        launch_meta_!("max headers: {} totaling {}",
+            bold(self.max_headers), bold(self.max_header_size));
+
+        if self.max_requests_per_connection != 0 {
+            launch_meta_!("max requests per connection: {}",
+                bold(self.max_requests_per_connection));
+        }
+
         match (self.tls_enabled(), self.mtls_enabled()) {
             (true, true) => launch_meta_!("tls: {}", bold("enabled w/mtls")),
             (true, false) => launch_meta_!("tls: {} w/o mtls", bold("enabled")),
             (false, _) => launch_meta_!("tls: {}", bold("disabled")),
         }
 
+        #[cfg(feature = "tls")]
+        if let Some(tls) = self.tls.as_ref().filter(|_| self.tls_enabled()) {
+            let names: Vec<_> = tls.sni().map(|(name, _)| name).collect();
+            if !names.is_empty() {
+                launch_meta_!("tls sni: {}", bold(names.join(", ")));
+            }
+
+            if tls.reload_interval() > 0 {
+                launch_meta_!("tls reload: {}{}", bold(tls.reload_interval()), bold("s"));
+            }
+        }
+
         #[cfg(feature = "secrets")] {
             launch_meta_!("secret key: {}", bold(&self.secret_key));
             if !self.secret_key.is_provided() {
@@ -420,6 +691,8 @@ impl Config {
         }
 
         launch_meta_!("shutdown: {}", bold(&self.shutdown));
+        launch_meta_!("catcher format: {}", bold(self.catcher_format));
+        launch_meta_!("problem details: {}", bold(self.problem_details));
         launch_meta_!("log level: {}", bold(self.log_level));
         launch_meta_!("cli colors: {}", bold(&self.cli_colors));
 
@@ -493,18 +766,69 @@ impl Config {
     /// The stringy parameter name for setting/extracting [`Config::keep_alive`].
     pub const KEEP_ALIVE: &'static str = "keep_alive";
 
+    /// The stringy parameter name for setting/extracting
+    /// [`Config::header_timeout`].
+    pub const HEADER_TIMEOUT: &'static str = "header_timeout";
+
+    /// The stringy parameter name for setting/extracting
+    /// [`Config::request_timeout`].
+    pub const REQUEST_TIMEOUT: &'static str = "request_timeout";
+
+    /// The stringy parameter name for setting/extracting
+    /// [`Config::max_connections`].
+    pub const MAX_CONNECTIONS: &'static str = "max_connections";
+
+    /// The stringy parameter name for setting/extracting
+    /// [`Config::max_header_size`].
+    pub const MAX_HEADER_SIZE: &'static str = "max_header_size";
+
+    /// The stringy parameter name for setting/extracting [`Config::max_headers`].
+    pub const MAX_HEADERS: &'static str = "max_headers";
+
+    /// The stringy parameter name for setting/extracting
+    /// [`Config::max_requests_per_connection`].
+    pub const MAX_REQUESTS_PER_CONNECTION: &'static str = "max_requests_per_connection";
+
+    /// The stringy parameter name for setting/extracting [`Config::http2`].
+    pub const HTTP2: &'static str = "http2";
+
+    /// The stringy parameter name for setting/extracting
+    /// [`Config::proxy_protocol`].
+    pub const PROXY_PROTOCOL: &'static str = "proxy_protocol";
+
     /// The stringy parameter name for setting/extracting [`Config::limits`].
     pub const LIMITS: &'static str = "limits";
 
+    /// The stringy parameter name for setting/extracting [`Config::decompress`].
+    pub const DECOMPRESS: &'static str = "decompress";
+
     /// The stringy parameter name for setting/extracting [`Config::tls`].
     pub const TLS: &'static str = "tls";
 
+    /// The stringy parameter name for setting/extracting [`Config::endpoints`].
+    pub const ENDPOINTS: &'static str = "endpoints";
+
+    /// The stringy parameter name for setting/extracting
+    /// [`Config::socket_activation`].
+    pub const SOCKET_ACTIVATION: &'static str = "socket_activation";
+
+    /// The stringy parameter name for setting/extracting [`Config::unix`].
+    pub const UNIX: &'static str = "unix";
+
     /// The stringy parameter name for setting/extracting [`Config::secret_key`].
     pub const SECRET_KEY: &'static str = "secret_key";
 
     /// The stringy parameter name for setting/extracting [`Config::temp_dir`].
     pub const TEMP_DIR: &'static str = "temp_dir";
 
+    /// The stringy parameter name for setting/extracting
+    /// [`Config::catcher_format`].
+    pub const CATCHER_FORMAT: &'static str = "catcher_format";
+
+    /// The stringy parameter name for setting/extracting
+    /// [`Config::problem_details`].
+    pub const PROBLEM_DETAILS: &'static str = "problem_details";
+
     /// The stringy parameter name for setting/extracting [`Config::log_level`].
     pub const LOG_LEVEL: &'static str = "log_level";
 
@@ -0,0 +1,192 @@
+use std::net::{IpAddr, SocketAddr};
+
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "tls")]
+use crate::config::TlsConfig;
+
+/// An additional address and port to serve on, with optional TLS.
+///
+/// When [`Config::endpoints`](crate::Config::endpoints) is non-empty, Rocket
+/// binds _every_ listed endpoint instead of the single `address`, `port`, and
+/// `tls` parameters of [`Config`](crate::Config). All endpoints are served by
+/// the same instance: routes, catchers, fairings, and managed state are
+/// shared. Four parameters control each endpoint:
+///
+///   * `address`, `port`
+///
+///     The IP address and port to bind. A port of `0` binds an
+///     OS-assigned port. The addresses Rocket actually bound are available
+///     via [`Rocket::addresses()`](crate::Rocket::addresses()) once in orbit.
+///
+///   * `tls`
+///
+///     The [`TlsConfig`] for this endpoint, if any. Requires the `tls`
+///     feature. When absent, the endpoint serves plaintext HTTP.
+///
+///   * `https_redirect`
+///
+///     A boolean that, when `true`, causes the plaintext endpoint to respond
+///     to _every_ request with a `308 Permanent Redirect` to the same host and
+///     path on the first TLS-enabled endpoint instead of routing the request.
+///     Defaults to `false`. Launch fails if no endpoint has TLS enabled or if
+///     this endpoint has TLS enabled.
+///
+/// In `Rocket.toml`, serving plaintext HTTP on `80` that redirects to HTTPS on
+/// `443` might look like:
+///
+/// ```toml
+/// [[default.endpoints]]
+/// address = "0.0.0.0"
+/// port = 80
+/// https_redirect = true
+///
+/// [[default.endpoints]]
+/// address = "0.0.0.0"
+/// port = 443
+/// tls = { certs = "private/cert.pem", key = "private/key.pem" }
+/// ```
+///
+/// Programmatically, serving on two ports might look like:
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// use std::net::Ipv4Addr;
+/// use rocket::config::{Config, Endpoint};
+///
+/// #[launch]
+/// fn rocket() -> _ {
+///     let config = Config {
+///         endpoints: vec![
+///             Endpoint::new(Ipv4Addr::LOCALHOST, 8000),
+///             Endpoint::new(Ipv4Addr::LOCALHOST, 8001),
+///         ],
+///         ..Default::default()
+///     };
+///
+///     rocket::custom(config)
+/// }
+/// ```
+#[derive(PartialEq, Debug, Clone, Deserialize, Serialize)]
+pub struct Endpoint {
+    /// IP address to bind.
+    pub(crate) address: IpAddr,
+    /// Port to bind.
+    pub(crate) port: u16,
+    /// The TLS configuration, if any.
+    #[serde(default)]
+    #[cfg(feature = "tls")]
+    #[cfg_attr(nightly, doc(cfg(feature = "tls")))]
+    pub(crate) tls: Option<TlsConfig>,
+    /// Whether to redirect all requests to the first TLS-enabled endpoint.
+    #[serde(default)]
+    #[serde(deserialize_with = "figment::util::bool_from_str_or_int")]
+    pub(crate) https_redirect: bool,
+}
+
+impl Endpoint {
+    /// Constructs a plaintext `Endpoint` that binds `address` and `port`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::net::Ipv4Addr;
+    /// use rocket::config::Endpoint;
+    ///
+    /// let endpoint = Endpoint::new(Ipv4Addr::UNSPECIFIED, 80);
+    /// assert_eq!(endpoint.port(), 80);
+    /// assert!(!endpoint.https_redirect());
+    /// ```
+    pub fn new<A: Into<IpAddr>>(address: A, port: u16) -> Self {
+        Endpoint {
+            address: address.into(),
+            port,
+            #[cfg(feature = "tls")]
+            tls: None,
+            https_redirect: false,
+        }
+    }
+
+    /// Sets the TLS configuration for this endpoint to `tls`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::net::Ipv4Addr;
+    /// use rocket::config::{Endpoint, TlsConfig};
+    ///
+    /// let tls = TlsConfig::from_paths("/ssl/certs.pem", "/ssl/key.pem");
+    /// let endpoint = Endpoint::new(Ipv4Addr::UNSPECIFIED, 443).with_tls(tls);
+    /// assert!(endpoint.tls().is_some());
+    /// ```
+    #[cfg(feature = "tls")]
+    #[cfg_attr(nightly, doc(cfg(feature = "tls")))]
+    pub fn with_tls(mut self, tls: TlsConfig) -> Self {
+        self.tls = Some(tls);
+        self
+    }
+
+    /// Sets whether this endpoint redirects every request to the first
+    /// TLS-enabled endpoint.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::net::Ipv4Addr;
+    /// use rocket::config::Endpoint;
+    ///
+    /// let endpoint = Endpoint::new(Ipv4Addr::UNSPECIFIED, 80).with_https_redirect(true);
+    /// assert!(endpoint.https_redirect());
+    /// ```
+    pub fn with_https_redirect(mut self, redirect: bool) -> Self {
+        self.https_redirect = redirect;
+        self
+    }
+
+    /// Returns the IP address to bind.
+    pub fn address(&self) -> IpAddr {
+        self.address
+    }
+
+    /// Returns the port to bind.
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+
+    /// Returns the socket address to bind.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::net::{Ipv4Addr, SocketAddr};
+    /// use rocket::config::Endpoint;
+    ///
+    /// let endpoint = Endpoint::new(Ipv4Addr::LOCALHOST, 8080);
+    /// assert_eq!(endpoint.socket_addr(), SocketAddr::from((Ipv4Addr::LOCALHOST, 8080)));
+    /// ```
+    pub fn socket_addr(&self) -> SocketAddr {
+        SocketAddr::new(self.address, self.port)
+    }
+
+    /// Returns the TLS configuration for this endpoint, if any.
+    #[cfg(feature = "tls")]
+    #[cfg_attr(nightly, doc(cfg(feature = "tls")))]
+    pub fn tls(&self) -> Option<&TlsConfig> {
+        self.tls.as_ref()
+    }
+
+    /// Returns whether this endpoint redirects to a TLS-enabled endpoint.
+    pub fn https_redirect(&self) -> bool {
+        self.https_redirect
+    }
+
+    /// Returns `true` if TLS is configured for this endpoint with at least one
+    /// ciphersuite.
+    pub fn tls_enabled(&self) -> bool {
+        #[cfg(feature = "tls")] {
+            self.tls.as_ref().map_or(false, |tls| !tls.ciphers.is_empty())
+        }
+
+        #[cfg(not(feature = "tls"))] { false }
+    }
+}
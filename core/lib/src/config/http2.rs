@@ -0,0 +1,217 @@
+use std::fmt;
+
+use figment::error::{Actual, Kind};
+use serde::{Deserialize, Serialize};
+
+use crate::data::ByteUnit;
+
+/// HTTP/2 connection tuning.
+///
+/// These settings apply to every HTTP/2 connection: those negotiated via ALPN
+/// on TLS listeners as well as cleartext, prior-knowledge (`h2c`) connections.
+/// Settings left unset use [`hyper`](https://hyper.rs)'s defaults, noted below.
+///
+/// Values are validated when Rocket ignites. Frame sizes must be between
+/// `16KiB` and `16MiB - 1`, window sizes must not exceed `2GiB - 1`, and window
+/// sizes cannot be combined with [`Http2::adaptive_window`], which overrides
+/// them.
+///
+/// # Example
+///
+/// As with all Rocket configuration options, when using the default
+/// [`Config::figment()`](crate::Config::figment()), `Http2` can be configured
+/// via a `Rocket.toml` file:
+///
+/// ```rust
+/// # use rocket::figment::{Figment, providers::{Format, Toml}};
+/// use rocket::Config;
+/// use rocket::data::ToByteUnit;
+///
+/// // If these are the contents of `Rocket.toml`...
+/// # let toml = Toml::string(r#"
+/// [default.http2]
+/// max_concurrent_streams = 100
+/// initial_stream_window_size = "1MiB"
+/// max_frame_size = "32KiB"
+/// keep_alive_interval = 30
+/// # "#).nested();
+///
+/// // The config parses as follows:
+/// # let config = Config::from(Figment::from(Config::debug_default()).merge(toml));
+/// assert_eq!(config.http2.max_concurrent_streams, Some(100));
+/// assert_eq!(config.http2.initial_stream_window_size, Some(1.mebibytes()));
+/// assert_eq!(config.http2.max_frame_size, Some(32.kibibytes()));
+/// assert_eq!(config.http2.keep_alive_interval, Some(30));
+/// ```
+///
+/// Or, programmatically:
+///
+/// ```rust
+/// use rocket::config::{Config, Http2};
+///
+/// let config = Config {
+///     http2: Http2 {
+///         max_concurrent_streams: Some(100),
+///         adaptive_window: true,
+///         ..Default::default()
+///     },
+///     ..Config::default()
+/// };
+///
+/// assert!(config.http2.adaptive_window);
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(nightly, doc(cfg(feature = "http2")))]
+pub struct Http2 {
+    /// Maximum number of concurrent streams a client may open on one
+    /// connection.
+    ///
+    /// **default: `None` (unlimited)**
+    pub max_concurrent_streams: Option<u32>,
+    /// Initial flow-control window size of each stream.
+    ///
+    /// **default: `None` (`1MiB`)**
+    pub initial_stream_window_size: Option<ByteUnit>,
+    /// Initial flow-control window size of each connection.
+    ///
+    /// **default: `None` (`1MiB`)**
+    pub initial_connection_window_size: Option<ByteUnit>,
+    /// Whether to adapt flow-control windows to the connection's estimated
+    /// bandwidth-delay product. Cannot be combined with explicit window sizes.
+    ///
+    /// **default: `false`**
+    #[serde(deserialize_with = "figment::util::bool_from_str_or_int")]
+    pub adaptive_window: bool,
+    /// Maximum size of a frame's payload Rocket is willing to receive.
+    ///
+    /// **default: `None` (`16KiB`)**
+    pub max_frame_size: Option<ByteUnit>,
+    /// Number of seconds between keep-alive pings; disabled when `0`.
+    ///
+    /// **default: `None` ([`Config::keep_alive`](crate::Config::keep_alive))**
+    pub keep_alive_interval: Option<u32>,
+    /// Number of seconds to wait for a keep-alive ping to be acknowledged
+    /// before closing the connection. Must be nonzero.
+    ///
+    /// **default: `20`**
+    pub keep_alive_timeout: u32,
+    /// Maximum total size of a request's headers, as advertised to clients.
+    ///
+    /// **default: `None` (`16MiB`)**
+    pub max_header_list_size: Option<ByteUnit>,
+    /// PRIVATE: This structure may grow (but never change otherwise) in a
+    /// non-breaking release. As such, constructing this structure should
+    /// _always_ be done using a public constructor or update syntax:
+    ///
+    /// ```rust
+    /// use rocket::config::Http2;
+    ///
+    /// let config = Http2 {
+    ///     max_concurrent_streams: Some(100),
+    ///     ..Default::default()
+    /// };
+    /// ```
+    #[doc(hidden)]
+    #[serde(skip)]
+    pub __non_exhaustive: (),
+}
+
+/// The largest flow-control window size permitted by HTTP/2.
+const MAX_WINDOW_SIZE: u64 = (1 << 31) - 1;
+
+/// The smallest and largest frame sizes permitted by HTTP/2.
+const FRAME_SIZES: std::ops::RangeInclusive<u64> = (1 << 14)..=((1 << 24) - 1);
+
+impl Http2 {
+    /// Returns an error if any value is out of range or if settings conflict.
+    pub(crate) fn validate(&self) -> figment::Result<()> {
+        fn invalid(key: &str, value: u64, expected: &str) -> figment::Error {
+            let kind = Kind::InvalidValue(Actual::Unsigned(value.into()), expected.into());
+            figment::Error::from(kind).with_path("http2").with_path(key)
+        }
+
+        let windows = [
+            ("initial_stream_window_size", self.initial_stream_window_size),
+            ("initial_connection_window_size", self.initial_connection_window_size),
+        ];
+
+        for (key, size) in windows.iter() {
+            match size {
+                Some(size) if self.adaptive_window => {
+                    let expected = "no window size when `adaptive_window` is enabled";
+                    return Err(invalid(key, size.as_u64(), expected));
+                }
+                Some(size) if size.as_u64() > MAX_WINDOW_SIZE => {
+                    return Err(invalid(key, size.as_u64(), "a window size below 2GiB"));
+                }
+                _ => {}
+            }
+        }
+
+        if let Some(size) = self.max_frame_size {
+            if !FRAME_SIZES.contains(&size.as_u64()) {
+                let expected = "a frame size between 16KiB and 16MiB - 1";
+                return Err(invalid("max_frame_size", size.as_u64(), expected));
+            }
+        }
+
+        if let Some(size) = self.max_header_list_size {
+            if size.as_u64() > u32::MAX as u64 {
+                return Err(invalid("max_header_list_size", size.as_u64(), "a size below 4GiB"));
+            }
+        }
+
+        if self.keep_alive_timeout == 0 {
+            return Err(invalid("keep_alive_timeout", 0, "a nonzero timeout"));
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for Http2 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.max_concurrent_streams {
+            Some(n) => write!(f, "streams = {}, ", n)?,
+            None => write!(f, "streams = unlimited, ")?,
+        }
+
+        let size = |s: Option<ByteUnit>| s.map_or("default".into(), |s| s.to_string());
+        match self.adaptive_window {
+            true => write!(f, "windows = adaptive, ")?,
+            false => write!(f, "windows = {}/{}, ",
+                size(self.initial_stream_window_size),
+                size(self.initial_connection_window_size))?,
+        }
+
+        if let Some(size) = self.max_frame_size {
+            write!(f, "max frame = {}, ", size)?;
+        }
+
+        if let Some(size) = self.max_header_list_size {
+            write!(f, "max headers = {}, ", size)?;
+        }
+
+        match self.keep_alive_interval {
+            Some(0) => write!(f, "ping = disabled"),
+            Some(n) => write!(f, "ping = {}s/{}s", n, self.keep_alive_timeout),
+            None => write!(f, "ping = keep-alive/{}s", self.keep_alive_timeout),
+        }
+    }
+}
+
+impl Default for Http2 {
+    fn default() -> Self {
+        Http2 {
+            max_concurrent_streams: None,
+            initial_stream_window_size: None,
+            initial_connection_window_size: None,
+            adaptive_window: false,
+            max_frame_size: None,
+            keep_alive_interval: None,
+            keep_alive_timeout: 20,
+            max_header_list_size: None,
+            __non_exhaustive: (),
+        }
+    }
+}
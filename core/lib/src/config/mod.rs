@@ -114,11 +114,20 @@
 mod ident;
 mod config;
 mod shutdown;
+mod catcher_format;
 mod ip_header;
+mod endpoint;
+mod proxy_protocol;
 
 #[cfg(feature = "tls")]
 mod tls;
 
+#[cfg(feature = "http2")]
+mod http2;
+
+#[cfg(unix)]
+mod unix;
+
 #[cfg(feature = "secrets")]
 mod secret_key;
 
@@ -127,10 +136,16 @@ pub use config::pretty_print_error;
 pub use config::Config;
 pub use crate::log_utils::LogLevel;
 pub use shutdown::Shutdown;
+pub use catcher_format::CatcherFormat;
 pub use ident::Ident;
+pub use endpoint::Endpoint;
+pub use proxy_protocol::ProxyProtocol;
 
 #[cfg(feature = "tls")]
-pub use tls::{TlsConfig, CipherSuite};
+pub use tls::{TlsConfig, SniCertificate, CipherSuite};
+
+#[cfg(feature = "http2")]
+pub use http2::Http2;
 
 #[cfg(feature = "mtls")]
 pub use tls::MutualTls;
@@ -141,6 +156,9 @@ pub use secret_key::SecretKey;
 #[cfg(unix)]
 pub use shutdown::Sig;
 
+#[cfg(unix)]
+pub use unix::UnixConfig;
+
 #[cfg(test)]
 mod tests {
     use std::net::Ipv4Addr;
@@ -269,10 +287,268 @@ mod tests {
         });
     }
 
+    #[test]
+    #[cfg(unix)]
+    fn test_unix_config_from_file() {
+        use crate::config::UnixConfig;
+
+        figment::Jail::expect_with(|jail| {
+            jail.create_file("Rocket.toml", r#"
+                [global.unix]
+                path = "/run/app.sock"
+                mode = 0o660
+                owner = 1000
+                group = 33
+            "#)?;
+
+            let config = Config::from(Config::figment());
+            assert_eq!(config, Config {
+                unix: Some(UnixConfig::from_path("/run/app.sock")
+                    .with_mode(0o660)
+                    .with_owner(1000)
+                    .with_group(33)),
+                ..Config::default()
+            });
+
+            jail.create_file("Rocket.toml", r#"
+                [global.unix]
+                path = "app.sock"
+                reuse = false
+            "#)?;
+
+            let config = Config::from(Config::figment());
+            assert_eq!(config, Config {
+                unix: Some(UnixConfig::from_path(jail.directory().join("app.sock"))
+                    .with_reuse(false)),
+                ..Config::default()
+            });
+
+            jail.set_env("ROCKET_ENDPOINTS", "[{address=\"127.0.0.1\",port=80}]");
+            let error = Config::try_from(Config::figment()).unwrap_err();
+            assert_eq!(error.path, vec!["unix"]);
+
+            #[cfg(feature = "tls")] {
+                jail.set_env("ROCKET_ENDPOINTS", "[]");
+                jail.set_env("ROCKET_TLS", "{certs=\"cert.pem\",key=\"key.pem\"}");
+                let error = Config::try_from(Config::figment()).unwrap_err();
+                assert_eq!(error.path, vec!["unix"]);
+            }
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_unix_address_from_file() {
+        use std::path::Path;
+
+        figment::Jail::expect_with(|jail| {
+            jail.create_file("Rocket.toml", r#"
+                [global]
+                address = "unix:/run/app.sock"
+            "#)?;
+
+            let config = Config::from(Config::figment());
+            assert_eq!(config.address, Config::default().address);
+            assert_eq!(config.unix.unwrap().path(), Path::new("/run/app.sock"));
+
+            jail.create_file("Rocket.toml", r#"
+                [debug]
+                address = "unix:app.sock"
+            "#)?;
+
+            let config = Config::from(Config::figment());
+            assert_eq!(config.unix.unwrap().path(), jail.directory().join("app.sock"));
+
+            jail.set_env("ROCKET_ADDRESS", "unix:/tmp/env.sock");
+            let config = Config::from(Config::figment());
+            assert_eq!(config.unix.unwrap().path(), Path::new("/tmp/env.sock"));
+
+            jail.set_env("ROCKET_UNIX", "{path=\"/tmp/other.sock\"}");
+            let error = Config::try_from(Config::figment()).unwrap_err();
+            assert_eq!(error.path, vec!["address"]);
+
+            jail.set_env("ROCKET_ADDRESS", "127.0.0.1");
+            let config = Config::from(Config::figment());
+            assert_eq!(config.unix.unwrap().path(), Path::new("/tmp/other.sock"));
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn test_endpoints_config_from_file() {
+        use std::net::Ipv4Addr;
+        use crate::config::Endpoint;
+
+        figment::Jail::expect_with(|jail| {
+            jail.create_file("Rocket.toml", r#"
+                [[global.endpoints]]
+                address = "0.0.0.0"
+                port = 80
+                https_redirect = true
+
+                [[global.endpoints]]
+                address = "127.0.0.1"
+                port = 8080
+            "#)?;
+
+            let config = Config::from(Config::figment());
+            assert_eq!(config, Config {
+                endpoints: vec![
+                    Endpoint::new(Ipv4Addr::UNSPECIFIED, 80).with_https_redirect(true),
+                    Endpoint::new(Ipv4Addr::LOCALHOST, 8080),
+                ],
+                ..Config::default()
+            });
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn test_proxy_protocol_config_from_file() {
+        use crate::config::ProxyProtocol;
+
+        figment::Jail::expect_with(|jail| {
+            jail.create_file("Rocket.toml", r#"
+                [global.proxy_protocol]
+                trusted = ["10.0.0.0/8", "fd00::/8", "192.0.2.1"]
+            "#)?;
+
+            let config = Config::from(Config::figment());
+            let trusted = ["10.0.0.0/8", "fd00::/8", "192.0.2.1/32"]
+                .iter()
+                .map(|range| range.parse().unwrap());
+
+            assert_eq!(config, Config {
+                proxy_protocol: Some(ProxyProtocol::trust(trusted)),
+                ..Config::default()
+            });
+
+            jail.create_file("Rocket.toml", r#"
+                [global.proxy_protocol]
+                trusted = ["10.0.0.0/33"]
+            "#)?;
+
+            assert!(Config::figment().extract::<Config>().is_err());
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn test_connection_limits_config_from_file() {
+        use crate::data::ToByteUnit;
+
+        figment::Jail::expect_with(|jail| {
+            jail.create_file("Rocket.toml", r#"
+                [global]
+                header_timeout = 5
+                request_timeout = 30
+                max_connections = 1024
+                max_header_size = "16KiB"
+                max_headers = 50
+                max_requests_per_connection = 1000
+            "#)?;
+
+            let config = Config::from(Config::figment());
+            assert_eq!(config, Config {
+                header_timeout: 5,
+                request_timeout: 30,
+                max_connections: 1024,
+                max_header_size: 16.kibibytes(),
+                max_headers: 50,
+                max_requests_per_connection: 1000,
+                ..Config::default()
+            });
+
+            jail.set_env("ROCKET_MAX_HEADER_SIZE", 4096);
+            jail.set_env("ROCKET_HEADER_TIMEOUT", 10);
+            let config = Config::from(Config::figment());
+            assert_eq!(config.max_header_size, 4.kibibytes());
+            assert_eq!(config.header_timeout, 10);
+            Ok(())
+        });
+    }
+
+    #[test]
+    #[cfg(feature = "compression")]
+    fn test_decompress_config() {
+        figment::Jail::expect_with(|jail| {
+            assert!(!Config::from(Config::figment()).decompress);
+
+            jail.create_file("Rocket.toml", r#"
+                [global]
+                decompress = true
+            "#)?;
+
+            assert!(Config::from(Config::figment()).decompress);
+
+            jail.set_env("ROCKET_DECOMPRESS", 0);
+            assert!(!Config::from(Config::figment()).decompress);
+            Ok(())
+        });
+    }
+
+    #[test]
+    #[cfg(feature = "http2")]
+    fn test_http2_config_from_file() {
+        use crate::config::Http2;
+        use crate::data::ToByteUnit;
+
+        figment::Jail::expect_with(|jail| {
+            jail.create_file("Rocket.toml", r#"
+                [global.http2]
+                max_concurrent_streams = 250
+                initial_stream_window_size = "256KiB"
+                initial_connection_window_size = "2MiB"
+                max_frame_size = 32768
+                keep_alive_interval = 0
+                keep_alive_timeout = 5
+                max_header_list_size = "64KiB"
+            "#)?;
+
+            let config = Config::from(Config::figment());
+            assert_eq!(config.http2, Http2 {
+                max_concurrent_streams: Some(250),
+                initial_stream_window_size: Some(256.kibibytes()),
+                initial_connection_window_size: Some(2.mebibytes()),
+                max_frame_size: Some(32.kibibytes()),
+                keep_alive_interval: Some(0),
+                keep_alive_timeout: 5,
+                max_header_list_size: Some(64.kibibytes()),
+                ..Default::default()
+            });
+
+            jail.create_file("Rocket.toml", r#"
+                [global.http2]
+                adaptive_window = true
+            "#)?;
+
+            let config = Config::from(Config::figment());
+            assert_eq!(config.http2, Http2 { adaptive_window: true, ..Default::default() });
+
+            jail.create_file("Rocket.toml", r#"
+                [global.http2]
+                max_frame_size = "8KiB"
+            "#)?;
+
+            let error = Config::try_from(Config::figment()).unwrap_err();
+            assert_eq!(error.path, vec!["http2", "max_frame_size"]);
+            assert!(error.metadata.is_some());
+
+            jail.create_file("Rocket.toml", "")?;
+            jail.set_env("ROCKET_HTTP2", "{adaptive_window=true,initial_stream_window_size=1}");
+            let error = Config::try_from(Config::figment()).unwrap_err();
+            assert_eq!(error.path, vec!["http2", "initial_stream_window_size"]);
+            Ok(())
+        });
+    }
+
     #[test]
     #[cfg(feature = "tls")]
     fn test_tls_config_from_file() {
-        use crate::config::{TlsConfig, CipherSuite, Ident, Shutdown};
+        use crate::config::{TlsConfig, SniCertificate, CipherSuite, Ident, Shutdown};
 
         figment::Jail::expect_with(|jail| {
             jail.create_file("Rocket.toml", r#"
@@ -317,6 +593,38 @@ mod tests {
                 ..Config::default()
             });
 
+            jail.create_file("Rocket.toml", r#"
+                [global.tls]
+                certs = "cert.pem"
+                key = "key.pem"
+                reload_interval = 60
+
+                [global.tls.sni."example.com"]
+                certs = "example_cert.pem"
+                key = "example_key.pem"
+
+                [global.tls.sni."*.example.com"]
+                certs = [1, 2, 3]
+                key = [4, 5, 6]
+            "#)?;
+
+            let config = Config::from(Config::figment());
+            let example = SniCertificate::from_paths(
+                jail.directory().join("example_cert.pem"),
+                jail.directory().join("example_key.pem"),
+            );
+
+            assert_eq!(config, Config {
+                tls: Some(TlsConfig::from_paths(
+                    jail.directory().join("cert.pem"),
+                    jail.directory().join("key.pem")
+                )
+                    .with_reload_interval(60)
+                    .with_sni("example.com", example)
+                    .with_sni("*.example.com", SniCertificate::from_bytes(&[1, 2, 3], &[4, 5, 6]))),
+                ..Config::default()
+            });
+
             jail.create_file("Rocket.toml", r#"
                 [global.tls]
                 certs = "cert.pem"
@@ -0,0 +1,77 @@
+use serde::{Deserialize, Serialize};
+
+use crate::listener::IpRange;
+
+/// PROXY protocol configuration: which upstreams to read headers from.
+///
+/// Load balancers that forward TCP connections, such as HAProxy or AWS Network
+/// Load Balancers, can send a [PROXY protocol] header ahead of a connection's
+/// data describing the original client. When `proxy_protocol` is configured,
+/// Rocket reads version 1 and version 2 headers from connections whose peer
+/// address is in one of the `trusted` ranges, and [`Request::remote()`] and
+/// [`Request::client_ip()`] report the original client's address. The full
+/// header, including version 2 TLVs such as the TLS SNI, is available via the
+/// [`&ProxyHeader`](crate::listener::ProxyHeader) request guard.
+///
+/// Connections from trusted ranges _must_ begin with a valid header, sent
+/// within the configured [`header_timeout`](crate::Config::header_timeout), if
+/// any, or they are closed. Connections from any other address are served without
+/// reading a header. Headers are read on every TCP listener, including TLS
+/// listeners, where the header precedes the TLS handshake as sent by
+/// TLS-passthrough load balancers. They are not read on Unix domain sockets.
+///
+/// In `Rocket.toml`, configuration might look like:
+///
+/// ```toml
+/// [default.proxy_protocol]
+/// trusted = ["10.0.0.0/8", "fd00::/8"]
+/// ```
+///
+/// Programmatically, configuration might look like:
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// use rocket::config::{Config, ProxyProtocol};
+///
+/// #[launch]
+/// fn rocket() -> _ {
+///     let proxy = ProxyProtocol::trust(["10.0.0.0/8".parse().unwrap()]);
+///     let config = Config {
+///         proxy_protocol: Some(proxy),
+///         ..Default::default()
+///     };
+///
+///     rocket::custom(config)
+/// }
+/// ```
+///
+/// [PROXY protocol]: https://www.haproxy.org/download/2.8/doc/proxy-protocol.txt
+/// [`Request::remote()`]: crate::Request::remote()
+/// [`Request::client_ip()`]: crate::Request::client_ip()
+#[derive(PartialEq, Debug, Clone, Deserialize, Serialize)]
+pub struct ProxyProtocol {
+    /// Ranges of upstream addresses to read PROXY headers from.
+    pub(crate) trusted: Vec<IpRange>,
+}
+
+impl ProxyProtocol {
+    /// Constructs a `ProxyProtocol` that reads headers from upstreams with
+    /// addresses in any of the `trusted` ranges.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::config::ProxyProtocol;
+    ///
+    /// let proxy = ProxyProtocol::trust(["10.0.0.0/8".parse().unwrap()]);
+    /// assert_eq!(proxy.trusted().len(), 1);
+    /// ```
+    pub fn trust<I: IntoIterator<Item = IpRange>>(trusted: I) -> Self {
+        ProxyProtocol { trusted: trusted.into_iter().collect() }
+    }
+
+    /// Returns the ranges of upstream addresses to read PROXY headers from.
+    pub fn trusted(&self) -> &[IpRange] {
+        &self.trusted
+    }
+}
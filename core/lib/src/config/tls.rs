@@ -1,6 +1,6 @@
 use figment::value::magic::{Either, RelativePathBuf};
 use serde::{Deserialize, Serialize};
-use indexmap::IndexSet;
+use indexmap::{IndexMap, IndexSet};
 
 /// TLS configuration: certificate chain, key, and ciphersuites.
 ///
@@ -27,6 +27,25 @@ use indexmap::IndexSet;
 ///     ciphersuite preferences over the client's. The default and recommended
 ///     value is `false`.
 ///
+/// Two further parameters control which certificate is served and when it is
+/// loaded:
+///
+///   * `sni`
+///
+///     A map from server names to [`SniCertificate`]s. During the handshake,
+///     the certificate paired with the name the client requests via SNI is
+///     served. Names may be wildcards of the form `*.example.com`, matching
+///     exactly one additional label. Clients that request no name or an
+///     unlisted name are served `certs`. It defaults to an empty map.
+///
+///   * `reload_interval`
+///
+///     The interval, in seconds, at which certificate and key files are checked
+///     for changes. When any has changed, all are reloaded and new handshakes
+///     use the new certificates; established connections are unaffected. A
+///     value of `0`, the default, disables checking. Reloads can also be
+///     triggered on demand via [`TlsReloader`](crate::tls::TlsReloader).
+///
 /// Additionally, the `mutual` parameter controls if and how the server
 /// authenticates clients via mutual TLS. It works in concert with the
 /// [`mtls`](crate::mtls) module. See [`MutualTls`] for configuration details.
@@ -37,6 +56,11 @@ use indexmap::IndexSet;
 /// [default.tls]
 /// certs = "private/rsa_sha256_cert.pem"
 /// key = "private/rsa_sha256_key.pem"
+/// reload_interval = 3600
+///
+/// [default.tls.sni."api.example.com"]
+/// certs = "private/api_cert.pem"
+/// key = "private/api_key.pem"
 /// ```
 ///
 /// With a custom programmatic configuration, this might look like:
@@ -91,6 +115,12 @@ pub struct TlsConfig {
     /// Whether to prefer the server's cipher suite order over the client's.
     #[serde(default)]
     pub(crate) prefer_server_cipher_order: bool,
+    /// Certificates to serve by requested server name.
+    #[serde(default)]
+    pub(crate) sni: IndexMap<String, SniCertificate>,
+    /// Seconds between checks of certificate files for changes. `0` disables.
+    #[serde(default)]
+    pub(crate) reload_interval: u32,
     /// Configuration for mutual TLS, if any.
     #[serde(default)]
     #[cfg(feature = "mtls")]
@@ -98,6 +128,51 @@ pub struct TlsConfig {
     pub(crate) mutual: Option<MutualTls>,
 }
 
+/// A certificate chain and private key served to clients requesting a specific
+/// server name via SNI.
+///
+/// Like [`TlsConfig`]'s default pair, `certs` and `key` can each be configured
+/// as a path or as raw bytes. See [`TlsConfig#sni`](TlsConfig) for details.
+///
+/// In `Rocket.toml`, configuration might look like:
+///
+/// ```toml
+/// [default.tls.sni."example.com"]
+/// certs = "/ssl/example_certs.pem"
+/// key = "/ssl/example_key.pem"
+/// ```
+///
+/// Programmatically, configuration might look like:
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// use rocket::config::{Config, TlsConfig, SniCertificate};
+///
+/// #[launch]
+/// fn rocket() -> _ {
+///     let example = SniCertificate::from_paths("/ssl/example_certs.pem", "/ssl/example_key.pem");
+///     let tls_config = TlsConfig::from_paths("/ssl/certs.pem", "/ssl/key.pem")
+///         .with_sni("example.com", example);
+///
+///     let config = Config {
+///         tls: Some(tls_config),
+///         ..Default::default()
+///     };
+///
+///     rocket::custom(config)
+/// }
+/// ```
+#[derive(PartialEq, Debug, Clone, Deserialize, Serialize)]
+#[cfg_attr(nightly, doc(cfg(feature = "tls")))]
+pub struct SniCertificate {
+    /// Path to a PEM file with, or raw bytes for, a DER-encoded X.509 TLS
+    /// certificate chain.
+    pub(crate) certs: Either<RelativePathBuf, Vec<u8>>,
+    /// Path to a PEM file with, or raw bytes for, DER-encoded private key in
+    /// either PKCS#8 or PKCS#1 format.
+    pub(crate) key: Either<RelativePathBuf, Vec<u8>>,
+}
+
 /// Mutual TLS configuration.
 ///
 /// Configuration works in concert with the [`mtls`](crate::mtls) module, which
@@ -254,6 +329,8 @@ impl TlsConfig {
             key: Either::Right(vec![]),
             ciphers: CipherSuite::default_set(),
             prefer_server_cipher_order: false,
+            sni: IndexMap::new(),
+            reload_interval: 0,
             #[cfg(feature = "mtls")]
             mutual: None,
         }
@@ -398,6 +475,45 @@ impl TlsConfig {
         self
     }
 
+    /// Serves `cert` to clients that request the server name `name`, which may
+    /// be a wildcard of the form `*.example.com`. Names are case-insensitive.
+    /// If a certificate is already configured for `name`, it is replaced.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::config::{TlsConfig, SniCertificate};
+    ///
+    /// let api = SniCertificate::from_paths("/ssl/api_certs.pem", "/ssl/api_key.pem");
+    /// let tls_config = TlsConfig::from_paths("/ssl/certs.pem", "/ssl/key.pem")
+    ///     .with_sni("api.example.com", api);
+    ///
+    /// assert_eq!(tls_config.sni().count(), 1);
+    /// ```
+    pub fn with_sni<N: Into<String>>(mut self, name: N, cert: SniCertificate) -> Self {
+        self.sni.insert(name.into(), cert);
+        self
+    }
+
+    /// Sets the interval, in seconds, at which certificate and key files are
+    /// checked for changes and reloaded if any has changed. A value of `0`
+    /// disables checking.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::config::TlsConfig;
+    ///
+    /// let tls_config = TlsConfig::from_paths("/ssl/certs.pem", "/ssl/key.pem")
+    ///     .with_reload_interval(60);
+    ///
+    /// assert_eq!(tls_config.reload_interval(), 60);
+    /// ```
+    pub fn with_reload_interval(mut self, seconds: u32) -> Self {
+        self.reload_interval = seconds;
+        self
+    }
+
     /// Configures mutual TLS. See [`MutualTls`] for details.
     ///
     /// # Example
@@ -514,6 +630,44 @@ impl TlsConfig {
         self.prefer_server_cipher_order
     }
 
+    /// Returns an iterator over the configured server names and the
+    /// certificates served for them.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::config::{TlsConfig, SniCertificate};
+    ///
+    /// # let certs_buf = &[];
+    /// # let key_buf = &[];
+    /// let tls_config = TlsConfig::from_bytes(certs_buf, key_buf)
+    ///     .with_sni("a.example.com", SniCertificate::from_bytes(certs_buf, key_buf))
+    ///     .with_sni("*.example.com", SniCertificate::from_bytes(certs_buf, key_buf));
+    ///
+    /// let names: Vec<_> = tls_config.sni().map(|(name, _)| name).collect();
+    /// assert_eq!(names, ["a.example.com", "*.example.com"]);
+    /// ```
+    pub fn sni(&self) -> impl Iterator<Item = (&str, &SniCertificate)> + '_ {
+        self.sni.iter().map(|(name, cert)| (name.as_str(), cert))
+    }
+
+    /// Returns the interval, in seconds, at which certificate files are checked
+    /// for changes. A value of `0` indicates that checking is disabled.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::config::TlsConfig;
+    ///
+    /// # let certs_buf = &[];
+    /// # let key_buf = &[];
+    /// let tls_config = TlsConfig::from_bytes(certs_buf, key_buf);
+    /// assert_eq!(tls_config.reload_interval(), 0);
+    /// ```
+    pub fn reload_interval(&self) -> u32 {
+        self.reload_interval
+    }
+
     /// Returns the value of the `mutual` parameter.
     ///
     /// # Example
@@ -538,6 +692,83 @@ impl TlsConfig {
     }
 }
 
+impl SniCertificate {
+    /// Constructs an `SniCertificate` from paths to a `certs` certificate chain
+    /// and a `key` private key. This method does no validation.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::config::SniCertificate;
+    ///
+    /// let cert = SniCertificate::from_paths("/ssl/certs.pem", "/ssl/key.pem");
+    /// ```
+    pub fn from_paths<C, K>(certs: C, key: K) -> Self
+        where C: AsRef<std::path::Path>, K: AsRef<std::path::Path>
+    {
+        SniCertificate {
+            certs: Either::Left(certs.as_ref().to_path_buf().into()),
+            key: Either::Left(key.as_ref().to_path_buf().into()),
+        }
+    }
+
+    /// Constructs an `SniCertificate` from byte buffers to a `certs`
+    /// certificate chain and a `key` private key. This method does no
+    /// validation.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::config::SniCertificate;
+    ///
+    /// # let certs_buf = &[];
+    /// # let key_buf = &[];
+    /// let cert = SniCertificate::from_bytes(certs_buf, key_buf);
+    /// ```
+    pub fn from_bytes(certs: &[u8], key: &[u8]) -> Self {
+        SniCertificate {
+            certs: Either::Right(certs.to_vec()),
+            key: Either::Right(key.to_vec()),
+        }
+    }
+
+    /// Returns the value of the `certs` parameter.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::path::Path;
+    /// use rocket::config::SniCertificate;
+    ///
+    /// let cert = SniCertificate::from_paths("/ssl/certs.pem", "/ssl/key.pem");
+    /// assert_eq!(cert.certs().unwrap_left(), Path::new("/ssl/certs.pem"));
+    /// ```
+    pub fn certs(&self) -> either::Either<std::path::PathBuf, &[u8]> {
+        match &self.certs {
+            Either::Left(path) => either::Either::Left(path.relative()),
+            Either::Right(bytes) => either::Either::Right(&bytes),
+        }
+    }
+
+    /// Returns the value of the `key` parameter.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::path::Path;
+    /// use rocket::config::SniCertificate;
+    ///
+    /// let cert = SniCertificate::from_paths("/ssl/certs.pem", "/ssl/key.pem");
+    /// assert_eq!(cert.key().unwrap_left(), Path::new("/ssl/key.pem"));
+    /// ```
+    pub fn key(&self) -> either::Either<std::path::PathBuf, &[u8]> {
+        match &self.key {
+            Either::Left(path) => either::Either::Left(path.relative()),
+            Either::Right(bytes) => either::Either::Right(&bytes),
+        }
+    }
+}
+
 #[cfg(feature = "mtls")]
 impl MutualTls {
     /// Constructs a `MutualTls` from a path to a PEM file with a certificate
@@ -628,8 +859,11 @@ impl MutualTls {
 mod with_tls_feature {
     use std::fs;
     use std::io::{self, Error};
+    use std::path::PathBuf;
+    use std::sync::Arc;
 
-    use crate::http::tls::Config;
+    use crate::http::tls::{Config, CertResolver};
+    use crate::http::tls::rustls::sign::CertifiedKey;
     use crate::http::tls::rustls::SupportedCipherSuite as RustlsCipher;
     use crate::http::tls::rustls::cipher_suite;
 
@@ -654,12 +888,44 @@ mod with_tls_feature {
         }
     }
 
+    fn load(
+        certs: &Either<RelativePathBuf, Vec<u8>>,
+        key: &Either<RelativePathBuf, Vec<u8>>,
+    ) -> io::Result<CertifiedKey> {
+        CertResolver::load(to_reader(certs)?, to_reader(key)?)
+    }
+
     impl TlsConfig {
+        /// Loads the default certificate and the certificate for each SNI name.
+        pub(crate) fn load_certs(&self) -> io::Result<(CertifiedKey, Vec<(String, CertifiedKey)>)> {
+            let default = load(&self.certs, &self.key)?;
+            let sni = self.sni.iter()
+                .map(|(name, cert)| match load(&cert.certs, &cert.key) {
+                    Ok(key) => Ok((name.clone(), key)),
+                    Err(e) => Err(Error::new(e.kind(), format!("{} (for `{}`)", e, name))),
+                })
+                .collect::<io::Result<_>>()?;
+
+            Ok((default, sni))
+        }
+
+        /// The paths to all of the configured certificate and key files.
+        pub(crate) fn paths(&self) -> impl Iterator<Item = PathBuf> + '_ {
+            let pairs = std::iter::once((&self.certs, &self.key))
+                .chain(self.sni.values().map(|cert| (&cert.certs, &cert.key)));
+
+            pairs.flat_map(|(certs, key)| [certs, key])
+                .filter_map(|value| match value {
+                    Either::Left(path) => Some(path.relative()),
+                    Either::Right(_) => None,
+                })
+        }
+
         /// This is only called when TLS is enabled.
         pub(crate) fn to_native_config(&self) -> io::Result<Config<Reader>> {
+            let (default, sni) = self.load_certs()?;
             Ok(Config {
-                cert_chain: to_reader(&self.certs)?,
-                private_key: to_reader(&self.key)?,
+                resolver: Arc::new(CertResolver::new(default, sni)),
                 ciphersuites: self.rustls_ciphers().collect(),
                 prefer_server_order: self.prefer_server_cipher_order,
                 #[cfg(not(feature = "mtls"))]
@@ -0,0 +1,329 @@
+use std::io;
+use std::path::{Path, PathBuf};
+
+use figment::value::magic::RelativePathBuf;
+use serde::{Deserialize, Serialize};
+
+use crate::http::private::UnixListener;
+
+/// Unix domain socket configuration: socket path, permissions, and ownership.
+///
+/// When a `unix` configuration is present, Rocket binds a Unix domain socket at
+/// `path` _instead_ of a TCP socket at `address` and `port`. This is useful when
+/// Rocket is deployed behind a reverse proxy, such as nginx, running on the same
+/// host. TLS is not supported on Unix domain sockets: launch fails if either
+/// `tls` or `endpoints` is configured alongside `unix`. Five parameters control
+/// `unix` configuration:
+///
+///   * `path`
+///
+///     The path of the socket file. When a path is configured in a file, such
+///     as `Rocket.toml`, relative paths are interpreted as relative to the
+///     source file's directory. The socket file is removed when the server
+///     shuts down.
+///
+///   * `mode`
+///
+///     An optional set of Unix permission bits to apply to the socket file.
+///     Processes that connect to the socket require write permission. When not
+///     set, the permissions are determined by the process `umask`. The socket
+///     is only reachable at `path` once its permissions and ownership are set:
+///     it is first bound in a private directory beside `path`, which must thus
+///     be writable.
+///
+///   * `owner`, `group`
+///
+///     An optional numeric user ID and group ID, respectively, to set as the
+///     owner of the socket file once it is bound. Changing ownership typically
+///     requires elevated privileges.
+///
+///   * `reuse`
+///
+///     A boolean that indicates whether a _stale_ socket file at `path`, that
+///     is, a socket file no process is listening on, should be removed before
+///     binding. If a process _is_ listening on the socket, binding fails
+///     irrespective of this setting. Defaults to `true`.
+///
+/// The [`PeerCredentials`](crate::request::PeerCredentials) request guard can
+/// be used to retrieve the credentials of the connecting process.
+///
+/// In `Rocket.toml`, configuration might look like:
+///
+/// ```toml
+/// [default.unix]
+/// path = "/run/app/app.sock"
+/// mode = 0o660
+/// group = 33
+/// ```
+///
+/// When only a path is needed, `address` may be set to `unix:<path>` instead:
+///
+/// ```toml
+/// [default]
+/// address = "unix:/run/app/app.sock"
+/// ```
+///
+/// With a custom programmatic configuration, this might look like:
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// use rocket::config::{Config, UnixConfig};
+///
+/// #[launch]
+/// fn rocket() -> _ {
+///     let unix_config = UnixConfig::from_path("/run/app/app.sock")
+///         .with_mode(0o660)
+///         .with_group(33);
+///
+///     let config = Config {
+///         unix: Some(unix_config),
+///         ..Default::default()
+///     };
+///
+///     rocket::custom(config)
+/// }
+/// ```
+///
+/// Or by creating a custom figment:
+///
+/// ```rust
+/// use rocket::config::Config;
+///
+/// let figment = Config::figment()
+///     .merge(("unix.path", "/run/app/app.sock"))
+///     .merge(("unix.mode", 0o600));
+/// #
+/// # let config = rocket::Config::from(figment);
+/// # let unix_config = config.unix.as_ref().unwrap();
+/// # assert_eq!(unix_config.path(), std::path::Path::new("/run/app/app.sock"));
+/// # assert_eq!(unix_config.mode(), Some(0o600));
+/// # assert!(unix_config.reuse());
+/// ```
+#[derive(PartialEq, Debug, Clone, Deserialize, Serialize)]
+#[cfg_attr(nightly, doc(cfg(unix)))]
+pub struct UnixConfig {
+    /// Path to the socket file.
+    pub(crate) path: RelativePathBuf,
+    /// Permission bits to set on the socket file, if any.
+    #[serde(default)]
+    pub(crate) mode: Option<u32>,
+    /// Numeric ID of the user to set as the socket file's owner, if any.
+    #[serde(default)]
+    pub(crate) owner: Option<u32>,
+    /// Numeric ID of the group to set as the socket file's group, if any.
+    #[serde(default)]
+    pub(crate) group: Option<u32>,
+    /// Whether to remove a stale socket file at `path` before binding.
+    #[serde(default = "default_reuse")]
+    #[serde(deserialize_with = "figment::util::bool_from_str_or_int")]
+    pub(crate) reuse: bool,
+}
+
+fn default_reuse() -> bool {
+    true
+}
+
+impl UnixConfig {
+    /// Constructs a `UnixConfig` that binds a socket at `path`. Permissions
+    /// and ownership are left unchanged and stale sockets are removed.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::config::UnixConfig;
+    ///
+    /// let unix_config = UnixConfig::from_path("/run/app.sock");
+    /// assert_eq!(unix_config.mode(), None);
+    /// assert!(unix_config.reuse());
+    /// ```
+    pub fn from_path<P: AsRef<std::path::Path>>(path: P) -> Self {
+        UnixConfig {
+            path: path.as_ref().to_path_buf().into(),
+            mode: None,
+            owner: None,
+            group: None,
+            reuse: default_reuse(),
+        }
+    }
+
+    /// Sets the permission bits to apply to the socket file to `mode`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::config::UnixConfig;
+    ///
+    /// let unix_config = UnixConfig::from_path("/run/app.sock").with_mode(0o660);
+    /// assert_eq!(unix_config.mode(), Some(0o660));
+    /// ```
+    pub fn with_mode(mut self, mode: u32) -> Self {
+        self.mode = Some(mode);
+        self
+    }
+
+    /// Sets the numeric ID of the user that owns the socket file to `uid`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::config::UnixConfig;
+    ///
+    /// let unix_config = UnixConfig::from_path("/run/app.sock").with_owner(1000);
+    /// assert_eq!(unix_config.owner(), Some(1000));
+    /// ```
+    pub fn with_owner(mut self, uid: u32) -> Self {
+        self.owner = Some(uid);
+        self
+    }
+
+    /// Sets the numeric ID of the group that owns the socket file to `gid`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::config::UnixConfig;
+    ///
+    /// let unix_config = UnixConfig::from_path("/run/app.sock").with_group(33);
+    /// assert_eq!(unix_config.group(), Some(33));
+    /// ```
+    pub fn with_group(mut self, gid: u32) -> Self {
+        self.group = Some(gid);
+        self
+    }
+
+    /// Sets whether a stale socket file at the configured path is removed
+    /// before binding.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::config::UnixConfig;
+    ///
+    /// let unix_config = UnixConfig::from_path("/run/app.sock").with_reuse(false);
+    /// assert!(!unix_config.reuse());
+    /// ```
+    pub fn with_reuse(mut self, reuse: bool) -> Self {
+        self.reuse = reuse;
+        self
+    }
+
+    /// Returns the path of the socket file.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::path::Path;
+    /// use rocket::config::UnixConfig;
+    ///
+    /// let unix_config = UnixConfig::from_path("/run/app.sock");
+    /// assert_eq!(unix_config.path(), Path::new("/run/app.sock"));
+    /// ```
+    pub fn path(&self) -> PathBuf {
+        self.path.relative()
+    }
+
+    /// Returns the permission bits applied to the socket file, if any.
+    pub fn mode(&self) -> Option<u32> {
+        self.mode
+    }
+
+    /// Returns the numeric ID of the socket file's owner, if any.
+    pub fn owner(&self) -> Option<u32> {
+        self.owner
+    }
+
+    /// Returns the numeric ID of the socket file's group, if any.
+    pub fn group(&self) -> Option<u32> {
+        self.group
+    }
+
+    /// Returns whether a stale socket file is removed before binding.
+    pub fn reuse(&self) -> bool {
+        self.reuse
+    }
+
+    /// Binds a Unix domain socket according to `self`.
+    pub(crate) fn bind(&self) -> io::Result<UnixListener> {
+        use std::os::unix::fs::FileTypeExt;
+
+        let path = self.path();
+        let is_socket = std::fs::symlink_metadata(&path)
+            .map_or(false, |metadata| metadata.file_type().is_socket());
+
+        // A socket file nobody is listening on is left behind by a process
+        // that didn't exit cleanly. Connecting is the only reliable check.
+        if self.reuse && is_socket {
+            if let Err(e) = std::os::unix::net::UnixStream::connect(&path) {
+                if e.kind() == io::ErrorKind::ConnectionRefused {
+                    warn!("removing stale unix socket file `{}`", path.display());
+                    std::fs::remove_file(&path)?;
+                }
+            }
+        }
+
+        let result = match (self.mode, self.owner, self.group) {
+            (None, None, None) => UnixListener::bind(&path),
+            _ => self.bind_private(&path),
+        };
+
+        result.map_err(|e| {
+            io::Error::new(e.kind(), format!("failed to bind `{}`: {}", path.display(), e))
+        })
+    }
+
+    /// Binds a socket at `path` that is only reachable once its permissions
+    /// and ownership are set: the socket is bound in a private directory
+    /// beside `path`, adjusted there, then linked into place. Linking, unlike
+    /// renaming, fails if `path` exists, as binding would.
+    fn bind_private(&self, path: &Path) -> io::Result<UnixListener> {
+        use std::os::unix::fs::{DirBuilderExt, PermissionsExt};
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        static NEXT: AtomicUsize = AtomicUsize::new(0);
+
+        let parent = match path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => Path::new("."),
+        };
+
+        // Keep the name short: socket paths are limited to ~100 bytes.
+        let n = NEXT.fetch_add(1, Ordering::Relaxed);
+        let dir = parent.join(format!(".rocket-{}-{}", std::process::id(), n));
+        std::fs::DirBuilder::new().mode(0o700).create(&dir)?;
+
+        let temp = dir.join("s");
+        let result = UnixListener::bind(&temp).and_then(|listener| {
+            if let Some(mode) = self.mode {
+                std::fs::set_permissions(&temp, std::fs::Permissions::from_mode(mode))?;
+            }
+
+            if self.owner.is_some() || self.group.is_some() {
+                chown(&temp, self.owner, self.group)?;
+            }
+
+            std::fs::hard_link(&temp, path)?;
+            Ok(listener)
+        });
+
+        let _ = std::fs::remove_file(&temp);
+        let _ = std::fs::remove_dir(&dir);
+        result
+    }
+}
+
+/// Sets the owner and group of `path`, leaving either unchanged if `None`.
+///
+/// `std::os::unix::fs::chown()` is only available since Rust 1.73.
+fn chown(path: &Path, owner: Option<u32>, group: Option<u32>) -> io::Result<()> {
+    use std::os::unix::ffi::OsStrExt;
+
+    let path = std::ffi::CString::new(path.as_os_str().as_bytes())?;
+
+    // An ID of `-1`, i.e, all bits set, leaves the ID unchanged.
+    let owner = owner.map_or(libc::uid_t::MAX, |uid| uid as libc::uid_t);
+    let group = group.map_or(libc::gid_t::MAX, |gid| gid as libc::gid_t);
+    match unsafe { libc::chown(path.as_ptr(), owner, group) } {
+        0 => Ok(()),
+        _ => Err(io::Error::last_os_error()),
+    }
+}
@@ -0,0 +1,566 @@
+use std::{fmt, io};
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::sync::{Arc, Weak};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+use futures::future::{poll_fn, Future};
+use pin_project_lite::pin_project;
+use state::Storage;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, ReadBuf};
+use tokio::sync::mpsc;
+use tokio::time::{sleep, Sleep};
+
+use crate::{Rocket, Orbit};
+use crate::http::private::{Listener, Connection, Certificates, PeerCredentials, ProxyHeader};
+use crate::http::private::{CertificateData, TlsInfo};
+use crate::request::{FromRequest, Outcome, Request};
+use crate::http::Status;
+
+/// An identifier for a connection, unique among the connections accepted by a
+/// running application.
+///
+/// Identifiers are assigned sequentially as connections are accepted. Every
+/// request received on a connection is tagged with the connection's
+/// identifier, retrievable via the `ConnectionId` request guard. The guard
+/// forwards with a 404 Not Found status if the request wasn't received on a
+/// connection, as is the case for local requests.
+///
+/// # Example
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// use rocket::listener::ConnectionId;
+///
+/// #[get("/")]
+/// fn index(id: ConnectionId) -> String {
+///     format!("Hello from connection {}!", id)
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ConnectionId(u64);
+
+/// Information about an accepted connection.
+///
+/// A `ConnectionInfo` is passed to [connection
+/// fairings](crate::fairing::Fairing#connect) and can be retrieved in a handler
+/// via the `&ConnectionInfo` request guard, which forwards with a 404 Not Found
+/// status if the request wasn't received on a connection.
+///
+/// The byte counts reflect the data exchanged on the connection so far. When
+/// passed to [`Fairing::on_disconnect()`](crate::fairing::Fairing::on_disconnect()),
+/// they are final, and [`ConnectionInfo::duration()`] is the connection's
+/// lifetime.
+///
+/// # Example
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// use rocket::listener::ConnectionInfo;
+///
+/// #[get("/")]
+/// fn index(conn: &ConnectionInfo) -> String {
+///     format!("connection {} has read {} bytes", conn.id(), conn.bytes_read())
+/// }
+/// ```
+pub struct ConnectionInfo {
+    id: ConnectionId,
+    remote: Option<SocketAddr>,
+    certificates: Option<Certificates>,
+    tls: Storage<TlsInfo>,
+    started: Instant,
+    read: AtomicU64,
+    written: AtomicU64,
+}
+
+/// The response written to connections accepted beyond `max_connections`.
+const UNAVAILABLE: &[u8] = b"HTTP/1.1 503 Service Unavailable\r\n\
+    connection: close\r\ncontent-length: 0\r\n\r\n";
+
+/// The response written to connections that time out sending headers.
+const TIMED_OUT: &[u8] = b"HTTP/1.1 408 Request Timeout\r\n\
+    connection: close\r\ncontent-length: 0\r\n\r\n";
+
+pin_project! {
+    /// A listener that enforces connection-level limits: the maximum number of
+    /// concurrent connections and the time allotted to send request headers.
+    ///
+    /// When connection fairings are attached, the listener also completes any
+    /// handshake and runs the fairings before yielding a connection.
+    pub struct LimitedListener<L: Listener> {
+        #[pin]
+        listener: L,
+        counters: Arc<Counters>,
+        max_connections: usize,
+        header_timeout: Option<Duration>,
+        fairings: Option<Fairings<L::Connection>>,
+    }
+}
+
+/// Connection counters shared by every [`LimitedListener`] of an application so
+/// that limits are global and identifiers are unique across listeners.
+#[derive(Debug, Default)]
+pub(crate) struct Counters {
+    /// The number of open connections.
+    active: AtomicUsize,
+    /// The identifier of the next connection to be accepted.
+    next_id: AtomicU64,
+}
+
+/// The state needed to run connection fairings in the background.
+struct Fairings<C> {
+    rocket: Weak<Rocket<Orbit>>,
+    tx: mpsc::UnboundedSender<LimitedIo<C>>,
+    rx: mpsc::UnboundedReceiver<LimitedIo<C>>,
+}
+
+/// A connection accepted by a [`LimitedListener`].
+pub struct LimitedIo<C> {
+    io: C,
+    info: Arc<ConnectionInfo>,
+    counters: Arc<Counters>,
+    header_timeout: Duration,
+    head: Head,
+    tls_checked: bool,
+    /// Set once connect fairings have run; disconnect fairings run on drop.
+    rocket: Option<Weak<Rocket<Orbit>>>,
+}
+
+/// The state of an HTTP/1 request head on a connection.
+enum Head {
+    /// Headers aren't timed: there's no timeout or this isn't HTTP/1.
+    Untimed,
+    /// Reading a head that must be complete before `deadline`. `read` is the
+    /// number of bytes read so far; `tail` holds the last bytes read.
+    Reading { deadline: Pin<Box<Sleep>>, read: usize, tail: [u8; 3] },
+    /// A head was read; waiting for the response to it.
+    Read,
+    /// A response was written; the next read begins a new head.
+    Responded,
+    /// The deadline passed. Writing `TIMED_OUT` from the given offset.
+    TimedOut(usize),
+}
+
+impl ConnectionId {
+    /// Returns the identifier as an integer.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::listener::ConnectionId;
+    ///
+    /// fn log(id: ConnectionId) {
+    ///     println!("connection #{}", id.get());
+    /// }
+    /// ```
+    pub fn get(self) -> u64 {
+        self.0
+    }
+}
+
+impl fmt::Display for ConnectionId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl ConnectionInfo {
+    fn new<C: Connection>(id: ConnectionId, io: &C) -> Self {
+        ConnectionInfo {
+            id,
+            remote: io.peer_address(),
+            certificates: io.peer_certificates(),
+            tls: Storage::new(),
+            started: Instant::now(),
+            read: AtomicU64::new(0),
+            written: AtomicU64::new(0),
+        }
+    }
+
+    /// The connection's identifier.
+    pub fn id(&self) -> ConnectionId {
+        self.id
+    }
+
+    /// The address of the remote end of the connection, if known. When a
+    /// [`ProxyHeader`] was received, this is the address it conveyed.
+    pub fn remote(&self) -> Option<SocketAddr> {
+        self.remote
+    }
+
+    /// Information about the connection's TLS session, if the connection is
+    /// secured by TLS. Always available to connection fairings.
+    pub fn tls(&self) -> Option<&TlsInfo> {
+        self.tls.try_get()
+    }
+
+    /// The DER-encoded certificate chain presented by the client, if any. A
+    /// chain is only requested when mutual TLS is configured.
+    pub fn peer_certificates(&self) -> Option<&[CertificateData]> {
+        self.certificates.as_ref()?.chain_data()
+    }
+
+    /// The instant the connection was accepted.
+    pub fn started(&self) -> Instant {
+        self.started
+    }
+
+    /// The time elapsed since the connection was accepted.
+    pub fn duration(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    /// The number of bytes read from the connection so far.
+    pub fn bytes_read(&self) -> u64 {
+        self.read.load(Ordering::Relaxed)
+    }
+
+    /// The number of bytes written to the connection so far.
+    pub fn bytes_written(&self) -> u64 {
+        self.written.load(Ordering::Relaxed)
+    }
+}
+
+impl fmt::Debug for ConnectionInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ConnectionInfo")
+            .field("id", &self.id)
+            .field("remote", &self.remote)
+            .field("tls", &self.tls())
+            .field("duration", &self.duration())
+            .field("bytes_read", &self.bytes_read())
+            .field("bytes_written", &self.bytes_written())
+            .finish()
+    }
+}
+
+impl<L: Listener> LimitedListener<L> {
+    pub fn new(listener: L, rocket: &Arc<Rocket<Orbit>>) -> Self {
+        let fairings = rocket.fairings.has_connection().then(|| {
+            let (tx, rx) = mpsc::unbounded_channel();
+            Fairings { rocket: Arc::downgrade(rocket), tx, rx }
+        });
+
+        LimitedListener {
+            listener,
+            fairings,
+            max_connections: rocket.config.max_connections,
+            counters: rocket.connections.clone(),
+            header_timeout: match rocket.config.header_timeout {
+                0 => None,
+                n => Some(Duration::from_secs(n.into())),
+            },
+        }
+    }
+}
+
+/// Writes `response` to `io` and closes it, lingering briefly so that the
+/// response isn't lost to a reset caused by unread request data.
+async fn reject<C: AsyncRead + AsyncWrite + Unpin>(mut io: C, response: &[u8]) {
+    if io.write_all(response).await.is_err() || io.shutdown().await.is_err() {
+        return;
+    }
+
+    let mut buf = [0; 1024];
+    let drain = async { while let Ok(1..) = io.read(&mut buf).await { } };
+    let _ = tokio::time::timeout(Duration::from_secs(1), drain).await;
+}
+
+/// Completes `io`'s handshake, then runs connect fairings, sending `io` to `tx`
+/// if they all accept it.
+async fn connect<C: Connection + Unpin>(
+    mut io: LimitedIo<C>,
+    rocket: Weak<Rocket<Orbit>>,
+    tx: mpsc::UnboundedSender<LimitedIo<C>>,
+) {
+    let handshake = poll_fn(|cx| Pin::new(&mut io.io).poll_handshake(cx));
+    let result = match io.header_timeout {
+        Duration::ZERO => handshake.await,
+        timeout => tokio::time::timeout(timeout, handshake).await
+            .unwrap_or_else(|_| Err(io::Error::new(io::ErrorKind::TimedOut, "handshake"))),
+    };
+
+    if let Err(e) = result {
+        debug_!("Handshake with {:?} failed: {}", io.info.remote, e);
+        return;
+    }
+
+    io.check_tls();
+    let accepted = match rocket.upgrade() {
+        Some(rocket) => {
+            io.rocket = Some(Arc::downgrade(&rocket));
+            rocket.fairings.handle_connect(&io.info).await
+        }
+        None => false,
+    };
+
+    if accepted {
+        let _ = tx.send(io);
+    } else {
+        debug_!("Connection {} from {:?} rejected.", io.info.id, io.info.remote);
+    }
+}
+
+impl<L> Listener for LimitedListener<L>
+    where L: Listener, L::Connection: Send + Unpin + 'static
+{
+    type Connection = LimitedIo<L::Connection>;
+
+    fn local_addr(&self) -> Option<std::net::SocketAddr> {
+        self.listener.local_addr()
+    }
+
+    fn poll_accept(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>
+    ) -> Poll<io::Result<Self::Connection>> {
+        let mut me = self.project();
+        if let Some(fairings) = me.fairings.as_mut() {
+            if let Poll::Ready(Some(io)) = fairings.rx.poll_recv(cx) {
+                return Poll::Ready(Ok(io));
+            }
+        }
+
+        loop {
+            let io = futures::ready!(me.listener.as_mut().poll_accept(cx))?;
+            let active = me.counters.active.load(Ordering::Acquire);
+            if *me.max_connections != 0 && active >= *me.max_connections {
+                debug_!("Connection limit reached. Rejecting {:?}.", io.peer_address());
+                tokio::spawn(reject(io, UNAVAILABLE));
+                continue;
+            }
+
+            me.counters.active.fetch_add(1, Ordering::AcqRel);
+            let (header_timeout, head) = match *me.header_timeout {
+                Some(timeout) => (timeout, Head::reading(timeout)),
+                None => (Duration::ZERO, Head::Untimed),
+            };
+
+            let id = ConnectionId(me.counters.next_id.fetch_add(1, Ordering::Relaxed));
+
+            let io = LimitedIo {
+                info: Arc::new(ConnectionInfo::new(id, &io)),
+                counters: me.counters.clone(),
+                tls_checked: false,
+                rocket: None,
+                io, header_timeout, head,
+            };
+
+            match me.fairings {
+                Some(fairings) => {
+                    tokio::spawn(connect(io, fairings.rocket.clone(), fairings.tx.clone()));
+                }
+                None => return Poll::Ready(Ok(io)),
+            }
+        }
+    }
+}
+
+impl<C: Connection> LimitedIo<C> {
+    /// Information about this connection.
+    pub fn info(&self) -> &Arc<ConnectionInfo> {
+        &self.info
+    }
+
+    /// Records TLS session information, if any, once the handshake completes.
+    fn check_tls(&mut self) {
+        if !self.tls_checked {
+            self.tls_checked = true;
+            if let Some(tls) = self.io.tls_info() {
+                self.info.tls.set(tls);
+            }
+        }
+    }
+}
+
+impl Head {
+    fn reading(timeout: Duration) -> Self {
+        Head::Reading { deadline: Box::pin(sleep(timeout)), read: 0, tail: [0; 3] }
+    }
+
+    /// Updates the state after `data` is read. A new head, begun by reading
+    /// after a response, must be read within `timeout`.
+    fn on_read(&mut self, data: &[u8], timeout: Duration) {
+        if matches!(self, Head::Responded) && !data.is_empty() {
+            *self = Head::reading(timeout);
+        }
+
+        if let Head::Reading { read, tail, .. } = self {
+            // HTTP/2 connections begin with the preface `PRI * HTTP/2.0`.
+            if *read == 0 && data.starts_with(b"PRI ") {
+                *self = Head::Untimed;
+                return;
+            }
+
+            let mut window = tail.to_vec();
+            window.extend_from_slice(data);
+            let complete = window.windows(4).any(|w| w == b"\r\n\r\n")
+                || window.windows(2).any(|w| w == b"\n\n");
+
+            if complete {
+                *self = Head::Read;
+            } else {
+                *read += data.len();
+                let start = window.len().saturating_sub(3);
+                let end = &window[start..];
+                tail[3 - end.len()..].copy_from_slice(end);
+            }
+        }
+    }
+
+    /// Updates the state before `data` is written.
+    fn on_write(&mut self, data: &[u8]) {
+        // A response head that isn't informational (`1xx`) ends a request.
+        let is_final_head = data.starts_with(b"HTTP/1.")
+            && data.get(9).map_or(false, |&b| b != b'1');
+
+        if matches!(self, Head::Read) && is_final_head {
+            *self = Head::Responded;
+        }
+    }
+}
+
+impl<C: Connection + Unpin> AsyncRead for LimitedIo<C> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = &mut *self;
+        if let Head::Reading { deadline, read, .. } = &mut this.head {
+            if deadline.as_mut().poll(cx).is_ready() {
+                // An idle connection is simply closed.
+                if *read == 0 {
+                    return Poll::Ready(Ok(()));
+                }
+
+                this.head = Head::TimedOut(0);
+            }
+        }
+
+        if let Head::TimedOut(written) = &mut this.head {
+            while *written < TIMED_OUT.len() {
+                let data = &TIMED_OUT[*written..];
+                *written += futures::ready!(Pin::new(&mut this.io).poll_write(cx, data))?;
+            }
+
+            futures::ready!(Pin::new(&mut this.io).poll_shutdown(cx))?;
+            let msg = "timed out reading request headers";
+            return Poll::Ready(Err(io::Error::new(io::ErrorKind::TimedOut, msg)));
+        }
+
+        let filled = buf.filled().len();
+        futures::ready!(Pin::new(&mut this.io).poll_read(cx, buf))?;
+        let data = &buf.filled()[filled..];
+        this.info.read.fetch_add(data.len() as u64, Ordering::Relaxed);
+        this.head.on_read(data, this.header_timeout);
+        this.check_tls();
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<C: Connection + Unpin> AsyncWrite for LimitedIo<C> {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        self.head.on_write(buf);
+        let n = futures::ready!(Pin::new(&mut self.io).poll_write(cx, buf))?;
+        self.info.written.fetch_add(n as u64, Ordering::Relaxed);
+        Poll::Ready(Ok(n))
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.io).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.io).poll_shutdown(cx)
+    }
+
+    fn poll_write_vectored(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[io::IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        if let Some(buf) = bufs.iter().find(|buf| !buf.is_empty()) {
+            self.head.on_write(buf);
+        }
+
+        let n = futures::ready!(Pin::new(&mut self.io).poll_write_vectored(cx, bufs))?;
+        self.info.written.fetch_add(n as u64, Ordering::Relaxed);
+        Poll::Ready(Ok(n))
+    }
+
+    fn is_write_vectored(&self) -> bool {
+        self.io.is_write_vectored()
+    }
+}
+
+impl<C: Connection + Unpin> Connection for LimitedIo<C> {
+    fn peer_address(&self) -> Option<std::net::SocketAddr> {
+        self.io.peer_address()
+    }
+
+    fn enable_nodelay(&self) -> io::Result<()> {
+        self.io.enable_nodelay()
+    }
+
+    fn peer_certificates(&self) -> Option<Certificates> {
+        self.io.peer_certificates()
+    }
+
+    fn peer_credentials(&self) -> Option<PeerCredentials> {
+        self.io.peer_credentials()
+    }
+
+    fn proxy_header(&self) -> Option<Arc<ProxyHeader>> {
+        self.io.proxy_header()
+    }
+
+    fn tls_info(&self) -> Option<TlsInfo> {
+        self.io.tls_info()
+    }
+
+    fn poll_handshake(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.io).poll_handshake(cx)
+    }
+}
+
+impl<C> Drop for LimitedIo<C> {
+    fn drop(&mut self) {
+        self.counters.active.fetch_sub(1, Ordering::AcqRel);
+        let rocket = self.rocket.take().and_then(|rocket| rocket.upgrade());
+        if let (Some(rocket), Ok(runtime)) = (rocket, tokio::runtime::Handle::try_current()) {
+            let info = self.info.clone();
+            runtime.spawn(async move { rocket.fairings.handle_disconnect(&info).await });
+        }
+    }
+}
+
+#[crate::async_trait]
+impl<'r> FromRequest<'r> for &'r ConnectionInfo {
+    type Error = std::convert::Infallible;
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        match request.connection.info {
+            Some(ref info) => Outcome::Success(info),
+            None => Outcome::Forward(Status::NotFound)
+        }
+    }
+}
+
+#[crate::async_trait]
+impl<'r> FromRequest<'r> for ConnectionId {
+    type Error = std::convert::Infallible;
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        match request.connection.info {
+            Some(ref info) => Outcome::Success(info.id),
+            None => Outcome::Forward(Status::NotFound)
+        }
+    }
+}
@@ -65,6 +65,41 @@ impl<'r> Data<'r> {
         }
     }
 
+    /// Replaces the body with the result of undoing `encodings`, listed in the
+    /// order they were applied. Reads from the returned stream, including
+    /// peeks, yield decoded data.
+    #[cfg(feature = "compression")]
+    pub(crate) fn decode(&mut self, encodings: &[crate::compression::Encoding]) {
+        use std::io::Cursor;
+        use std::pin::Pin;
+        use tokio::io::{AsyncRead, BufReader};
+        use async_compression::tokio::bufread::*;
+        use crate::compression::Encoding;
+        use crate::ext::Chain;
+
+        let buffer = std::mem::take(&mut self.buffer);
+        let stream = std::mem::replace(&mut self.stream, StreamReader::empty());
+        let mut reader: Pin<Box<dyn AsyncRead + Send + Sync + 'r>> =
+            Box::pin(Chain::new(Cursor::new(buffer), stream));
+
+        for encoding in encodings.iter().rev() {
+            let inner = BufReader::new(reader);
+            reader = match encoding {
+                Encoding::Brotli => Box::pin(BrotliDecoder::new(inner)),
+                Encoding::Zstd => Box::pin(ZstdDecoder::new(inner)),
+                Encoding::Deflate => Box::pin(ZlibDecoder::new(inner)),
+                Encoding::Gzip => {
+                    let mut decoder = GzipDecoder::new(inner);
+                    decoder.multiple_members(true);
+                    Box::pin(decoder)
+                }
+            };
+        }
+
+        self.stream = StreamReader::from(reader);
+        self.is_complete = false;
+    }
+
     /// Returns the raw data stream, limited to `limit` bytes.
     ///
     /// The stream contains all of the data in the body of the request,
@@ -60,7 +60,17 @@ enum State {
 enum StreamKind<'r> {
     Empty,
     Body(&'r mut hyper::Body),
-    Multipart(multer::Field<'r>)
+    Multipart(multer::Field<'r>),
+    #[cfg(feature = "compression")]
+    Decoded(Decoded<'r>),
+}
+
+/// A decoded request body. Unlike the underlying stream, it keeps failing after
+/// an error so that an error encountered while peeking isn't lost.
+#[cfg(feature = "compression")]
+struct Decoded<'r> {
+    stream: tokio_util::io::ReaderStream<Pin<Box<dyn AsyncRead + Send + Sync + 'r>>>,
+    error: Option<(io::ErrorKind, String)>,
 }
 
 impl<'r> DataStream<'r> {
@@ -246,6 +256,15 @@ impl<'r> From<multer::Field<'r>> for StreamReader<'r> {
     }
 }
 
+#[cfg(feature = "compression")]
+impl<'r> From<Pin<Box<dyn AsyncRead + Send + Sync + 'r>>> for StreamReader<'r> {
+    fn from(reader: Pin<Box<dyn AsyncRead + Send + Sync + 'r>>) -> Self {
+        let stream = tokio_util::io::ReaderStream::new(reader);
+        let decoded = Decoded { stream, error: None };
+        Self { inner: StreamKind::Decoded(decoded), state: State::Pending }
+    }
+}
+
 impl AsyncRead for DataStream<'_> {
     #[inline(always)]
     fn poll_read(
@@ -259,6 +278,8 @@ impl AsyncRead for DataStream<'_> {
                 StreamKind::Empty => "an empty stream (vacuous)",
                 StreamKind::Body(_) => "the request body",
                 StreamKind::Multipart(_) => "a multipart form field",
+                #[cfg(feature = "compression")]
+                StreamKind::Decoded(_) => "the decoded request body",
             };
 
             let msg = yansi::Paint::default(kind).bold();
@@ -281,6 +302,19 @@ impl Stream for StreamKind<'_> {
                 .map_err_ext(|e| io::Error::new(io::ErrorKind::Other, e)),
             StreamKind::Multipart(mp) => Pin::new(mp).poll_next(cx)
                 .map_err_ext(|e| io::Error::new(io::ErrorKind::Other, e)),
+            #[cfg(feature = "compression")]
+            StreamKind::Decoded(decoded) => {
+                if let Some((kind, ref msg)) = decoded.error {
+                    return Poll::Ready(Some(Err(io::Error::new(kind, msg.clone()))));
+                }
+
+                let next = ready!(Pin::new(&mut decoded.stream).poll_next(cx));
+                if let Some(Err(ref e)) = next {
+                    decoded.error = Some((e.kind(), e.to_string()));
+                }
+
+                Poll::Ready(next)
+            }
             StreamKind::Empty => Poll::Ready(None),
         }
     }
@@ -289,6 +323,8 @@ impl Stream for StreamKind<'_> {
         match self {
             StreamKind::Body(body) => body.size_hint(),
             StreamKind::Multipart(mp) => mp.size_hint(),
+            #[cfg(feature = "compression")]
+            StreamKind::Decoded(decoded) => decoded.stream.size_hint(),
             StreamKind::Empty => (0, Some(0)),
         }
     }
@@ -63,10 +63,15 @@ use crate::http::uncased::Uncased;
 /// | `bytes`           | 8KiB    | [`Vec<u8>`]  | data guard                            |
 /// | `json`            | 1MiB    | [`Json`]     | JSON data and form payloads           |
 /// | `msgpack`         | 1MiB    | [`MsgPack`]  | MessagePack data and form payloads    |
+/// | `cbor`            | 1MiB    | [`Cbor`]     | CBOR data and form payloads           |
+/// | `ndjson`          | 8MiB    | [`Lines`]    | entire newline-delimited JSON stream  |
+/// | `ndjson/line`     | 1MiB    | [`Lines`]    | each line of a `ndjson` stream        |
 ///
 /// [`TempFile`]: crate::fs::TempFile
 /// [`Json`]: crate::serde::json::Json
 /// [`MsgPack`]: crate::serde::msgpack::MsgPack
+/// [`Cbor`]: crate::serde::cbor::Cbor
+/// [`Lines`]: crate::serde::json::Lines
 ///
 /// # Usage
 ///
@@ -137,6 +142,9 @@ impl Default for Limits {
             .limit("bytes", Limits::BYTES)
             .limit("json", Limits::JSON)
             .limit("msgpack", Limits::MESSAGE_PACK)
+            .limit("cbor", Limits::CBOR)
+            .limit("ndjson", Limits::NDJSON)
+            .limit("ndjson/line", Limits::NDJSON_LINE)
     }
 }
 
@@ -162,6 +170,15 @@ impl Limits {
     /// Default limit for MessagePack payloads.
     pub const MESSAGE_PACK: ByteUnit = ByteUnit::Mebibyte(1);
 
+    /// Default limit for CBOR payloads.
+    pub const CBOR: ByteUnit = ByteUnit::Mebibyte(1);
+
+    /// Default limit for newline-delimited JSON streams.
+    pub const NDJSON: ByteUnit = ByteUnit::Mebibyte(8);
+
+    /// Default limit for each line of a newline-delimited JSON stream.
+    pub const NDJSON_LINE: ByteUnit = ByteUnit::Mebibyte(1);
+
     /// Construct a new `Limits` structure with no limits set.
     ///
     /// # Example
@@ -265,7 +265,8 @@ impl<F: Future, I: AsyncWrite> AsyncWrite for CancellableIo<F, I> {
     }
 }
 
-use crate::http::private::{Listener, Connection, Certificates};
+use crate::http::private::{Listener, Connection, Certificates, PeerCredentials, ProxyHeader};
+use crate::http::private::TlsInfo;
 
 impl<F: Future, C: Connection> Connection for CancellableIo<F, C> {
     fn peer_address(&self) -> Option<std::net::SocketAddr> {
@@ -276,6 +277,22 @@ impl<F: Future, C: Connection> Connection for CancellableIo<F, C> {
         self.io().and_then(|io| io.peer_certificates())
     }
 
+    fn peer_credentials(&self) -> Option<PeerCredentials> {
+        self.io().and_then(|io| io.peer_credentials())
+    }
+
+    fn proxy_header(&self) -> Option<std::sync::Arc<ProxyHeader>> {
+        self.io().and_then(|io| io.proxy_header())
+    }
+
+    fn tls_info(&self) -> Option<TlsInfo> {
+        self.io().and_then(|io| io.tls_info())
+    }
+
+    fn poll_handshake(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.poll_trigger_then(cx, |io, cx| io.poll_handshake(cx))
+    }
+
     fn enable_nodelay(&self) -> io::Result<()> {
         match self.io() {
             Some(io) => io.enable_nodelay(),
@@ -3,6 +3,7 @@ use parking_lot::Mutex;
 
 use crate::{Rocket, Request, Response, Data, Build, Orbit};
 use crate::fairing::{Fairing, Kind, Info, Result};
+use crate::listener::ConnectionInfo;
 
 /// A ad-hoc fairing that can be created from a function or closure.
 ///
@@ -11,9 +12,10 @@ use crate::fairing::{Fairing, Kind, Info, Result};
 ///
 /// # Usage
 ///
-/// Use [`AdHoc::on_ignite`], [`AdHoc::on_liftoff`], [`AdHoc::on_request()`], or
-/// [`AdHoc::on_response()`] to create an `AdHoc` structure from a function or
-/// closure. Then, simply attach the structure to the `Rocket` instance.
+/// Use [`AdHoc::on_ignite`], [`AdHoc::on_liftoff`], [`AdHoc::on_request()`],
+/// [`AdHoc::on_response()`], [`AdHoc::on_shutdown()`], [`AdHoc::on_connect()`],
+/// or [`AdHoc::on_disconnect()`] to create an `AdHoc` structure from a function
+/// or closure. Then, simply attach the structure to the `Rocket` instance.
 ///
 /// # Example
 ///
@@ -68,6 +70,14 @@ enum AdHocKind {
 
     /// An ad-hoc **shutdown** fairing. Called on shutdown.
     Shutdown(Once<dyn for<'a> FnOnce(&'a Rocket<Orbit>) -> BoxFuture<'a, ()> + Send + 'static>),
+
+    /// An ad-hoc **connect** fairing. Called when a connection is accepted.
+    Connect(Box<dyn for<'a> Fn(&'a ConnectionInfo) -> BoxFuture<'a, Result<(), ()>>
+        + Send + Sync + 'static>),
+
+    /// An ad-hoc **disconnect** fairing. Called when a connection closes.
+    Disconnect(Box<dyn for<'a> Fn(&'a ConnectionInfo) -> BoxFuture<'a, ()>
+        + Send + Sync + 'static>),
 }
 
 impl AdHoc {
@@ -204,6 +214,54 @@ impl AdHoc {
         AdHoc { name, kind: AdHocKind::Shutdown(Once::new(Box::new(f))) }
     }
 
+    /// Constructs an `AdHoc` connect fairing named `name`. The function `f`
+    /// will be called and the returned `Future` will be `await`ed by Rocket
+    /// when a connection is accepted. Returning an `Err` rejects the
+    /// connection.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::net::{IpAddr, Ipv4Addr};
+    /// use rocket::fairing::AdHoc;
+    ///
+    /// // A fairing that rejects connections from a denied address.
+    /// const DENIED: IpAddr = IpAddr::V4(Ipv4Addr::new(192, 0, 2, 1));
+    ///
+    /// let fairing = AdHoc::on_connect("Deny List", |conn| Box::pin(async move {
+    ///     match conn.remote() {
+    ///         Some(remote) if remote.ip() == DENIED => Err(()),
+    ///         _ => Ok(()),
+    ///     }
+    /// }));
+    /// ```
+    pub fn on_connect<F: Send + Sync + 'static>(name: &'static str, f: F) -> AdHoc
+        where F: for<'a> Fn(&'a ConnectionInfo) -> BoxFuture<'a, Result<(), ()>>
+    {
+        AdHoc { name, kind: AdHocKind::Connect(Box::new(f)) }
+    }
+
+    /// Constructs an `AdHoc` disconnect fairing named `name`. The function `f`
+    /// will be called and the returned `Future` will be `await`ed by Rocket
+    /// when a connection closes.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::fairing::AdHoc;
+    ///
+    /// // A fairing that logs connection statistics.
+    /// let fairing = AdHoc::on_disconnect("Statistics", |conn| Box::pin(async move {
+    ///     println!("connection {} closed after {:?}: {} bytes in, {} bytes out",
+    ///         conn.id(), conn.duration(), conn.bytes_read(), conn.bytes_written());
+    /// }));
+    /// ```
+    pub fn on_disconnect<F: Send + Sync + 'static>(name: &'static str, f: F) -> AdHoc
+        where F: for<'a> Fn(&'a ConnectionInfo) -> BoxFuture<'a, ()>
+    {
+        AdHoc { name, kind: AdHocKind::Disconnect(Box::new(f)) }
+    }
+
     /// Constructs an `AdHoc` launch fairing that extracts a configuration of
     /// type `T` from the configured provider and stores it in managed state. If
     /// extractions fails, pretty-prints the error message and aborts launch.
@@ -331,6 +389,8 @@ impl Fairing for AdHoc {
             AdHocKind::Request(_) => Kind::Request,
             AdHocKind::Response(_) => Kind::Response,
             AdHocKind::Shutdown(_) => Kind::Shutdown,
+            AdHocKind::Connect(_) => Kind::Connect,
+            AdHocKind::Disconnect(_) => Kind::Disconnect,
         };
 
         Info { name: self.name, kind }
@@ -366,4 +426,17 @@ impl Fairing for AdHoc {
             (f.take())(rocket).await
         }
     }
+
+    async fn on_connect(&self, conn: &ConnectionInfo) -> Result<(), ()> {
+        match self.kind {
+            AdHocKind::Connect(ref f) => f(conn).await,
+            _ => Ok(())
+        }
+    }
+
+    async fn on_disconnect(&self, conn: &ConnectionInfo) {
+        if let AdHocKind::Disconnect(ref f) = self.kind {
+            f(conn).await
+        }
+    }
 }
@@ -2,6 +2,7 @@ use std::collections::HashSet;
 
 use crate::{Rocket, Request, Response, Data, Build, Orbit};
 use crate::fairing::{Fairing, Info, Kind};
+use crate::listener::ConnectionInfo;
 use crate::log_utils::PaintExt;
 
 use yansi::Paint;
@@ -20,6 +21,8 @@ pub struct Fairings {
     request: Vec<usize>,
     response: Vec<usize>,
     shutdown: Vec<usize>,
+    connect: Vec<usize>,
+    disconnect: Vec<usize>,
 }
 
 macro_rules! iter {
@@ -48,6 +51,8 @@ impl Fairings {
             .chain(self.request.iter())
             .chain(self.response.iter())
             .chain(self.shutdown.iter())
+            .chain(self.connect.iter())
+            .chain(self.disconnect.iter())
     }
 
     pub fn add(&mut self, fairing: Box<dyn Fairing>) {
@@ -93,6 +98,8 @@ impl Fairings {
                 remove(i, &mut self.request);
                 remove(i, &mut self.response);
                 remove(i, &mut self.shutdown);
+                remove(i, &mut self.connect);
+                remove(i, &mut self.disconnect);
             }
         }
 
@@ -103,6 +110,8 @@ impl Fairings {
         if this_info.kind.is(Kind::Request) { self.request.push(index); }
         if this_info.kind.is(Kind::Response) { self.response.push(index); }
         if this_info.kind.is(Kind::Shutdown) { self.shutdown.push(index); }
+        if this_info.kind.is(Kind::Connect) { self.connect.push(index); }
+        if this_info.kind.is(Kind::Disconnect) { self.disconnect.push(index); }
     }
 
     pub fn append(&mut self, others: &mut Fairings) {
@@ -163,6 +172,30 @@ impl Fairings {
         futures::future::join_all(shutdown_futures).await;
     }
 
+    /// Returns `true` if any connect or disconnect fairings are attached.
+    pub fn has_connection(&self) -> bool {
+        !self.connect.is_empty() || !self.disconnect.is_empty()
+    }
+
+    /// Runs every connect fairing, returning `true` if all accepted `conn`.
+    pub async fn handle_connect(&self, conn: &ConnectionInfo) -> bool {
+        let mut accepted = true;
+        for fairing in iter!(self.connect) {
+            if fairing.on_connect(conn).await.is_err() {
+                debug_!("Connection rejected by '{}'.", fairing.info().name);
+                accepted = false;
+            }
+        }
+
+        accepted
+    }
+
+    #[inline(always)]
+    pub async fn handle_disconnect(&self, conn: &ConnectionInfo) {
+        let disconnect_futures = iter!(self.disconnect).map(|f| f.on_disconnect(conn));
+        futures::future::join_all(disconnect_futures).await;
+    }
+
     pub fn audit(&self) -> Result<(), &[Info]> {
         match self.failures.is_empty() {
             true => Ok(()),
@@ -195,6 +228,8 @@ impl std::fmt::Debug for Fairings {
             .field("request", &debug_info(iter!(self.request)))
             .field("response", &debug_info(iter!(self.response)))
             .field("shutdown", &debug_info(iter!(self.shutdown)))
+            .field("connect", &debug_info(iter!(self.connect)))
+            .field("disconnect", &debug_info(iter!(self.disconnect)))
             .finish()
     }
 }
@@ -41,6 +41,8 @@ pub struct Info {
 ///   * Request
 ///   * Response
 ///   * Shutdown
+///   * Connect
+///   * Disconnect
 ///
 /// Two `Kind` structures can be `or`d together to represent a combination. For
 /// instance, to represent a fairing that is both an ignite and request fairing,
@@ -74,6 +76,12 @@ impl Kind {
     /// [singleton](crate::fairing::Fairing#singletons) fairing.
     pub const Singleton: Kind = Kind(1 << 5);
 
+    /// `Kind` flag representing a request for a 'connect' callback.
+    pub const Connect: Kind = Kind(1 << 6);
+
+    /// `Kind` flag representing a request for a 'disconnect' callback.
+    pub const Disconnect: Kind = Kind(1 << 7);
+
     /// Returns `true` if `self` is a superset of `other`. In other words,
     /// returns `true` if all of the kinds in `other` are also in `self`.
     ///
@@ -146,6 +154,8 @@ impl std::fmt::Display for Kind {
         write("request", Kind::Request)?;
         write("response", Kind::Response)?;
         write("shutdown", Kind::Shutdown)?;
+        write("connect", Kind::Connect)?;
+        write("disconnect", Kind::Disconnect)?;
         write("singleton", Kind::Singleton)
     }
 }
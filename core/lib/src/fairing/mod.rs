@@ -52,6 +52,7 @@
 use std::any::Any;
 
 use crate::{Rocket, Request, Response, Data, Build, Orbit};
+use crate::listener::ConnectionInfo;
 
 mod fairings;
 mod ad_hoc;
@@ -101,11 +102,11 @@ pub type Result<T = Rocket<Build>, E = Rocket<Build>> = std::result::Result<T, E
 ///
 /// ## Fairing Callbacks
 ///
-/// There are five kinds of fairing callbacks: launch, liftoff, request,
-/// response, and shutdown. A fairing can request any combination of these
-/// callbacks through the `kind` field of the [`Info`] structure returned from
-/// the `info` method. Rocket will only invoke the callbacks identified in the
-/// fairing's [`Kind`].
+/// There are seven kinds of fairing callbacks: launch, liftoff, request,
+/// response, shutdown, connect, and disconnect. A fairing can request any
+/// combination of these callbacks through the `kind` field of the [`Info`]
+/// structure returned from the `info` method. Rocket will only invoke the
+/// callbacks identified in the fairing's [`Kind`].
 ///
 /// The callback kinds are as follows:
 ///
@@ -195,6 +196,32 @@ pub type Result<T = Rocket<Build>, E = Rocket<Build>> = std::result::Result<T, E
 ///     [grace and mercy periods]: crate::config::Shutdown#summary
 ///     [`Client::terminate()`]: crate::local::blocking::Client::terminate()
 ///
+///   * **<a name="connect">Connect</a> (`on_connect`)**
+///
+///     A connect callback, represented by the [`Fairing::on_connect()`]
+///     method, is called when a connection is accepted, after any TLS
+///     handshake has completed but before any request is read. The callback
+///     receives the connection's [`ConnectionInfo`]: its identifier, remote
+///     address, and TLS session information such as the requested server name
+///     (SNI), negotiated protocol (ALPN), and client certificates.
+///
+///     A connect callback returns `Ok` to accept the connection and `Err` to
+///     reject it. A rejected connection is closed without a response. All
+///     connect callbacks are executed, in `attach()` order, even if one or more
+///     reject the connection. Connect callbacks delay serving the connection,
+///     so they should complete quickly. Local requests, as dispatched by a
+///     [`Client`](crate::local), do not arrive on a connection and thus do not
+///     trigger connect callbacks.
+///
+///   * **<a name="disconnect">Disconnect</a> (`on_disconnect`)**
+///
+///     A disconnect callback, represented by the [`Fairing::on_disconnect()`]
+///     method, is called when a connection that was passed to connect
+///     callbacks closes, including connections that were rejected. The
+///     [`ConnectionInfo`] reflects the connection's final byte counts and
+///     duration. All disconnect callbacks are run concurrently in a background
+///     task.
+///
 /// # Singletons
 ///
 /// In general, any number of instances of a given fairing type can be attached
@@ -209,7 +236,8 @@ pub type Result<T = Rocket<Build>, E = Rocket<Build>> = std::result::Result<T, E
 ///
 /// A `Fairing` implementation has one required method: [`info`]. A `Fairing`
 /// can also implement any of the available callbacks: `on_ignite`, `on_liftoff`,
-/// `on_request`, and `on_response`. A `Fairing` _must_ set the appropriate
+/// `on_request`, `on_response`, `on_shutdown`, `on_connect`, and
+/// `on_disconnect`. A `Fairing` _must_ set the appropriate
 /// callback kind in the `kind` field of the returned `Info` structure from
 /// [`info`] for a callback to actually be called by Rocket.
 ///
@@ -531,6 +559,33 @@ pub trait Fairing: Send + Sync + Any + 'static {
     ///
     /// The default implementation of this method does nothing.
     async fn on_shutdown(&self, _rocket: &Rocket<Orbit>) { }
+
+    /// The connect callback. Returns `Ok` if the connection should be served
+    /// and `Err` if it should be closed.
+    ///
+    /// See [Fairing Callbacks](#connect) for complete semantics.
+    ///
+    /// This method is called when a connection is accepted if `Kind::Connect`
+    /// is in the `kind` field of the `Info` structure for this fairing. The
+    /// `&ConnectionInfo` parameter describes the accepted connection.
+    ///
+    /// ## Default Implementation
+    ///
+    /// The default implementation of this method simply returns `Ok(())`.
+    async fn on_connect(&self, _conn: &ConnectionInfo) -> Result<(), ()> { Ok(()) }
+
+    /// The disconnect callback.
+    ///
+    /// See [Fairing Callbacks](#disconnect) for complete semantics.
+    ///
+    /// This method is called when a connection closes if `Kind::Disconnect` is
+    /// in the `kind` field of the `Info` structure for this fairing. The
+    /// `&ConnectionInfo` parameter describes the closed connection.
+    ///
+    /// ## Default Implementation
+    ///
+    /// The default implementation of this method does nothing.
+    async fn on_disconnect(&self, _conn: &ConnectionInfo) { }
 }
 
 #[crate::async_trait]
@@ -564,4 +619,14 @@ impl<T: Fairing + ?Sized> Fairing for std::sync::Arc<T> {
     async fn on_shutdown(&self, rocket: &Rocket<Orbit>) {
         (self as &T).on_shutdown(rocket).await
     }
+
+    #[inline]
+    async fn on_connect(&self, conn: &ConnectionInfo) -> Result<(), ()> {
+        (self as &T).on_connect(conn).await
+    }
+
+    #[inline]
+    async fn on_disconnect(&self, conn: &ConnectionInfo) {
+        (self as &T).on_disconnect(conn).await
+    }
 }
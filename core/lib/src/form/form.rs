@@ -3,6 +3,7 @@ use std::ops::{Deref, DerefMut};
 use crate::Request;
 use crate::outcome::try_outcome;
 use crate::data::{Data, FromData, Outcome};
+use crate::response::Problem;
 use crate::http::{RawStr, ext::IntoOwned};
 use crate::form::{SharedStack, parser::{Parser, RawStrParser}};
 use crate::form::prelude::*;
@@ -322,7 +323,12 @@ impl<'r, T: FromForm<'r>> FromData<'r> for Form<T> {
     async fn from_data(req: &'r Request<'_>, data: Data<'r>) -> Outcome<'r, Self> {
         use either::Either;
 
-        let mut parser = try_outcome!(Parser::new(req, data).await);
+        let parser = Parser::new(req, data).await.map_failure(|(status, e)| {
+            Problem::record(req, || Problem::from(&e));
+            (status, e)
+        });
+
+        let mut parser = try_outcome!(parser);
         let mut context = T::init(Options::Lenient);
         while let Some(field) = parser.next().await {
             match field {
@@ -334,7 +340,10 @@ impl<'r, T: FromForm<'r>> FromData<'r> for Form<T> {
 
         match T::finalize(context) {
             Ok(value) => Outcome::Success(Form(value)),
-            Err(e) => Outcome::Failure((e.status(), e)),
+            Err(e) => {
+                Problem::record(req, || Problem::from(&e));
+                Outcome::Failure((e.status(), e))
+            }
         }
     }
 }
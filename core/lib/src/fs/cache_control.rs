@@ -0,0 +1,153 @@
+use std::borrow::Cow;
+
+/// `Cache-Control` rules for the files served by a file server: the value of
+/// the first rule whose pattern matches a file's path, if any, or else the
+/// default value, if there is one.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct CachePolicy {
+    rules: Vec<(String, Cow<'static, str>)>,
+    default: Option<Cow<'static, str>>,
+}
+
+impl CachePolicy {
+    pub(crate) fn add_rule(&mut self, pattern: &str, value: Cow<'static, str>) {
+        self.rules.push((pattern.trim_start_matches('/').into(), value));
+    }
+
+    pub(crate) fn set_default(&mut self, value: Cow<'static, str>) {
+        self.default = Some(value);
+    }
+
+    /// Returns the `Cache-Control` value for the file at the `/`-separated
+    /// `path`, relative to the file server's root.
+    pub(crate) fn get(&self, path: &str) -> Option<Cow<'static, str>> {
+        self.rules.iter()
+            .find(|(pattern, _)| glob_matches(pattern, path))
+            .map(|(_, value)| value)
+            .or(self.default.as_ref())
+            .cloned()
+    }
+}
+
+/// Returns `true` if the glob `pattern` matches the `/`-separated `path`. A
+/// pattern without a `/` is matched against the file name alone.
+fn glob_matches(pattern: &str, path: &str) -> bool {
+    if !pattern.contains('/') {
+        let name = path.rsplit('/').next().unwrap_or(path);
+        return segment_matches(&chars(pattern), &chars(name));
+    }
+
+    let pattern = pattern.split('/').collect::<Vec<_>>();
+    let path = path.split('/').collect::<Vec<_>>();
+    segments_match(&pattern, &path)
+}
+
+fn chars(string: &str) -> Vec<char> {
+    string.chars().collect()
+}
+
+/// Matches path segments, where a `**` segment matches zero or more segments.
+fn segments_match(pattern: &[&str], path: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        Some((&"**", rest)) => (0..=path.len()).any(|i| segments_match(rest, &path[i..])),
+        Some((first, rest)) => match path.split_first() {
+            Some((segment, path)) => {
+                segment_matches(&chars(first), &chars(segment)) && segments_match(rest, path)
+            }
+            None => false,
+        }
+    }
+}
+
+/// Matches a single segment, where `*` matches any run of characters, `?` any
+/// one character, and `[...]` any one character in the class.
+fn segment_matches(pattern: &[char], segment: &[char]) -> bool {
+    match pattern.split_first() {
+        None => segment.is_empty(),
+        Some(('*', rest)) => (0..=segment.len()).any(|i| segment_matches(rest, &segment[i..])),
+        Some(('?', rest)) => !segment.is_empty() && segment_matches(rest, &segment[1..]),
+        Some(('[', rest)) if rest.iter().skip(1).any(|&c| c == ']') => {
+            let end = 1 + rest[1..].iter().position(|&c| c == ']').expect("checked");
+            match segment.split_first() {
+                Some((c, segment)) => {
+                    class_matches(&rest[..end], *c) && segment_matches(&rest[end + 1..], segment)
+                }
+                None => false,
+            }
+        }
+        Some((p, rest)) => match segment.split_first() {
+            Some((c, segment)) => p == c && segment_matches(rest, segment),
+            None => false,
+        }
+    }
+}
+
+/// Matches `c` against a character class, such as `a-z0-9` or `!.`.
+fn class_matches(class: &[char], c: char) -> bool {
+    let (negated, mut class) = match class.split_first() {
+        Some(('!', rest)) => (true, rest),
+        _ => (false, class),
+    };
+
+    let mut matched = false;
+    while let Some((&first, rest)) = class.split_first() {
+        match rest {
+            ['-', last, rest @ ..] => {
+                matched |= (first..=*last).contains(&c);
+                class = rest;
+            }
+            _ => {
+                matched |= first == c;
+                class = rest;
+            }
+        }
+    }
+
+    matched != negated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{glob_matches, CachePolicy};
+
+    #[test]
+    fn test_glob_matches() {
+        assert!(glob_matches("index.html", "index.html"));
+        assert!(glob_matches("index.html", "a/b/index.html"));
+        assert!(glob_matches("*.js", "js/app.js"));
+        assert!(glob_matches("app.??.js", "app.v1.js"));
+        assert!(glob_matches("*.[0-9a-f][0-9a-f].css", "site.3e.css"));
+        assert!(glob_matches("*.[!0-9]", "file.x"));
+        assert!(glob_matches("[]", "[]"));
+        assert!(!glob_matches("*.js", "app.json"));
+        assert!(!glob_matches("app.??.js", "app.v10.js"));
+        assert!(!glob_matches("*.[0-9a-f][0-9a-f].css", "site.3g.css"));
+        assert!(!glob_matches("*.[!0-9]", "file.1"));
+
+        assert!(glob_matches("assets/**", "assets/app.js"));
+        assert!(glob_matches("assets/**", "assets/img/logo.png"));
+        assert!(glob_matches("**/index.html", "index.html"));
+        assert!(glob_matches("**/img/*.png", "a/b/img/logo.png"));
+        assert!(glob_matches("a/*/c", "a/b/c"));
+        assert!(!glob_matches("assets/**", "other/assets/app.js"));
+        assert!(!glob_matches("assets/*", "assets/img/logo.png"));
+        assert!(!glob_matches("a/*/c", "a/b/b/c"));
+    }
+
+    #[test]
+    fn test_cache_policy() {
+        let mut policy = CachePolicy::default();
+        assert_eq!(policy.get("index.html"), None);
+
+        policy.add_rule("/assets/**", "immutable".into());
+        policy.add_rule("*.html", "no-cache".into());
+        policy.add_rule("assets/index.html", "unreachable".into());
+        assert_eq!(policy.get("assets/index.html").as_deref(), Some("immutable"));
+        assert_eq!(policy.get("inner/index.html").as_deref(), Some("no-cache"));
+        assert_eq!(policy.get("app.js"), None);
+
+        policy.set_default("max-age=60".into());
+        assert_eq!(policy.get("app.js").as_deref(), Some("max-age=60"));
+    }
+}
@@ -0,0 +1,509 @@
+use std::fmt;
+use std::borrow::Cow;
+use std::io::Cursor;
+
+use crate::{Request, Data};
+use crate::http::{Method, ContentType, Header, uri::Segments, ext::IntoOwned};
+use crate::route::{Route, Handler, Outcome};
+use crate::response::{self, Ranged, Redirect, Responder};
+use crate::fs::Options;
+use crate::fs::server::{is_asset, precompressed_variants, ServedFile};
+use crate::fs::listing::{Entry, Listing};
+use crate::fs::cache_control::CachePolicy;
+
+/// A directory embedded into the binary by [`embed!`].
+///
+/// An `Embedded` is a read-only, in-memory view of the files in a directory as
+/// they were at compile-time. It is typically served by an
+/// [`EmbeddedFileServer`] but individual files can also be retrieved with
+/// [`Embedded::get()`] and returned directly from a route.
+///
+/// # Example
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// use rocket::fs::{embed, Embedded, EmbeddedFile};
+///
+/// static STATIC: Embedded = embed!("tests/static");
+///
+/// #[get("/")]
+/// fn index() -> Option<&'static EmbeddedFile> {
+///     STATIC.get("index.html")
+/// }
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Embedded {
+    files: &'static [EmbeddedFile],
+}
+
+/// A file embedded into the binary by [`embed!`].
+///
+/// Responds with the contents of the file, its `Content-Type`, as determined
+/// by its extension at compile-time, and an `ETag` computed from its contents
+/// at compile-time. Like [`NamedFile`](crate::fs::NamedFile), the response
+/// supports [range requests](crate::response::Ranged).
+pub struct EmbeddedFile {
+    #[doc(hidden)]
+    pub path: &'static str,
+    #[doc(hidden)]
+    pub contents: &'static [u8],
+    #[doc(hidden)]
+    pub content_type: Option<ContentType>,
+    #[doc(hidden)]
+    pub etag: &'static str,
+}
+
+impl Embedded {
+    #[doc(hidden)]
+    pub const fn from_files(files: &'static [EmbeddedFile]) -> Self {
+        Embedded { files }
+    }
+
+    /// Returns the file at the `/`-separated `path`, relative to the embedded
+    /// directory, if there is one.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::fs::embed;
+    ///
+    /// let files = embed!("tests/static");
+    /// assert!(files.get("inner/goodbye").is_some());
+    /// assert!(files.get("inner").is_none());
+    /// assert!(files.get("nonexistent").is_none());
+    /// ```
+    pub fn get(&self, path: &str) -> Option<&'static EmbeddedFile> {
+        let files = self.files;
+        files.binary_search_by(|file| file.path.cmp(path)).ok().map(|i| &files[i])
+    }
+
+    /// Returns an iterator over every embedded file, in lexicographic order of
+    /// their paths.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::fs::embed;
+    ///
+    /// let files = embed!("tests/static");
+    /// assert!(files.iter().any(|file| file.path() == "index.html"));
+    /// ```
+    pub fn iter(&self) -> impl Iterator<Item = &'static EmbeddedFile> {
+        self.files.iter()
+    }
+
+    /// Returns the files in the directory `dir`, which is either empty or ends
+    /// with a `/`, and in all of its subdirectories.
+    fn files_in<'a>(&self, dir: &'a str) -> impl Iterator<Item = &'static EmbeddedFile> + 'a {
+        let start = self.files.partition_point(|file| file.path < dir);
+        self.files[start..].iter().take_while(move |file| file.path.starts_with(dir))
+    }
+
+    /// Returns `true` if `path` names a directory containing at least one file.
+    /// The root directory, `""`, always exists.
+    fn is_dir(&self, path: &str) -> bool {
+        path.is_empty() || self.files_in(&format!("{}/", path)).next().is_some()
+    }
+}
+
+impl EmbeddedFile {
+    /// The `/`-separated path to the file relative to the embedded directory.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::fs::embed;
+    ///
+    /// let file = embed!("tests/static").get("inner/goodbye").unwrap();
+    /// assert_eq!(file.path(), "inner/goodbye");
+    /// ```
+    pub fn path(&self) -> &'static str {
+        self.path
+    }
+
+    /// The contents of the file.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::fs::embed;
+    ///
+    /// let file = embed!("tests/static").get("other/hello.txt").unwrap();
+    /// assert_eq!(file.contents(), b"Hi!\n");
+    /// ```
+    pub fn contents(&self) -> &'static [u8] {
+        self.contents
+    }
+
+    /// The `Content-Type` of the file as determined by its extension, if it
+    /// has a known extension.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::fs::embed;
+    /// use rocket::http::ContentType;
+    ///
+    /// let files = embed!("tests/static");
+    /// let file = files.get("index.html").unwrap();
+    /// assert_eq!(file.content_type(), Some(&ContentType::HTML));
+    ///
+    /// let file = files.get("inner/goodbye").unwrap();
+    /// assert_eq!(file.content_type(), None);
+    /// ```
+    pub fn content_type(&self) -> Option<&ContentType> {
+        self.content_type.as_ref()
+    }
+
+    /// The strong entity tag of the file, including quotes, computed from the
+    /// file's contents at compile-time. The tag depends only on the contents,
+    /// so it is the same across builds, compilers, and hosts.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::fs::embed;
+    ///
+    /// let file = embed!("tests/static").get("index.html").unwrap();
+    /// assert!(file.etag().starts_with('"') && file.etag().ends_with('"'));
+    /// ```
+    pub fn etag(&self) -> &'static str {
+        self.etag
+    }
+}
+
+impl fmt::Debug for EmbeddedFile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EmbeddedFile")
+            .field("path", &self.path)
+            .field("len", &self.contents.len())
+            .field("content_type", &self.content_type)
+            .field("etag", &self.etag)
+            .finish()
+    }
+}
+
+impl<'r> Responder<'r, 'static> for &'static EmbeddedFile {
+    fn respond_to(self, req: &'r Request<'_>) -> response::Result<'static> {
+        let mut response = Ranged(Cursor::new(self.contents)).respond_to(req)?;
+        if let Some(content_type) = &self.content_type {
+            response.set_header(content_type.clone());
+        }
+
+        response.set_header(Header::new("ETag", self.etag));
+        Ok(response)
+    }
+}
+
+/// Custom handler for serving static files embedded into the binary.
+///
+/// This handler is the compile-time counterpart to
+/// [`FileServer`](crate::fs::FileServer): it serves files from a directory
+/// embedded into the binary by [`embed!`] and requires no access to the file
+/// system at run-time. If a requested file is not found, the routes _forward_
+/// the incoming request. The default rank of the generated routes is `10`. To
+/// customize route ranking, use the [`EmbeddedFileServer::rank()`] method.
+///
+/// # Options
+///
+/// The handler's functionality can be customized by passing an [`Options`] to
+/// [`EmbeddedFileServer::new()`]. Options have the same semantics as they do
+/// for `FileServer`, with the following exceptions:
+///
+///   * [`Options::Missing`] and [`Options::IndexFile`] have no effect: `embed!`
+///     requires an existing directory and fails to compile otherwise.
+///   * Directories are implied by the files within them, so empty directories
+///     are not served.
+///   * With [`Options::Precompressed`], variants are the embedded `.br` and
+///     `.gz` files alongside the original.
+///   * With [`Options::Listing`], listings report no modification times.
+///   * With [`Options::Spa`], the fallback is the embedded `index.html`.
+///
+/// Every file is embedded regardless of the options, including dotfiles, which
+/// are only served with [`Options::DotFiles`].
+///
+/// # Example
+///
+/// Serve the files in the crate-relative `tests/static/` directory at `/`:
+///
+/// ```rust,no_run
+/// # #[macro_use] extern crate rocket;
+/// use rocket::fs::{embed, EmbeddedFileServer};
+///
+/// #[launch]
+/// fn rocket() -> _ {
+///     rocket::build().mount("/", EmbeddedFileServer::from(embed!("tests/static")))
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct EmbeddedFileServer {
+    dir: Embedded,
+    options: Options,
+    rank: isize,
+    cache: CachePolicy,
+}
+
+impl EmbeddedFileServer {
+    /// The default rank use by `EmbeddedFileServer` routes.
+    const DEFAULT_RANK: isize = 10;
+
+    /// Constructs a new `EmbeddedFileServer` that serves the files in `dir`
+    /// with `options` enabled. By default, the handler's routes have a rank of
+    /// `10`. To choose a different rank, use [`EmbeddedFileServer::rank()`].
+    ///
+    /// # Example
+    ///
+    /// Serve the embedded files, including their precompressed variants, on
+    /// path `/static`.
+    ///
+    /// ```rust,no_run
+    /// # #[macro_use] extern crate rocket;
+    /// use rocket::fs::{embed, EmbeddedFileServer, Options};
+    ///
+    /// #[launch]
+    /// fn rocket() -> _ {
+    ///     let options = Options::default() | Options::Precompressed;
+    ///     let server = EmbeddedFileServer::new(embed!("tests/static"), options);
+    ///     rocket::build().mount("/static", server)
+    /// }
+    /// ```
+    pub fn new(dir: Embedded, options: Options) -> Self {
+        EmbeddedFileServer { dir, options, rank: Self::DEFAULT_RANK, cache: CachePolicy::default() }
+    }
+
+    /// Sets the rank for generated routes to `rank`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rocket::fs::{embed, EmbeddedFileServer};
+    ///
+    /// EmbeddedFileServer::from(embed!("tests/static")).rank(3);
+    /// ```
+    pub fn rank(mut self, rank: isize) -> Self {
+        self.rank = rank;
+        self
+    }
+
+    /// Sets the `Cache-Control` header of responses for files matching the
+    /// glob `pattern` to `value`. Patterns and rules are as for
+    /// [`FileServer::cache_control()`](crate::fs::FileServer::cache_control()).
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rocket::fs::{embed, EmbeddedFileServer};
+    ///
+    /// EmbeddedFileServer::from(embed!("tests/static"))
+    ///     .cache_control("assets/**", "immutable, max-age=31536000")
+    ///     .cache_control("index.html", "no-cache");
+    /// ```
+    pub fn cache_control<V>(mut self, pattern: &str, value: V) -> Self
+        where V: Into<Cow<'static, str>>
+    {
+        self.cache.add_rule(pattern, value.into());
+        self
+    }
+
+    /// Sets the `Cache-Control` header of responses for files that match no
+    /// rule added with [`EmbeddedFileServer::cache_control()`] to `value`. By
+    /// default, such responses have no `Cache-Control` header.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rocket::fs::{embed, EmbeddedFileServer};
+    ///
+    /// EmbeddedFileServer::from(embed!("tests/static")).default_cache_control("no-cache");
+    /// ```
+    pub fn default_cache_control<V>(mut self, value: V) -> Self
+        where V: Into<Cow<'static, str>>
+    {
+        self.cache.set_default(value.into());
+        self
+    }
+
+    /// Returns the file at `path` or, if [`Options::Precompressed`] is
+    /// enabled, the precompressed variant of it that `req` prefers.
+    fn open(&self, req: &Request<'_>, path: &str) -> Option<ServedFile<&'static EmbeddedFile>> {
+        let file = self.dir.get(path)?;
+        let cache_control = self.cache.get(path);
+        if !self.options.contains(Options::Precompressed) {
+            return Some(ServedFile { file, encoding: None, vary: false, cache_control });
+        }
+
+        for (ext, coding) in precompressed_variants(req) {
+            if let Some(variant) = self.dir.get(&format!("{}.{}", path, ext)) {
+                let encoding = Some((coding, file.content_type.clone()));
+                let file = variant;
+                return Some(ServedFile { file, encoding, vary: true, cache_control });
+            }
+        }
+
+        Some(ServedFile { file, encoding: None, vary: true, cache_control })
+    }
+
+    /// Lists the entries of the directory `dir`, which is either empty or ends
+    /// with a `/`.
+    fn listing(&self, req: &Request<'_>, dir: &str) -> Listing {
+        let dotfiles = self.options.contains(Options::DotFiles);
+        let mut entries: Vec<Entry> = vec![];
+        for file in self.dir.files_in(dir) {
+            let rest = &file.path[dir.len()..];
+            let (name, is_dir) = match rest.split_once('/') {
+                Some((name, _)) => (name, true),
+                None => (rest, false),
+            };
+
+            // Files in the same subdirectory are adjacent.
+            let seen = entries.last().map_or(false, |e| e.is_dir && e.name == name);
+            if seen || (!dotfiles && name.starts_with('.')) {
+                continue;
+            }
+
+            let size = if is_dir { 0 } else { file.contents.len() as u64 };
+            entries.push(Entry { name: name.into(), is_dir, size, modified: None });
+        }
+
+        Listing::new(req, dir.is_empty(), entries)
+    }
+}
+
+impl From<Embedded> for EmbeddedFileServer {
+    /// Constructs a new `EmbeddedFileServer` that serves the files in `dir`
+    /// with the default [`Options`].
+    fn from(dir: Embedded) -> Self {
+        EmbeddedFileServer::new(dir, Options::default())
+    }
+}
+
+impl From<EmbeddedFileServer> for Vec<Route> {
+    fn from(server: EmbeddedFileServer) -> Self {
+        let mut route = Route::ranked(server.rank, Method::Get, "/<path..>", server);
+        route.name = Some("EmbeddedFileServer".into());
+        vec![route]
+    }
+}
+
+#[crate::async_trait]
+impl Handler for EmbeddedFileServer {
+    async fn handle<'r>(&self, req: &'r Request<'_>, data: Data<'r>) -> Outcome<'r> {
+        let spa = self.options.contains(Options::Spa);
+        match self.serve(req, data) {
+            Outcome::Forward((data, _)) if spa && !is_asset(req) => {
+                Outcome::from_or_forward(req, data, self.open(req, "index.html"))
+            }
+            outcome => outcome,
+        }
+    }
+}
+
+impl EmbeddedFileServer {
+    /// Serves the file or directory requested by `req`, forwarding if there is
+    /// none.
+    fn serve<'r>(&self, req: &'r Request<'_>, data: Data<'r>) -> Outcome<'r> {
+        use crate::http::uri::fmt::Path;
+
+        // Get the segments as a `/`-separated path, allowing dotfiles requested.
+        let options = self.options;
+        let allow_dotfiles = options.contains(Options::DotFiles);
+        let path = req.segments::<Segments<'_, Path>>(0..).ok()
+            .and_then(|segments| segments.to_path_buf(allow_dotfiles).ok())
+            .and_then(|path| {
+                let segments = path.iter().map(|s| s.to_str()).collect::<Option<Vec<_>>>();
+                segments.map(|segments| segments.join("/"))
+            });
+
+        let path = match path {
+            Some(path) => path,
+            None => return Outcome::forward(data),
+        };
+
+        if !self.dir.is_dir(&path) {
+            return Outcome::from_or_forward(req, data, self.open(req, &path));
+        }
+
+        // Normalize '/a/b/foo' to '/a/b/foo/'.
+        if options.contains(Options::NormalizeDirs) && !req.uri().path().ends_with('/') {
+            let normal = req.uri().map_path(|p| format!("{}/", p))
+                .expect("adding a trailing slash to a known good path => valid path")
+                .into_owned();
+
+            return Outcome::from_or_forward(req, data, Redirect::permanent(normal));
+        }
+
+        let dir = if path.is_empty() { path } else { format!("{}/", path) };
+        if options.contains(Options::Index) {
+            if let Some(index) = self.open(req, &format!("{}index.html", dir)) {
+                return Outcome::from_or_forward(req, data, index);
+            }
+        }
+
+        if !options.contains(Options::Listing) {
+            return Outcome::forward(data);
+        }
+
+        Outcome::from_or_forward(req, data, self.listing(req, &dir))
+    }
+}
+
+crate::export! {
+    /// Embeds the files in a directory into the binary.
+    ///
+    /// The macro accepts one parameter, a string literal path to a directory
+    /// relative to the crate root, and returns an [`Embedded`] containing every
+    /// file in the directory and its subdirectories, following symlinks. The
+    /// `Content-Type` and `ETag` of each file are computed at compile-time. The
+    /// result is typically served with an [`EmbeddedFileServer`].
+    ///
+    /// Compilation fails if the directory cannot be read, contains a symlink
+    /// to one of its own ancestors, or contains a file whose path is not valid
+    /// UTF-8.
+    ///
+    /// # Rebuilds
+    ///
+    /// Changes to embedded files cause the crate to be recompiled, but files
+    /// added to or removed from the directory _do not_: procedural macros can
+    /// only track files, not directories, so without intervention, the binary
+    /// silently ships the file set from its last compilation. To recompile
+    /// when the set of files changes, add a build script, `build.rs`, that asks
+    /// Cargo to track the directory, which it scans recursively:
+    ///
+    /// ```rust,no_run
+    /// fn main() {
+    ///     println!("cargo:rerun-if-changed=tests/static");
+    /// }
+    /// ```
+    ///
+    /// Note that emitting any `rerun-if-changed` line disables Cargo's default
+    /// of rerunning the build script when any file in the package changes, so
+    /// a script that needs other inputs should list those as well. Without a
+    /// build script, touch the source file that invokes `embed!` to pick up an
+    /// added or removed file.
+    ///
+    /// # Example
+    ///
+    /// Serve the files in the crate-relative `tests/static/` directory at `/`:
+    ///
+    /// ```rust,no_run
+    /// # #[macro_use] extern crate rocket;
+    /// use rocket::fs::{embed, EmbeddedFileServer};
+    ///
+    /// #[launch]
+    /// fn rocket() -> _ {
+    ///     rocket::build().mount("/", EmbeddedFileServer::from(embed!("tests/static")))
+    /// }
+    /// ```
+    ///
+    /// Because `embed!` is a constant expression, the result can be stored in
+    /// a `static`:
+    ///
+    /// ```rust
+    /// use rocket::fs::{embed, Embedded};
+    ///
+    /// static ASSETS: Embedded = embed!("tests/static");
+    /// ```
+    macro_rules! embed {
+        ($($t:tt)*) => ($crate::__embed!($($t)*));
+    }
+}
@@ -0,0 +1,224 @@
+use std::io;
+use std::fmt::Write;
+use std::path::Path;
+use std::cmp::Ordering;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::request::Request;
+use crate::response::{self, Responder};
+use crate::http::{ContentType, RawStr};
+use crate::data::ToByteUnit;
+use crate::response::conditional::format_http_date;
+use crate::response::problem::json_string;
+
+/// A listing of a directory's contents, served by [`FileServer`] when
+/// [`Options::Listing`] is enabled.
+///
+/// [`FileServer`]: crate::fs::FileServer
+/// [`Options::Listing`]: crate::fs::Options::Listing
+pub(crate) struct Listing {
+    /// The percent-encoded request path, with a trailing slash.
+    path: String,
+    /// Whether the listed directory is the root of the file server.
+    root: bool,
+    entries: Vec<Entry>,
+    sort: Sort,
+    descending: bool,
+}
+
+/// An entry in a [`Listing`].
+pub(crate) struct Entry {
+    pub(crate) name: String,
+    pub(crate) is_dir: bool,
+    pub(crate) size: u64,
+    pub(crate) modified: Option<SystemTime>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Sort {
+    Name,
+    Size,
+    Modified,
+}
+
+impl Sort {
+    fn as_str(self) -> &'static str {
+        match self {
+            Sort::Name => "name",
+            Sort::Size => "size",
+            Sort::Modified => "modified",
+        }
+    }
+}
+
+macro_rules! listing_html_head {
+    () => (
+r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+    <meta charset="utf-8">
+    <title>Index of {title}</title>
+</head>
+<body>
+<h1>Index of {title}</h1>
+<table>
+<thead>
+<tr>"#
+    )
+}
+
+impl Listing {
+    /// Reads the entries of `dir` for `req`, omitting dotfiles unless
+    /// `dotfiles`, and sorting them as in [`Listing::new()`]. Entries with
+    /// names that aren't valid UTF-8, or whose metadata can't be read, are
+    /// omitted.
+    pub(crate) async fn read(
+        req: &Request<'_>,
+        dir: &Path,
+        root: bool,
+        dotfiles: bool
+    ) -> io::Result<Listing> {
+        let mut entries = vec![];
+        let mut read_dir = tokio::fs::read_dir(dir).await?;
+        while let Some(entry) = read_dir.next_entry().await? {
+            let name = match entry.file_name().into_string() {
+                Ok(name) if dotfiles || !name.starts_with('.') => name,
+                _ => continue,
+            };
+
+            // Like the files themselves, follow symlinks.
+            let metadata = match tokio::fs::metadata(entry.path()).await {
+                Ok(metadata) => metadata,
+                Err(_) => continue,
+            };
+
+            entries.push(Entry {
+                name,
+                is_dir: metadata.is_dir(),
+                size: if metadata.is_dir() { 0 } else { metadata.len() },
+                modified: metadata.modified().ok(),
+            });
+        }
+
+        Ok(Listing::new(req, root, entries))
+    }
+
+    /// Creates a listing of `entries` for `req`, sorted as requested by the
+    /// `sort` and `order` query parameters.
+    pub(crate) fn new(req: &Request<'_>, root: bool, mut entries: Vec<Entry>) -> Listing {
+        let query = |name: &str| req.query_value::<&str>(name).and_then(|v| v.ok());
+        let sort = match query("sort") {
+            Some("size") => Sort::Size,
+            Some("modified") => Sort::Modified,
+            _ => Sort::Name,
+        };
+
+        let descending = query("order") == Some("desc");
+        entries.sort_by(|a, b| {
+            let ordering = match sort {
+                Sort::Name => Ordering::Equal,
+                Sort::Size => a.size.cmp(&b.size),
+                Sort::Modified => a.modified.cmp(&b.modified),
+            };
+
+            let ordering = ordering.then_with(|| a.name.cmp(&b.name));
+            let ordering = if descending { ordering.reverse() } else { ordering };
+            b.is_dir.cmp(&a.is_dir).then(ordering)
+        });
+
+        let mut path = req.uri().path().as_str().to_string();
+        if !path.ends_with('/') {
+            path.push('/');
+        }
+
+        Listing { path, root, entries, sort, descending }
+    }
+
+    fn href(&self, entry: &Entry) -> String {
+        let name = RawStr::new(&entry.name).percent_encode();
+        let slash = if entry.is_dir { "/" } else { "" };
+        format!("{}{}{}", self.path, name, slash)
+    }
+
+    /// The absolute path of the parent directory. Like [`Listing::href()`],
+    /// it doesn't depend on whether the request's path ends in a `/`.
+    fn parent_href(&self) -> &str {
+        let path = self.path.trim_end_matches('/');
+        path.rfind('/').map_or("/", |i| &self.path[..=i])
+    }
+
+    fn to_json(&self) -> String {
+        let path = RawStr::new(&self.path).percent_decode_lossy();
+        let mut json = format!("{{\n  \"path\": {},\n  \"entries\": [", json_string(&path));
+        for (i, entry) in self.entries.iter().enumerate() {
+            let modified = entry.modified
+                .and_then(|m| m.duration_since(UNIX_EPOCH).ok())
+                .map_or_else(|| "null".into(), |d| d.as_secs().to_string());
+
+            let _ = write!(json,
+                "{}\n    {{ \"name\": {}, \"type\": \"{}\", \"size\": {}, \"modified\": {} }}",
+                if i == 0 { "" } else { "," },
+                json_string(&entry.name),
+                if entry.is_dir { "directory" } else { "file" },
+                entry.size,
+                modified);
+        }
+
+        let end = if self.entries.is_empty() { "]\n}" } else { "\n  ]\n}" };
+        json.push_str(end);
+        json
+    }
+
+    fn to_html(&self) -> String {
+        let path = RawStr::new(&self.path).percent_decode_lossy();
+        let title = RawStr::new(&path).html_escape();
+        let mut html = format!(listing_html_head!(), title = title);
+        for sort in [Sort::Name, Sort::Size, Sort::Modified] {
+            let descending = sort == self.sort && !self.descending;
+            let arrow = match (sort == self.sort, self.descending) {
+                (true, false) => " &#x25B2;",
+                (true, true) => " &#x25BC;",
+                (false, _) => "",
+            };
+
+            let name = sort.as_str();
+            let order = if descending { "desc" } else { "asc" };
+            let _ = write!(html, "<th><a href=\"?sort={}&amp;order={}\">{}{}</a></th>",
+                name, order, name, arrow);
+        }
+
+        html.push_str("</tr>\n</thead>\n<tbody>\n");
+        if !self.root {
+            let _ = writeln!(html, "<tr><td><a href=\"{}\">../</a></td><td></td><td></td></tr>",
+                RawStr::new(self.parent_href()).html_escape());
+        }
+
+        for entry in &self.entries {
+            let name = RawStr::new(&entry.name).html_escape();
+            let slash = if entry.is_dir { "/" } else { "" };
+            let size = match entry.is_dir {
+                true => "-".to_string(),
+                false => entry.size.bytes().to_string(),
+            };
+
+            let modified = entry.modified.and_then(format_http_date).unwrap_or_default();
+            let _ = writeln!(html, "<tr><td><a href=\"{}\">{}{}</a></td>\
+                <td>{}</td><td>{}</td></tr>",
+                RawStr::new(&self.href(entry)).html_escape(), name, slash, size, modified);
+        }
+
+        html.push_str("</tbody>\n</table>\n</body>\n</html>\n");
+        html
+    }
+}
+
+/// Responds with JSON if the request prefers JSON and with HTML otherwise.
+impl<'r> Responder<'r, 'static> for Listing {
+    fn respond_to(self, req: &'r Request<'_>) -> response::Result<'static> {
+        let prefers_json = req.accept().map_or(false, |a| a.preferred().is_json());
+        match prefers_json {
+            true => (ContentType::JSON, self.to_json()).respond_to(req),
+            false => (ContentType::HTML, self.to_html()).respond_to(req),
+        }
+    }
+}
@@ -1,12 +1,15 @@
 use std::io;
+use std::fs::Metadata;
+use std::time::UNIX_EPOCH;
 use std::path::{Path, PathBuf};
 use std::ops::{Deref, DerefMut};
 
 use tokio::fs::File;
 
 use crate::request::Request;
-use crate::response::{self, Responder};
-use crate::http::ContentType;
+use crate::response::{self, Ranged, Responder};
+use crate::http::{ContentType, Header};
+use crate::response::conditional::format_http_date;
 
 /// A [`Responder`] that sends file data with a Content-Type based on its
 /// file extension.
@@ -37,7 +40,7 @@ use crate::http::ContentType;
 ///
 /// [`FileServer`]: crate::fs::FileServer
 #[derive(Debug)]
-pub struct NamedFile(PathBuf, File);
+pub struct NamedFile(PathBuf, File, Option<Metadata>);
 
 impl NamedFile {
     /// Attempts to open a file in read-only mode.
@@ -65,7 +68,8 @@ impl NamedFile {
         // all of those `seek`s to determine the file size. But, what happens if
         // the file gets changed between now and then?
         let file = File::open(path.as_ref()).await?;
-        Ok(NamedFile(path.as_ref().to_path_buf(), file))
+        let metadata = file.metadata().await.ok();
+        Ok(NamedFile(path.as_ref().to_path_buf(), file, metadata))
     }
 
     /// Retrieve the underlying `File`.
@@ -146,15 +150,34 @@ impl NamedFile {
 /// recognized. See [`ContentType::from_extension()`] for more information. If
 /// you would like to stream a file with a different Content-Type than that
 /// implied by its extension, use a [`File`] directly.
+///
+/// The response's `ETag` and `Last-Modified` headers are set from the file's
+/// size and modification time as of when it was opened, making the response
+/// subject to the request's [preconditions]. Range requests are supported: see
+/// [`Ranged`] for details.
+///
+/// [preconditions]: crate::response::Conditional#preconditions
 impl<'r> Responder<'r, 'static> for NamedFile {
     fn respond_to(self, req: &'r Request<'_>) -> response::Result<'static> {
-        let mut response = self.1.respond_to(req)?;
+        let mut response = Ranged(self.1).respond_to(req)?;
         if let Some(ext) = self.0.extension() {
             if let Some(ct) = ContentType::from_extension(&ext.to_string_lossy()) {
                 response.set_header(ct);
             }
         }
 
+        let modified = self.2.as_ref().filter(|m| m.is_file()).and_then(|m| m.modified().ok());
+        if let (Some(metadata), Some(modified)) = (&self.2, modified) {
+            if let Ok(since_epoch) = modified.duration_since(UNIX_EPOCH) {
+                let etag = format!("\"{:x}-{:x}\"", metadata.len(), since_epoch.as_nanos());
+                response.set_header(Header::new("ETag", etag));
+            }
+
+            if let Some(date) = format_http_date(modified) {
+                response.set_header(Header::new("Last-Modified", date));
+            }
+        }
+
         Ok(response)
     }
 }
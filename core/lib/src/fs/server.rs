@@ -1,10 +1,14 @@
+use std::borrow::Cow;
 use std::path::{PathBuf, Path};
 
 use crate::{Request, Data};
-use crate::http::{Method, uri::Segments, ext::IntoOwned};
+use crate::http::{Method, ContentType, Header, uri::Segments, ext::IntoOwned};
+use crate::http::private::accept_encoding_qvalue;
 use crate::route::{Route, Handler, Outcome};
-use crate::response::Redirect;
+use crate::response::{self, Redirect, Responder};
 use crate::fs::NamedFile;
+use crate::fs::listing::Listing;
+use crate::fs::cache_control::CachePolicy;
 
 /// Custom handler for serving static files.
 ///
@@ -64,6 +68,7 @@ pub struct FileServer {
     root: PathBuf,
     options: Options,
     rank: isize,
+    cache: CachePolicy,
 }
 
 impl FileServer {
@@ -158,7 +163,45 @@ impl FileServer {
             }
         }
 
-        FileServer { root: path.into(), options, rank: Self::DEFAULT_RANK }
+        FileServer {
+            root: path.into(),
+            options,
+            rank: Self::DEFAULT_RANK,
+            cache: CachePolicy::default(),
+        }
+    }
+
+    /// Constructs a new `FileServer` that serves the single-page application
+    /// in the file system `path`. The default [`Options`] and [`Options::Spa`]
+    /// are enabled: requests for client-side routes are answered with the
+    /// application's `index.html`. See [`Options::Spa`] for details.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `path` does not exist or is not a directory.
+    ///
+    /// # Example
+    ///
+    /// Serve the application in the `/www/app` local directory at `/` and its
+    /// API at `/api`. Requests matching a route in `api::routes()` are handled
+    /// by that route; all other requests without an extension, including
+    /// those to `/api`, are answered with `/www/app/index.html`.
+    ///
+    /// ```rust,no_run
+    /// # #[macro_use] extern crate rocket;
+    /// # mod api { pub fn routes() -> Vec<rocket::Route> { vec![] } }
+    /// use rocket::fs::FileServer;
+    ///
+    /// #[launch]
+    /// fn rocket() -> _ {
+    ///     rocket::build()
+    ///         .mount("/api", api::routes())
+    ///         .mount("/", FileServer::spa("/www/app"))
+    /// }
+    /// ```
+    #[track_caller]
+    pub fn spa<P: AsRef<Path>>(path: P) -> Self {
+        FileServer::new(path, Options::default() | Options::Spa)
     }
 
     /// Sets the rank for generated routes to `rank`.
@@ -178,6 +221,73 @@ impl FileServer {
         self.rank = rank;
         self
     }
+
+    /// Sets the `Cache-Control` header of responses for files matching the
+    /// glob `pattern` to `value`.
+    ///
+    /// Patterns are matched against the `/`-separated path of the served file
+    /// relative to the root, ignoring any leading `/`. A pattern without a `/`
+    /// is matched against the file's name alone, in any directory. In patterns:
+    ///
+    ///   * `*` matches any sequence of characters except `/`.
+    ///   * `?` matches any one character except `/`.
+    ///   * `[...]` matches any one character in the class, such as `[0-9a-f]`.
+    ///     `[!...]` matches any one character _not_ in the class.
+    ///   * A `**` segment matches any number of segments, including none.
+    ///
+    /// Rules are tried in the order they are added, and the first matching
+    /// rule applies. If no rule matches, the value set by
+    /// [`FileServer::default_cache_control()`], if any, is used. The header is
+    /// set on every response for a file, including partial responses and
+    /// `304 Not Modified` responses, but not on directory listings or
+    /// redirects. The rule for a directory's `index.html` or a precompressed
+    /// variant is that of the file it stands in for: `dir/index.html` or the
+    /// original file, respectively.
+    ///
+    /// # Example
+    ///
+    /// Cache fingerprinted files, such as `app.3f9a1c2e.js`, forever, always
+    /// revalidate HTML files, and cache everything else for an hour:
+    ///
+    /// ```rust,no_run
+    /// # #[macro_use] extern crate rocket;
+    /// use rocket::fs::FileServer;
+    ///
+    /// #[launch]
+    /// fn rocket() -> _ {
+    ///     let hex = "[0-9a-f]".repeat(8);
+    ///     let server = FileServer::from("/www/public")
+    ///         .cache_control(&format!("*.{}.*", hex), "immutable, max-age=31536000")
+    ///         .cache_control("*.html", "no-cache")
+    ///         .default_cache_control("max-age=3600");
+    ///
+    ///     rocket::build().mount("/", server)
+    /// }
+    /// ```
+    pub fn cache_control<V>(mut self, pattern: &str, value: V) -> Self
+        where V: Into<Cow<'static, str>>
+    {
+        self.cache.add_rule(pattern, value.into());
+        self
+    }
+
+    /// Sets the `Cache-Control` header of responses for files that match no
+    /// rule added with [`FileServer::cache_control()`] to `value`. By default,
+    /// such responses have no `Cache-Control` header.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rocket::fs::FileServer;
+    ///
+    /// FileServer::from("/www/public").default_cache_control("no-cache");
+    /// ```
+    pub fn default_cache_control<V>(mut self, value: V) -> Self
+        where V: Into<Cow<'static, str>>
+    {
+        self.cache.set_default(value.into());
+        self
+    }
 }
 
 impl From<FileServer> for Vec<Route> {
@@ -192,6 +302,25 @@ impl From<FileServer> for Vec<Route> {
 #[crate::async_trait]
 impl Handler for FileServer {
     async fn handle<'r>(&self, req: &'r Request<'_>, data: Data<'r>) -> Outcome<'r> {
+        let spa = self.options.contains(Options::Spa);
+        match self.serve(req, data).await {
+            Outcome::Forward((data, _)) if spa && !is_asset(req) => {
+                let index = match self.root.is_file() {
+                    true => self.root.clone(),
+                    false => self.root.join("index.html"),
+                };
+
+                Outcome::from_or_forward(req, data, self.open(req, index).await)
+            }
+            outcome => outcome,
+        }
+    }
+}
+
+impl FileServer {
+    /// Serves the file or directory requested by `req`, forwarding if there is
+    /// none.
+    async fn serve<'r>(&self, req: &'r Request<'_>, data: Data<'r>) -> Outcome<'r> {
         use crate::http::uri::fmt::Path;
 
         // TODO: Should we reject dotfiles for `self.root` if !DotFiles?
@@ -203,7 +332,7 @@ impl Handler for FileServer {
             };
 
             if segments.is_empty() {
-                let file = NamedFile::open(&self.root).await.ok();
+                let file = self.open(req, self.root.clone()).await;
                 return Outcome::from_or_forward(req, data, file);
             } else {
                 return Outcome::forward(data);
@@ -227,17 +356,147 @@ impl Handler for FileServer {
                     return Outcome::from_or_forward(req, data, Redirect::permanent(normal));
                 }
 
-                if !options.contains(Options::Index) {
+                if options.contains(Options::Index) {
+                    if let Some(index) = self.open(req, p.join("index.html")).await {
+                        return Outcome::from_or_forward(req, data, index);
+                    }
+                }
+
+                if !options.contains(Options::Listing) {
                     return Outcome::forward(data);
                 }
 
-                let index = NamedFile::open(p.join("index.html")).await.ok();
-                Outcome::from_or_forward(req, data, index)
+                let root = p == self.root;
+                let listing = Listing::read(req, &p, root, allow_dotfiles).await.ok();
+                Outcome::from_or_forward(req, data, listing)
             },
-            Some(p) => Outcome::from_or_forward(req, data, NamedFile::open(p).await.ok()),
+            Some(p) => Outcome::from_or_forward(req, data, self.open(req, p).await),
             None => Outcome::forward(data),
         }
     }
+
+    /// Opens the file at `path` or, if [`Options::Precompressed`] is enabled,
+    /// the precompressed variant of it that `req` prefers.
+    async fn open(&self, req: &Request<'_>, path: PathBuf) -> Option<ServedFile<NamedFile>> {
+        let relative = match path.strip_prefix(&self.root) {
+            Ok(relative) if !relative.as_os_str().is_empty() => relative,
+            _ => Path::new(path.file_name()?),
+        };
+
+        let relative = relative.iter().map(|s| s.to_string_lossy()).collect::<Vec<_>>();
+        let cache_control = self.cache.get(&relative.join("/"));
+        if !self.options.contains(Options::Precompressed) {
+            let file = NamedFile::open(path).await.ok()?;
+            return Some(ServedFile { file, encoding: None, vary: false, cache_control });
+        }
+
+        for (ext, coding) in precompressed_variants(req) {
+            let mut variant = path.clone().into_os_string();
+            variant.push(".");
+            variant.push(ext);
+
+            let variant = PathBuf::from(variant);
+            if !tokio::fs::metadata(&variant).await.map_or(false, |m| m.is_file()) {
+                continue;
+            }
+
+            if let Ok(file) = NamedFile::open(variant).await {
+                let content_type = path.extension()
+                    .and_then(|ext| ContentType::from_extension(&ext.to_string_lossy()));
+
+                let encoding = Some((coding, content_type));
+                return Some(ServedFile { file, encoding, vary: true, cache_control });
+            }
+        }
+
+        let file = NamedFile::open(path).await.ok()?;
+        Some(ServedFile { file, encoding: None, vary: true, cache_control })
+    }
+}
+
+/// Returns `true` if `req` is for an asset rather than a client-side route of
+/// a single-page application: if its last segment has an extension or its
+/// first segment is `assets`.
+pub(crate) fn is_asset(req: &Request<'_>) -> bool {
+    use crate::http::uri::fmt;
+
+    let segments = match req.segments::<Segments<'_, fmt::Path>>(0..) {
+        Ok(segments) => segments,
+        Err(never) => match never {},
+    };
+
+    let extension = segments.num().checked_sub(1)
+        .and_then(|last| segments.get(last))
+        .map_or(false, |last| Path::new(last).extension().is_some());
+
+    extension || segments.get(0) == Some("assets")
+}
+
+/// The file extensions and content codings of precompressed variants, in order
+/// of preference.
+const PRECOMPRESSED: [(&str, &str); 2] = [("br", "br"), ("gz", "gzip")];
+
+/// Returns the file extensions and content codings of the precompressed
+/// variants that `req` accepts, most preferred first.
+pub(crate) fn precompressed_variants(req: &Request<'_>) -> Vec<(&'static str, &'static str)> {
+    let accept = req.headers().get("Accept-Encoding").collect::<Vec<_>>().join(",");
+    let qvalue = |coding: &str| accept_encoding_qvalue(&accept, |c| {
+        // `x-gzip` is an alias for `gzip`.
+        let c = c.strip_prefix("x-").or_else(|| c.strip_prefix("X-")).unwrap_or(c);
+        c.eq_ignore_ascii_case(coding)
+    });
+
+    let mut variants = PRECOMPRESSED.iter()
+        .map(|&(ext, coding)| (ext, coding, qvalue(coding)))
+        .filter(|&(_, _, q)| q > 0.0)
+        .collect::<Vec<_>>();
+
+    variants.sort_by(|a, b| b.2.partial_cmp(&a.2).unwrap_or(std::cmp::Ordering::Equal));
+    variants.into_iter().map(|(ext, coding, _)| (ext, coding)).collect()
+}
+
+/// A file served by [`FileServer`] or
+/// [`EmbeddedFileServer`](crate::fs::EmbeddedFileServer), read from `F`.
+pub(crate) struct ServedFile<F> {
+    pub(crate) file: F,
+    /// The content coding and original `Content-Type` of a precompressed file.
+    pub(crate) encoding: Option<(&'static str, Option<ContentType>)>,
+    /// Whether the choice of file depended on `Accept-Encoding`.
+    pub(crate) vary: bool,
+    /// The `Cache-Control` header value, if any.
+    pub(crate) cache_control: Option<Cow<'static, str>>,
+}
+
+impl<'r, F: Responder<'r, 'static>> Responder<'r, 'static> for ServedFile<F> {
+    fn respond_to(self, req: &'r Request<'_>) -> response::Result<'static> {
+        let mut response = self.file.respond_to(req)?;
+        if let Some((coding, content_type)) = self.encoding {
+            response.remove_header("Content-Type");
+            if let Some(content_type) = content_type {
+                response.set_header(content_type);
+            }
+
+            response.set_header(Header::new("Content-Encoding", coding));
+
+            // Ensure the variant's tag differs from that of the original file.
+            if let Some(etag) = response.headers().get_one("ETag") {
+                if let Some(tag) = etag.strip_suffix('"') {
+                    let etag = format!("{}-{}\"", tag, coding);
+                    response.set_header(Header::new("ETag", etag));
+                }
+            }
+        }
+
+        if self.vary {
+            response.adjoin_header(Header::new("Vary", "Accept-Encoding"));
+        }
+
+        if let Some(cache_control) = self.cache_control {
+            response.set_header(Header::new("Cache-Control", cache_control));
+        }
+
+        Ok(response)
+    }
 }
 
 /// A bitset representing configurable options for [`FileServer`].
@@ -251,6 +510,9 @@ impl Handler for FileServer {
 ///   * [`Options::Missing`] - Don't fail if the path to serve is missing.
 ///   * [`Options::NormalizeDirs`] - Redirect directories without a trailing
 ///     slash to ones with a trailing slash.
+///   * [`Options::Precompressed`] - Serve precompressed variants of files.
+///   * [`Options::Listing`] - Render listings of directories' contents.
+///   * [`Options::Spa`] - Serve `index.html` for client-side routes.
 ///
 /// `Options` structures can be `or`d together to select two or more options.
 /// For instance, to request that both dot files and index pages be returned,
@@ -360,6 +622,136 @@ impl Options {
     /// prevent inevitable 404 errors. This option overrides that.
     pub const Missing: Options = Options(1 << 4);
 
+    /// Serve precompressed variants of files when the client accepts them.
+    ///
+    /// When enabled, a request for a file `${file}` is answered with
+    /// `${file}.br` or `${file}.gz`, if it exists and the request's
+    /// `Accept-Encoding` header allows `br` or `gzip`, respectively. The
+    /// variant the client prefers is chosen; ties favor `br`. The response has
+    /// the `Content-Type` of `${file}`, a `Content-Encoding` of the variant's
+    /// coding, and a `Vary: Accept-Encoding` header. If no acceptable variant
+    /// exists, `${file}` itself is served, also with `Vary: Accept-Encoding`.
+    /// The variant's `ETag` is suffixed with its coding so that it differs from
+    /// that of `${file}`.
+    ///
+    /// Variants can still be requested directly by their own path.
+    ///
+    /// **Disabled by default.**
+    ///
+    /// # Example
+    ///
+    /// Given the following directory structure...
+    ///
+    /// ```text
+    /// static/
+    /// ├── app.js
+    /// ├── app.js.br
+    /// └── app.js.gz
+    /// ```
+    ///
+    /// ...requests for `/app.js` with `Accept-Encoding: gzip, br` are answered
+    /// with `app.js.br`:
+    ///
+    /// ```rust,no_run
+    /// # #[macro_use] extern crate rocket;
+    /// use rocket::fs::{FileServer, Options};
+    ///
+    /// #[launch]
+    /// fn rocket() -> _ {
+    ///     let options = Options::default() | Options::Precompressed;
+    ///     rocket::build().mount("/", FileServer::new("static", options))
+    /// }
+    /// ```
+    pub const Precompressed: Options = Options(1 << 5);
+
+    /// Respond to requests for a directory with a listing of its contents.
+    ///
+    /// When enabled, [`FileServer`] will respond to requests for a directory
+    /// with a listing of its entries, including each entry's size and
+    /// modification time, unless [`Options::Index`] is also enabled and the
+    /// directory contains an `index.html` file. The listing is a JSON document
+    /// if the request's `Accept` header prefers JSON and an HTML page
+    /// otherwise. Dotfiles are listed only if [`Options::DotFiles`] is enabled.
+    ///
+    /// Entries are sorted with directories first, then by the field named in
+    /// the `sort` query parameter (one of `name`, `size`, or `modified`,
+    /// defaulting to `name`), in the order given by the `order` parameter
+    /// (`asc` or `desc`, defaulting to `asc`). The column headers in the HTML
+    /// page link to the available orderings.
+    ///
+    /// A JSON listing has the following shape, where `modified` is a Unix
+    /// timestamp in seconds or `null` if unavailable:
+    ///
+    /// ```json
+    /// {
+    ///   "path": "/files/",
+    ///   "entries": [
+    ///     { "name": "docs", "type": "directory", "size": 0, "modified": 1697500000 },
+    ///     { "name": "notes.txt", "type": "file", "size": 1024, "modified": 1697500000 }
+    ///   ]
+    /// }
+    /// ```
+    ///
+    /// **Disabled by default.**
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # #[macro_use] extern crate rocket;
+    /// use rocket::fs::{FileServer, Options};
+    ///
+    /// #[launch]
+    /// fn rocket() -> _ {
+    ///     let options = Options::default() | Options::Listing;
+    ///     rocket::build().mount("/artifacts", FileServer::new("/srv/artifacts", options))
+    /// }
+    /// ```
+    pub const Listing: Options = Options(1 << 6);
+
+    /// Respond to requests for client-side routes of a single-page application
+    /// with the application's `index.html`.
+    ///
+    /// When enabled, a request that [`FileServer`] would otherwise forward is
+    /// answered with the file at `${root}/index.html`, or with the root itself
+    /// if it is a file and [`Options::IndexFile`] is enabled, and a status of
+    /// `200`. Requests for _assets_, those with a final path segment that has
+    /// an extension, such as `/app.js` or `/img/missing.png`, or with a first
+    /// path segment of `assets`, such as `/assets/logo`, continue to forward
+    /// and thus typically result in a `404`. Paths are relative to the mount
+    /// point.
+    ///
+    /// The fallback applies only to requests that reach the file server's
+    /// route. Routes with a lower rank, including routes with default ranks,
+    /// are tried first, so API routes mounted alongside a single-page
+    /// application take precedence over it. Use [`FileServer::spa()`] to
+    /// construct a `FileServer` with this option.
+    ///
+    /// **Disabled by default.**
+    ///
+    /// # Example
+    ///
+    /// Serve the application in `dist/` at `/`. A request for `/users/42`
+    /// returns `dist/index.html` while `/api/users/42` is handled by `user`:
+    ///
+    /// ```rust,no_run
+    /// # #[macro_use] extern crate rocket;
+    /// use rocket::fs::{FileServer, Options};
+    ///
+    /// #[get("/users/<id>")]
+    /// fn user(id: usize) -> String {
+    ///     format!("user #{}", id)
+    /// }
+    ///
+    /// #[launch]
+    /// fn rocket() -> _ {
+    ///     let options = Options::default() | Options::Spa;
+    ///     rocket::build()
+    ///         .mount("/api", routes![user])
+    ///         .mount("/", FileServer::new("dist", options))
+    /// }
+    /// ```
+    pub const Spa: Options = Options(1 << 7);
+
     /// Returns `true` if `self` is a superset of `other`. In other words,
     /// returns `true` if all of the options in `other` are also in `self`.
     ///
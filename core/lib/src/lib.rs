@@ -59,15 +59,17 @@
 //! To avoid compiling unused dependencies, Rocket gates certain features. With
 //! the exception of `http2`, all are disabled by default:
 //!
-//! | Feature   | Description                                             |
-//! |-----------|---------------------------------------------------------|
-//! | `secrets` | Support for authenticated, encrypted [private cookies]. |
-//! | `tls`     | Support for [TLS] encrypted connections.                |
-//! | `mtls`    | Support for verified clients via [mutual TLS].          |
-//! | `http2`   | Support for HTTP/2 (enabled by default).                |
-//! | `json`    | Support for [JSON (de)serialization].                   |
-//! | `msgpack` | Support for [MessagePack (de)serialization].            |
-//! | `uuid`    | Support for [UUID value parsing and (de)serialization]. |
+//! | Feature       | Description                                             |
+//! |---------------|---------------------------------------------------------|
+//! | `secrets`     | Support for authenticated, encrypted [private cookies]. |
+//! | `tls`         | Support for [TLS] encrypted connections.                |
+//! | `mtls`        | Support for verified clients via [mutual TLS].          |
+//! | `http2`       | Support for HTTP/2 (enabled by default).                |
+//! | `json`        | Support for [JSON (de)serialization].                   |
+//! | `msgpack`     | Support for [MessagePack (de)serialization].            |
+//! | `cbor`        | Support for [CBOR (de)serialization].                   |
+//! | `uuid`        | Support for [UUID value parsing and (de)serialization]. |
+//! | `compression` | Support for [response compression].                     |
 //!
 //! Disabled features can be selectively enabled in `Cargo.toml`:
 //!
@@ -85,10 +87,12 @@
 //!
 //! [JSON (de)serialization]: crate::serde::json
 //! [MessagePack (de)serialization]: crate::serde::msgpack
+//! [CBOR (de)serialization]: crate::serde::cbor
 //! [UUID value parsing and (de)serialization]: crate::serde::uuid
 //! [private cookies]: https://rocket.rs/v0.5-rc/guide/requests/#private-cookies
 //! [TLS]: https://rocket.rs/v0.5-rc/guide/configuration/#tls
 //! [mutual TLS]: crate::mtls
+//! [response compression]: crate::compression
 //!
 //! ## Configuration
 //!
@@ -137,6 +141,7 @@ pub mod route;
 pub mod serde;
 pub mod shield;
 pub mod fs;
+pub mod listener;
 
 // Reexport of HTTP everything.
 pub mod http {
@@ -164,14 +169,23 @@ pub mod http {
     pub use crate::cookies::*;
 }
 
+#[cfg(feature = "tls")]
+#[cfg_attr(nightly, doc(cfg(feature = "tls")))]
+pub mod tls;
+
 #[cfg(feature = "mtls")]
 #[cfg_attr(nightly, doc(cfg(feature = "mtls")))]
 pub mod mtls;
 
+#[cfg(feature = "compression")]
+#[cfg_attr(nightly, doc(cfg(feature = "compression")))]
+pub mod compression;
+
 /// TODO: We need a futures mod or something.
 mod trip_wire;
 mod shutdown;
 mod server;
+mod connection;
 mod ext;
 mod state;
 mod cookies;
@@ -0,0 +1,213 @@
+//! Sources of incoming connections.
+//!
+//! By default, Rocket binds a TCP socket, or a TLS-over-TCP socket when [TLS is
+//! configured](crate::config::TlsConfig), at the configured address and port.
+//! To serve on any other source of connections, implement [`Listener`] for it
+//! and launch with [`Rocket::launch_on()`](crate::Rocket::launch_on()). The
+//! listener, in turn, yields values implementing [`Connection`].
+//!
+//! Implementations are provided for tokio's [`TcpListener`], for
+//! [`UnixListener`] on Unix, and, as a [`Connection`], for tokio's in-memory
+//! [`DuplexStream`](tokio::io::DuplexStream), which makes it possible to
+//! exercise a full server without a network socket.
+//!
+//! Any listener can be wrapped in a [`ProxyListener`] to read the [PROXY
+//! protocol] headers load balancers send ahead of a connection's data. Rocket
+//! does so automatically for the listeners it binds when
+//! [`ProxyProtocol`](crate::config::ProxyProtocol) is configured.
+//!
+//! [PROXY protocol]: https://www.haproxy.org/download/2.8/doc/proxy-protocol.txt
+//!
+//! # Example
+//!
+//! Serving on a pre-bound TCP listener:
+//!
+//! ```rust,no_run
+//! # #[macro_use] extern crate rocket;
+//! use rocket::listener::TcpListener;
+//!
+//! #[get("/")]
+//! fn hello() -> &'static str {
+//!     "Hello, world!"
+//! }
+//!
+//! #[rocket::main]
+//! async fn main() -> Result<(), rocket::Error> {
+//!     let listener = TcpListener::bind("127.0.0.1:0").await.expect("bound");
+//!     let _rocket = rocket::build()
+//!         .mount("/", routes![hello])
+//!         .launch_on(listener)
+//!         .await?;
+//!
+//!     Ok(())
+//! }
+//! ```
+
+#[doc(inline)]
+pub use crate::http::private::{Listener, Connection, Certificates, PeerCredentials};
+
+#[doc(inline)]
+pub use crate::http::private::{CertificateData, TlsInfo};
+
+pub use crate::connection::{ConnectionId, ConnectionInfo};
+
+#[doc(inline)]
+pub use crate::http::private::TcpListener;
+
+#[doc(inline)]
+pub use crate::http::private::{ProxyListener, ProxyStream, ProxyHeader, IpRange};
+
+#[doc(inline)]
+#[cfg(unix)]
+#[cfg_attr(nightly, doc(cfg(unix)))]
+pub use crate::http::private::UnixListener;
+
+use std::io;
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+/// A type-erased connection from any of the listeners in [`Listeners`].
+pub(crate) struct BoxedConnection(Box<dyn Connection + Send + Unpin>);
+
+/// A type-erasing wrapper around a listener.
+struct BoxedListener<L>(L);
+
+/// A listener that accepts connections from several listeners of possibly
+/// differing types, such as a plaintext and a TLS listener.
+#[derive(Default)]
+pub(crate) struct Listeners {
+    listeners: Vec<Pin<Box<dyn Listener<Connection = BoxedConnection> + Send>>>,
+    next: usize,
+}
+
+impl Listeners {
+    pub fn push<L>(&mut self, listener: L)
+        where L: Listener + Send + Unpin + 'static,
+              <L as Listener>::Connection: Send + Unpin + 'static
+    {
+        self.listeners.push(Box::pin(BoxedListener(listener)));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.listeners.is_empty()
+    }
+}
+
+impl<L> Listener for BoxedListener<L>
+    where L: Listener + Unpin, <L as Listener>::Connection: Send + Unpin + 'static
+{
+    type Connection = BoxedConnection;
+
+    fn local_addr(&self) -> Option<SocketAddr> {
+        self.0.local_addr()
+    }
+
+    fn poll_accept(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>
+    ) -> Poll<io::Result<Self::Connection>> {
+        Pin::new(&mut self.0).poll_accept(cx)
+            .map_ok(|conn| BoxedConnection(Box::new(conn)))
+    }
+}
+
+impl Listener for Listeners {
+    type Connection = BoxedConnection;
+
+    fn local_addr(&self) -> Option<SocketAddr> {
+        self.listeners.first().and_then(|l| l.local_addr())
+    }
+
+    fn poll_accept(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>
+    ) -> Poll<io::Result<Self::Connection>> {
+        // Start polling after the last listener that yielded so that a busy
+        // listener can't starve the others.
+        let this = self.get_mut();
+        let len = this.listeners.len();
+        for i in 0..len {
+            let index = (this.next + i) % len;
+            if let Poll::Ready(result) = this.listeners[index].as_mut().poll_accept(cx) {
+                this.next = (index + 1) % len;
+                return Poll::Ready(result);
+            }
+        }
+
+        Poll::Pending
+    }
+}
+
+impl Connection for BoxedConnection {
+    fn peer_address(&self) -> Option<SocketAddr> {
+        self.0.peer_address()
+    }
+
+    fn enable_nodelay(&self) -> io::Result<()> {
+        self.0.enable_nodelay()
+    }
+
+    fn peer_certificates(&self) -> Option<Certificates> {
+        self.0.peer_certificates()
+    }
+
+    fn peer_credentials(&self) -> Option<PeerCredentials> {
+        self.0.peer_credentials()
+    }
+
+    fn proxy_header(&self) -> Option<Arc<ProxyHeader>> {
+        self.0.proxy_header()
+    }
+
+    fn tls_info(&self) -> Option<TlsInfo> {
+        self.0.tls_info()
+    }
+
+    fn poll_handshake(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut *self.0).poll_handshake(cx)
+    }
+}
+
+impl AsyncRead for BoxedConnection {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        Pin::new(&mut *self.0).poll_read(cx, buf)
+    }
+}
+
+impl AsyncWrite for BoxedConnection {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut *self.0).poll_write(cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut *self.0).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut *self.0).poll_shutdown(cx)
+    }
+
+    fn poll_write_vectored(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[io::IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut *self.0).poll_write_vectored(cx, bufs)
+    }
+
+    fn is_write_vectored(&self) -> bool {
+        self.0.is_write_vectored()
+    }
+}
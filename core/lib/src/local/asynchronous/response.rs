@@ -129,7 +129,14 @@ impl LocalResponse<'_> {
         self.blocking_read(|r| rmp_serde::from_read(r)).await?.ok()
     }
 
-    #[cfg(any(feature = "json", feature = "msgpack"))]
+    #[cfg(feature = "cbor")]
+    async fn _into_cbor<T: Send + 'static>(self) -> Option<T>
+        where T: serde::de::DeserializeOwned
+    {
+        self.blocking_read(|r| ciborium::de::from_reader(r)).await?.ok()
+    }
+
+    #[cfg(any(feature = "json", feature = "msgpack", feature = "cbor"))]
     async fn blocking_read<T, F>(mut self, f: F) -> Option<T>
         where T: Send + 'static,
               F: FnOnce(&mut dyn io::Read) -> T + Send + 'static
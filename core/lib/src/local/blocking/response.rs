@@ -85,6 +85,13 @@ impl LocalResponse<'_> {
         rmp_serde::from_read(self).ok()
     }
 
+    #[cfg(feature = "cbor")]
+    fn _into_cbor<T: Send + 'static>(self) -> Option<T>
+        where T: serde::de::DeserializeOwned
+    {
+        ciborium::de::from_reader(self).ok()
+    }
+
     // Generates the public API methods, which call the private methods above.
     pub_response_impl!("# use rocket::local::blocking::Client;\n\
         use rocket::local::blocking::LocalResponse;");
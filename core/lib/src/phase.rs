@@ -113,5 +113,9 @@ phases! {
         pub(crate) config: Config,
         pub(crate) state: Container![Send + Sync],
         pub(crate) shutdown: Shutdown,
+        pub(crate) addresses: Vec<std::net::SocketAddr>,
+        pub(crate) connections: std::sync::Arc<crate::connection::Counters>,
+        #[cfg(feature = "tls")]
+        pub(crate) tls_reloader: crate::tls::TlsReloader,
     }
 }
@@ -7,6 +7,8 @@ use crate::outcome::Outcome::*;
 
 use crate::http::{Status, ContentType, Accept, Method, CookieJar};
 use crate::http::uri::{Host, Origin};
+use crate::request::PeerCredentials;
+use crate::listener::ProxyHeader;
 
 /// Type alias for the `Outcome` of a `FromRequest` conversion.
 pub type Outcome<S, E> = outcome::Outcome<S, (Status, E), Status>;
@@ -179,6 +181,21 @@ impl<S, E> IntoOutcome<S, (Status, E), Status> for Result<S, E> {
 ///     via [`Request::remote()`]. If the remote address is not known, the
 ///     request is forwarded with a 404 Not Found status.
 ///
+///   * **PeerCredentials**
+///
+///     Extracts the [`PeerCredentials`] of the process on the other end of the
+///     connection. Credentials are only known for connections accepted on a
+///     Unix domain socket; see [`UnixConfig`](crate::config::UnixConfig). If
+///     the credentials are not known, the request is forwarded with a 404 Not
+///     Found status.
+///
+///   * **&ProxyHeader**
+///
+///     Extracts the [`ProxyHeader`] a trusted upstream sent ahead of the
+///     connection's data; see
+///     [`ProxyProtocol`](crate::config::ProxyProtocol). If no header was sent,
+///     the request is forwarded with a 404 Not Found status.
+///
 ///   * **Option&lt;T>** _where_ **T: FromRequest**
 ///
 ///     The type `T` is derived from the incoming request using `T`'s
@@ -495,6 +512,30 @@ impl<'r> FromRequest<'r> for SocketAddr {
     }
 }
 
+#[crate::async_trait]
+impl<'r> FromRequest<'r> for PeerCredentials {
+    type Error = std::convert::Infallible;
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        match request.connection.peer_credentials {
+            Some(credentials) => Success(credentials),
+            None => Forward(Status::NotFound)
+        }
+    }
+}
+
+#[crate::async_trait]
+impl<'r> FromRequest<'r> for &'r ProxyHeader {
+    type Error = std::convert::Infallible;
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        match request.connection.proxy_header {
+            Some(ref header) => Success(header),
+            None => Forward(Status::NotFound)
+        }
+    }
+}
+
 #[crate::async_trait]
 impl<'r, T: FromRequest<'r>> FromRequest<'r> for Result<T, T::Error> {
     type Error = std::convert::Infallible;
@@ -14,6 +14,9 @@ pub use self::from_param::{FromParam, FromSegments};
 #[doc(inline)]
 pub use crate::response::flash::FlashMessage;
 
+#[doc(inline)]
+pub use crate::http::private::PeerCredentials;
+
 pub(crate) use self::request::ConnectionMeta;
 
 crate::export! {
@@ -16,7 +16,8 @@ use crate::data::Limits;
 use crate::http::{hyper, Method, Header, HeaderMap};
 use crate::http::{ContentType, Accept, MediaType, CookieJar, Cookie};
 use crate::http::uncased::UncasedStr;
-use crate::http::private::Certificates;
+use crate::http::private::{Certificates, PeerCredentials, ProxyHeader};
+use crate::listener::ConnectionInfo;
 use crate::http::uri::{fmt::Path, Origin, Segments, Host, Authority};
 
 /// The type of an incoming web request.
@@ -39,6 +40,9 @@ pub(crate) struct ConnectionMeta {
     pub remote: Option<SocketAddr>,
     #[cfg_attr(not(feature = "mtls"), allow(dead_code))]
     pub client_certificates: Option<Certificates>,
+    pub peer_credentials: Option<PeerCredentials>,
+    pub proxy_header: Option<Arc<ProxyHeader>>,
+    pub info: Option<Arc<ConnectionInfo>>,
 }
 
 /// Information derived from the request.
@@ -48,6 +52,7 @@ pub(crate) struct RequestState<'r> {
     pub cookies: CookieJar<'r>,
     pub accept: Storage<Option<Accept>>,
     pub content_type: Storage<Option<ContentType>>,
+    pub id: Storage<String>,
     pub cache: Arc<Container![Send + Sync]>,
     pub host: Option<Host<'r>>,
 }
@@ -72,6 +77,7 @@ impl RequestState<'_> {
             cookies: self.cookies.clone(),
             accept: self.accept.clone(),
             content_type: self.content_type.clone(),
+            id: self.id.clone(),
             cache: self.cache.clone(),
             host: self.host.clone(),
         }
@@ -93,6 +99,9 @@ impl<'r> Request<'r> {
             connection: ConnectionMeta {
                 remote: None,
                 client_certificates: None,
+                peer_credentials: None,
+                proxy_header: None,
+                info: None,
             },
             state: RequestState {
                 rocket,
@@ -100,6 +109,7 @@ impl<'r> Request<'r> {
                 cookies: CookieJar::new(rocket.config()),
                 accept: Storage::new(),
                 content_type: Storage::new(),
+                id: Storage::new(),
                 cache: Arc::new(<Container![Send + Sync]>::new()),
                 host: None,
             }
@@ -572,6 +582,42 @@ impl<'r> Request<'r> {
         }).as_ref()
     }
 
+    /// Returns the ID of `self`, which identifies the request in error
+    /// documents produced by Rocket's default catcher.
+    ///
+    /// The ID is the value of the request's `X-Request-ID` header, typically
+    /// set by a proxy or the client, if it is present and consists of at most
+    /// 128 ASCII alphanumeric, `-`, `_`, `.`, `:`, `/`, `+`, or `=`
+    /// characters. Otherwise, it is a random 16 character hexadecimal string.
+    /// The ID is computed once and does not change for the lifetime of the
+    /// request.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::http::Header;
+    ///
+    /// # let c = rocket::local::blocking::Client::debug_with(vec![]).unwrap();
+    /// # let get = |uri| c.get(uri);
+    /// let req = get("/").header(Header::new("X-Request-ID", "f058ebd6-02f7"));
+    /// assert_eq!(req.id(), "f058ebd6-02f7");
+    ///
+    /// let req = get("/");
+    /// assert_eq!(req.id().len(), 16);
+    /// assert_eq!(req.id(), req.id());
+    /// ```
+    pub fn id(&self) -> &str {
+        self.state.id.get_or_set(|| {
+            let valid = |c: char| c.is_ascii_alphanumeric() || "-_.:/+=".contains(c);
+            match self.headers().get_one("X-Request-ID") {
+                Some(id) if !id.is_empty() && id.len() <= 128 && id.chars().all(valid) => {
+                    id.to_string()
+                }
+                _ => format!("{:016x}", rand::random::<u64>()),
+            }
+        })
+    }
+
     /// Returns the media type "format" of the request.
     ///
     /// The "format" of a request is either the Content-Type, if the request
@@ -937,6 +983,13 @@ impl<'r> Request<'r> {
         }
     }
 
+    /// Removes all headers with name `name`.
+    #[inline]
+    #[cfg(feature = "compression")]
+    pub(crate) fn remove_header(&mut self, name: &str) {
+        self.headers.remove(name);
+    }
+
     /// Get the `n`th non-empty path segment, 0-indexed, after the mount point
     /// for the currently matched route, as a string, if it exists. Used by
     /// codegen.
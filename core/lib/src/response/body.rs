@@ -66,6 +66,8 @@ pub struct Body<'r> {
     inner: Inner<'r>,
     /// The maximum chunk size.
     max_chunk: usize,
+    /// Whether the body may be served in ranges.
+    ranged: bool,
 }
 
 /// A "trait alias" of sorts so we can use `AsyncRead + AsyncSeek` in `dyn`.
@@ -75,7 +77,7 @@ pub trait AsyncReadSeek: AsyncRead + AsyncSeek { }
 impl<T: AsyncRead + AsyncSeek> AsyncReadSeek for T {  }
 
 /// A pinned `AsyncRead + AsyncSeek` body type.
-type SizedBody<'r> = Pin<Box<dyn AsyncReadSeek + Send + 'r>>;
+pub(crate) type SizedBody<'r> = Pin<Box<dyn AsyncReadSeek + Send + 'r>>;
 
 /// A pinned `AsyncRead` (not `AsyncSeek`) body type.
 type UnsizedBody<'r> = Pin<Box<dyn AsyncRead + Send + 'r>>;
@@ -97,6 +99,7 @@ impl Default for Body<'_> {
             size: Some(0),
             inner: Inner::None,
             max_chunk: Body::DEFAULT_MAX_CHUNK,
+            ranged: false,
         }
     }
 }
@@ -114,6 +117,7 @@ impl<'r> Body<'r> {
             size: preset_size,
             inner: Inner::Seekable(Box::pin(body)),
             max_chunk: Body::DEFAULT_MAX_CHUNK,
+            ranged: false,
         }
     }
 
@@ -124,6 +128,36 @@ impl<'r> Body<'r> {
             size: None,
             inner: Inner::Unsized(Box::pin(body)),
             max_chunk: Body::DEFAULT_MAX_CHUNK,
+            ranged: false,
+        }
+    }
+
+    /// Like [`Body::with_unsized()`] but with a known size of `size`.
+    pub(crate) fn with_unsized_of_size<T>(body: T, size: usize) -> Self
+        where T: AsyncRead + Send + 'r
+    {
+        Body { size: Some(size), ..Body::with_unsized(body) }
+    }
+
+    /// Allows the body, if it is seekable, to be served in ranges.
+    pub(crate) fn allow_ranges(&mut self) {
+        self.ranged = true;
+    }
+
+    /// Whether the body is seekable and may be served in ranges.
+    pub(crate) fn is_ranged(&self) -> bool {
+        self.ranged && matches!(self.inner, Inner::Seekable(_))
+    }
+
+    /// Takes the body if it is seekable, leaving an empty body in its place.
+    pub(crate) fn take_seekable(&mut self) -> Option<SizedBody<'r>> {
+        if !matches!(self.inner, Inner::Seekable(_)) {
+            return None;
+        }
+
+        match std::mem::take(self).inner {
+            Inner::Seekable(body) => Some(body),
+            _ => None,
         }
     }
 
@@ -138,6 +172,7 @@ impl<'r> Body<'r> {
                 size: body.size,
                 inner: Inner::Phantom(b),
                 max_chunk: body.max_chunk,
+                ranged: body.ranged,
             },
             Inner::Unsized(_) | Inner::None => Body::default()
         };
@@ -0,0 +1,337 @@
+use std::io::Cursor;
+use std::time::SystemTime;
+
+use time::{OffsetDateTime, PrimitiveDateTime, UtcOffset};
+use time::{macros::format_description, format_description::FormatItem};
+
+use crate::request::Request;
+use crate::response::{self, Responder, Response};
+use crate::http::{Header, Method, Status};
+use crate::http::private::fnv1a;
+
+/// The `IMF-fixdate` format of HTTP dates.
+static HTTP_DATE_FMT: &[FormatItem<'_>] = format_description!(
+    "[weekday repr:short], [day] [month repr:short] [year] [hour]:[minute]:[second] GMT"
+);
+
+/// A responder that adds an `ETag` computed from the response body.
+///
+/// The wrapped responder's body is read into memory and hashed into an entity
+/// tag which is set as the response's `ETag`. The hash is stable across Rust
+/// releases, so tags, and thus client caches, survive a toolchain upgrade as
+/// long as the body doesn't change. The tag is _strong_ when
+/// constructed with [`Conditional::new()`] and _weak_ when constructed with
+/// [`Conditional::weak()`]. Use a weak tag when semantically equivalent bodies
+/// may differ byte-for-byte, for instance, in the order of JSON object keys.
+/// Responses that already have an `ETag` are left unchanged, as are responses
+/// with a non-`2xx` status.
+///
+/// Like every response with an `ETag` or `Last-Modified` header, the response
+/// is then subject to the request's preconditions. See [Preconditions] for
+/// details.
+///
+/// [Preconditions]: #preconditions
+///
+/// Because the body is buffered to compute the tag, `Conditional` is not
+/// suitable for unbounded streams.
+///
+/// # Example
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// # #[cfg(feature = "json")] mod example {
+/// use rocket::response::Conditional;
+/// use rocket::serde::json::{Json, Value, json};
+///
+/// #[get("/report")]
+/// fn report() -> Conditional<Json<Value>> {
+///     // A request with an `If-None-Match` header containing the tag from a
+///     // previous response receives a `304 Not Modified` with no body.
+///     Conditional::weak(Json(json!({ "total": 42 })))
+/// }
+/// # }
+/// ```
+///
+/// # Preconditions
+///
+/// Rocket evaluates the preconditions of `GET` and `HEAD` requests, in the
+/// order prescribed by RFC 9110, for any successful (`2xx`) response with an
+/// `ETag` or `Last-Modified` header, including responses from
+/// [`NamedFile`](crate::fs::NamedFile) and thus
+/// [`FileServer`](crate::fs::FileServer):
+///
+///   * **`If-Match`**: responds `412 Precondition Failed` if no listed tag
+///     strongly matches the `ETag`.
+///   * **`If-Unmodified-Since`**: if there is no `If-Match`, responds `412
+///     Precondition Failed` if the representation was modified after the date.
+///   * **`If-None-Match`**: responds `304 Not Modified` if a listed tag weakly
+///     matches the `ETag`.
+///   * **`If-Modified-Since`**: if there is no `If-None-Match`, responds `304
+///     Not Modified` if the representation was not modified after the date.
+///
+/// `304` and `412` responses have no body and no `Content-Type`; all other
+/// headers, including the validators, are retained. Dates must be in the
+/// `IMF-fixdate` format. Invalid dates are ignored.
+///
+/// Preconditions are evaluated after the handler runs, so they are _not_
+/// evaluated for other methods, where the handler's side effects would have
+/// already occurred. Handlers for unsafe methods must check preconditions
+/// themselves.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Conditional<R> {
+    responder: R,
+    weak: bool,
+}
+
+impl<R> Conditional<R> {
+    /// Wraps `responder` so that its response is tagged with a strong `ETag`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::response::Conditional;
+    ///
+    /// let response = Conditional::new("Hello, world!");
+    /// ```
+    pub fn new(responder: R) -> Self {
+        Conditional { responder, weak: false }
+    }
+
+    /// Wraps `responder` so that its response is tagged with a weak `ETag`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::response::Conditional;
+    ///
+    /// let response = Conditional::weak("Hello, world!");
+    /// ```
+    pub fn weak(responder: R) -> Self {
+        Conditional { responder, weak: true }
+    }
+}
+
+/// Marks whether, and how, the route's response should be tagged.
+struct Tagging(Option<bool>);
+
+impl<'r, 'o: 'r, R: Responder<'r, 'o>> Responder<'r, 'o> for Conditional<R> {
+    fn respond_to(self, req: &'r Request<'_>) -> response::Result<'o> {
+        req.local_cache(|| Tagging(Some(self.weak)));
+        self.responder.respond_to(req)
+    }
+}
+
+/// Formats `time` as an HTTP date.
+pub(crate) fn format_http_date(time: SystemTime) -> Option<String> {
+    OffsetDateTime::from(time)
+        .to_offset(UtcOffset::UTC)
+        .format(HTTP_DATE_FMT)
+        .ok()
+}
+
+fn parse_http_date(string: &str) -> Option<OffsetDateTime> {
+    PrimitiveDateTime::parse(string.trim(), HTTP_DATE_FMT)
+        .ok()
+        .map(|date| date.assume_utc())
+}
+
+/// An entity tag. `tag` includes the surrounding quotes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct EntityTag<'a> {
+    weak: bool,
+    tag: &'a str,
+}
+
+impl<'a> EntityTag<'a> {
+    fn parse(string: &'a str) -> Option<EntityTag<'a>> {
+        let string = string.trim();
+        let (weak, tag) = match string.strip_prefix("W/") {
+            Some(tag) => (true, tag),
+            None => (false, string),
+        };
+
+        let opaque = tag.strip_prefix('"')?.strip_suffix('"')?;
+        if opaque.contains('"') {
+            return None;
+        }
+
+        Some(EntityTag { weak, tag })
+    }
+
+    /// Parses a comma-separated list of entity tags, stopping at the first
+    /// invalid entry.
+    fn parse_list(mut string: &'a str) -> Vec<EntityTag<'a>> {
+        let mut tags = vec![];
+        loop {
+            string = string.trim_start_matches(|c: char| c == ',' || c.is_ascii_whitespace());
+            let (weak, rest) = match string.strip_prefix("W/") {
+                Some(rest) => (true, rest),
+                None => (false, string),
+            };
+
+            let end = match rest.strip_prefix('"').and_then(|r| r.find('"')) {
+                Some(i) => i + 2,
+                None => break,
+            };
+
+            tags.push(EntityTag { weak, tag: &rest[..end] });
+            string = &rest[end..];
+        }
+
+        tags
+    }
+
+    fn strong_eq(&self, other: &EntityTag<'_>) -> bool {
+        !self.weak && !other.weak && self.tag == other.tag
+    }
+
+    fn weak_eq(&self, other: &EntityTag<'_>) -> bool {
+        self.tag == other.tag
+    }
+}
+
+/// Returns `true` if the `If-Match` or `If-None-Match` value `list` matches
+/// `etag` using the strong or weak comparison.
+fn list_matches(list: &str, etag: Option<EntityTag<'_>>, strong: bool) -> bool {
+    if list.trim() == "*" {
+        return true;
+    }
+
+    let etag = match etag {
+        Some(etag) => etag,
+        None => return false,
+    };
+
+    EntityTag::parse_list(list).iter()
+        .any(|tag| if strong { tag.strong_eq(&etag) } else { tag.weak_eq(&etag) })
+}
+
+/// Tags the response if it was marked by [`Conditional`], then evaluates the
+/// request's preconditions against the response.
+pub(crate) async fn evaluate_preconditions(req: &Request<'_>, response: &mut Response<'_>) {
+    if !matches!(req.method(), Method::Get | Method::Head)
+        || !response.status().class().is_success()
+    {
+        return;
+    }
+
+    let tagging = req.local_cache(|| Tagging(None)).0;
+    if let (Some(weak), false) = (tagging, response.headers().contains("ETag")) {
+        let ranged = response.body().is_ranged();
+        let max_chunk = response.body().max_chunk_size();
+        let bytes = match response.body_mut().to_bytes().await {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                error_!("Failed to read response body to compute its ETag: {}.", e);
+                response.set_status(Status::InternalServerError);
+                return;
+            }
+        };
+
+        let prefix = if weak { "W/" } else { "" };
+        let etag = format!("{}\"{:x}-{:x}\"", prefix, bytes.len(), fnv1a(&bytes));
+        response.set_header(Header::new("ETag", etag));
+        response.set_sized_body(bytes.len(), Cursor::new(bytes));
+        response.set_max_chunk_size(max_chunk);
+        if ranged {
+            response.body_mut().allow_ranges();
+        }
+    }
+
+    // Responses without validators opted out of conditional requests.
+    if !response.headers().contains("ETag") && !response.headers().contains("Last-Modified") {
+        return;
+    }
+
+    let etag = response.headers().get_one("ETag").and_then(EntityTag::parse);
+    let last_modified = response.headers().get_one("Last-Modified").and_then(parse_http_date);
+    let header = |name: &str| {
+        let values = req.headers().get(name).collect::<Vec<_>>();
+        if values.is_empty() { None } else { Some(values.join(",")) }
+    };
+
+    let date = |name: &str| req.headers().get_one(name).and_then(parse_http_date);
+    let failed = if let Some(if_match) = header("If-Match") {
+        !list_matches(&if_match, etag, true)
+    } else if let (Some(since), Some(modified)) = (date("If-Unmodified-Since"), last_modified) {
+        modified > since
+    } else {
+        false
+    };
+
+    let status = if failed {
+        Status::PreconditionFailed
+    } else if let Some(if_none_match) = header("If-None-Match") {
+        match list_matches(&if_none_match, etag, false) {
+            true => Status::NotModified,
+            false => return,
+        }
+    } else if let (Some(since), Some(modified)) = (date("If-Modified-Since"), last_modified) {
+        match modified <= since {
+            true => Status::NotModified,
+            false => return,
+        }
+    } else {
+        return;
+    };
+
+    info_!("Precondition evaluation responds with {}.", status);
+    response.set_status(status);
+    response.remove_header("Content-Type");
+    response.remove_header("Content-Length");
+    response.body_mut().take();
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, SystemTime};
+
+    use time::OffsetDateTime;
+
+    use super::{EntityTag, format_http_date, list_matches, parse_http_date};
+
+    fn etag(weak: bool, tag: &str) -> EntityTag<'_> {
+        EntityTag { weak, tag }
+    }
+
+    #[test]
+    fn test_http_dates() {
+        let time = SystemTime::UNIX_EPOCH + Duration::from_secs(784111777);
+        let date = format_http_date(time).unwrap();
+        assert_eq!(date, "Sun, 06 Nov 1994 08:49:37 GMT");
+        assert_eq!(parse_http_date(&date), Some(OffsetDateTime::from(time)));
+
+        assert!(parse_http_date("Sunday, 06-Nov-94 08:49:37 GMT").is_none());
+        assert!(parse_http_date("Sun, 06 Nov 1994 08:49:37").is_none());
+        assert!(parse_http_date("yesterday").is_none());
+    }
+
+    #[test]
+    fn test_entity_tags() {
+        assert_eq!(EntityTag::parse("\"abc\""), Some(etag(false, "\"abc\"")));
+        assert_eq!(EntityTag::parse(" W/\"abc\" "), Some(etag(true, "\"abc\"")));
+        assert_eq!(EntityTag::parse("\"\""), Some(etag(false, "\"\"")));
+        assert_eq!(EntityTag::parse("abc"), None);
+        assert_eq!(EntityTag::parse("\"abc"), None);
+        assert_eq!(EntityTag::parse("\"a\", \"b\""), None);
+        assert_eq!(EntityTag::parse("\"a\"b"), None);
+
+        let list = EntityTag::parse_list("\"a\", W/\"b\",,\"c,d\" ,bad, \"e\"");
+        assert_eq!(list, [etag(false, "\"a\""), etag(true, "\"b\""), etag(false, "\"c,d\"")]);
+    }
+
+    #[test]
+    fn test_list_matches() {
+        let strong = Some(etag(false, "\"a\""));
+        let weak = Some(etag(true, "\"a\""));
+
+        assert!(list_matches("*", None, true));
+        assert!(list_matches("\"b\", \"a\"", strong, true));
+        assert!(list_matches("W/\"a\"", strong, false));
+        assert!(list_matches("\"a\"", weak, false));
+        assert!(!list_matches("W/\"a\"", strong, true));
+        assert!(!list_matches("\"a\"", weak, true));
+        assert!(!list_matches("\"b\"", strong, false));
+        assert!(!list_matches("\"a\"", None, false));
+    }
+}
@@ -46,9 +46,10 @@ macro_rules! ctrs {
             ///
             /// Delegates the remainder of the response to the wrapped responder.
             ///
-            /// **Note:** Unlike types like [`Json`](crate::serde::json::Json)
-            /// and [`MsgPack`](crate::serde::msgpack::MsgPack), this type _does
-            /// not_ serialize data in any way. You should _always_ use those
+            /// **Note:** Unlike types like [`Json`](crate::serde::json::Json),
+            /// [`MsgPack`](crate::serde::msgpack::MsgPack), and
+            /// [`Cbor`](crate::serde::cbor::Cbor), this type _does not_
+            /// serialize data in any way. You should _always_ use those
             /// types to respond with serializable data. Additionally, you
             /// should _always_ use [`NamedFile`](crate::fs::NamedFile), which
             /// automatically sets a `Content-Type`, to respond with file data.
@@ -71,6 +72,7 @@ ctrs! {
     RawJson: JSON, "JSON", "application/json",
     RawXml: XML, "XML", "text/xml",
     RawMsgPack: MsgPack, "MessagePack", "application/msgpack",
+    RawCbor: CBOR, "CBOR", "application/cbor",
     RawHtml: HTML, "HTML", "text/html",
     RawText: Text, "plain text", "text/plain",
     RawCss: CSS, "CSS", "text/css",
@@ -21,6 +21,9 @@ mod debug;
 mod body;
 
 pub(crate) mod flash;
+pub(crate) mod problem;
+pub(crate) mod ranged;
+pub(crate) mod conditional;
 
 pub mod content;
 pub mod status;
@@ -34,7 +37,10 @@ pub use self::body::Body;
 pub use self::responder::Responder;
 pub use self::redirect::Redirect;
 pub use self::flash::Flash;
+pub use self::problem::Problem;
 pub use self::debug::Debug;
+pub use self::ranged::Ranged;
+pub use self::conditional::Conditional;
 
 /// Type alias for the `Result` of a [`Responder::respond_to()`] call.
 pub type Result<'r> = std::result::Result<Response<'r>, crate::http::Status>;
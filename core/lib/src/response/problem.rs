@@ -0,0 +1,409 @@
+use std::fmt::Write;
+use std::borrow::Cow;
+
+use figment::value::{Dict, Empty, Num, Value};
+use indexmap::IndexMap;
+use serde::Serialize;
+
+use crate::form;
+use crate::request::Request;
+use crate::response::{self, Responder};
+use crate::http::{ContentType, MediaType, Status};
+
+/// An RFC 9457 problem details response: an `application/problem+json`
+/// document describing an error.
+///
+/// A problem document consists of the standard members `type`, `title`,
+/// `status`, `detail`, and `instance` and any number of _extension_ members.
+/// [`Problem::new()`] sets `status` and sets `title` to the status' reason
+/// phrase; the remaining members are set with the builder methods. When `type`
+/// is unset, it is omitted and thus takes on its default value of
+/// `about:blank`.
+///
+/// # Example
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// use rocket::http::Status;
+/// use rocket::response::Problem;
+///
+/// #[post("/transfer/<amount>")]
+/// fn transfer(amount: u64) -> Result<&'static str, Problem> {
+///     let balance = 30;
+///     if amount > balance {
+///         return Err(Problem::new(Status::Forbidden)
+///             .with_type("https://example.com/probs/out-of-credit")
+///             .with_title("You do not have enough credit.")
+///             .with_detail(format!("Your current balance is {}.", balance))
+///             .with_instance("/account/12345/msgs/abc")
+///             .with_extension("balance", balance));
+///     }
+///
+///     Ok("transferred")
+/// }
+/// ```
+///
+/// # Guard Errors
+///
+/// When [`Config::problem_details`] is enabled, Rocket's default catcher
+/// responds with a problem document to requests that prefer JSON. If the error
+/// was caused by a failing [`Form`] or [`Json`] data guard, the document
+/// includes the guard's errors, as produced by the `From` implementations for
+/// [`form::Errors`] and [`json::Error`], in its extension members.
+///
+/// [`Config::problem_details`]: crate::Config::problem_details
+/// [`Form`]: crate::form::Form
+/// [`Json`]: crate::serde::json::Json
+/// [`json::Error`]: crate::serde::json::Error
+#[derive(Debug, Clone, PartialEq)]
+pub struct Problem {
+    status: Status,
+    kind: Option<Cow<'static, str>>,
+    title: Option<Cow<'static, str>>,
+    detail: Option<Cow<'static, str>>,
+    instance: Option<Cow<'static, str>>,
+    extensions: IndexMap<String, Value>,
+}
+
+/// The members defined by RFC 9457, which cannot be used as extensions.
+const STANDARD_MEMBERS: &[&str] = &["type", "title", "status", "detail", "instance"];
+
+/// The `Problem` recorded by a failing data guard for the default catcher.
+struct GuardProblem(Option<Problem>);
+
+impl Problem {
+    /// The media type of problem documents: `application/problem+json`.
+    pub(crate) const MEDIA_TYPE: MediaType =
+        MediaType::const_new("application", "problem+json", &[]);
+
+    /// Creates a new problem with the status `status` and a `title` of the
+    /// status' reason phrase, if it has one.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::http::Status;
+    /// use rocket::response::Problem;
+    ///
+    /// let problem = Problem::new(Status::NotFound);
+    /// assert_eq!(problem.status(), Status::NotFound);
+    /// assert_eq!(problem.title(), Some("Not Found"));
+    /// assert_eq!(problem.type_uri(), None);
+    /// ```
+    pub fn new(status: Status) -> Problem {
+        Problem {
+            status,
+            kind: None,
+            title: status.reason().map(Cow::Borrowed),
+            detail: None,
+            instance: None,
+            extensions: IndexMap::new(),
+        }
+    }
+
+    /// Sets the `type` member: a URI reference identifying the problem type.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::http::Status;
+    /// use rocket::response::Problem;
+    ///
+    /// let problem = Problem::new(Status::Forbidden)
+    ///     .with_type("https://example.com/probs/out-of-credit");
+    ///
+    /// assert_eq!(problem.type_uri(), Some("https://example.com/probs/out-of-credit"));
+    /// ```
+    pub fn with_type<T: Into<Cow<'static, str>>>(mut self, uri: T) -> Self {
+        self.kind = Some(uri.into());
+        self
+    }
+
+    /// Sets the `title` member: a short summary of the problem type.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::http::Status;
+    /// use rocket::response::Problem;
+    ///
+    /// let problem = Problem::new(Status::Forbidden).with_title("Out of credit.");
+    /// assert_eq!(problem.title(), Some("Out of credit."));
+    /// ```
+    pub fn with_title<T: Into<Cow<'static, str>>>(mut self, title: T) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    /// Sets the `detail` member: an explanation specific to this occurrence of
+    /// the problem.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::http::Status;
+    /// use rocket::response::Problem;
+    ///
+    /// let problem = Problem::new(Status::Forbidden).with_detail("Your balance is 30.");
+    /// assert_eq!(problem.detail(), Some("Your balance is 30."));
+    /// ```
+    pub fn with_detail<T: Into<Cow<'static, str>>>(mut self, detail: T) -> Self {
+        self.detail = Some(detail.into());
+        self
+    }
+
+    /// Sets the `instance` member: a URI reference identifying this occurrence
+    /// of the problem.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::http::Status;
+    /// use rocket::response::Problem;
+    ///
+    /// let problem = Problem::new(Status::Forbidden).with_instance("/account/12345");
+    /// assert_eq!(problem.instance(), Some("/account/12345"));
+    /// ```
+    pub fn with_instance<T: Into<Cow<'static, str>>>(mut self, instance: T) -> Self {
+        self.instance = Some(instance.into());
+        self
+    }
+
+    /// Sets the extension member `name` to `value`, replacing any previous
+    /// value. Extensions are emitted in the order they were first set.
+    ///
+    /// The names of the standard members (`type`, `title`, `status`, `detail`,
+    /// and `instance`) cannot be used as extensions; attempts to set them, as
+    /// well as values that fail to serialize, are ignored with a warning.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::http::Status;
+    /// use rocket::response::Problem;
+    ///
+    /// let problem = Problem::new(Status::Forbidden)
+    ///     .with_extension("balance", 30)
+    ///     .with_extension("accounts", ["/account/12345", "/account/67890"])
+    ///     .with_extension("status", "ignored");
+    ///
+    /// assert_eq!(problem.extension("balance"), Some(&30.into()));
+    /// assert!(problem.extension("accounts").is_some());
+    /// assert!(problem.extension("status").is_none());
+    /// ```
+    pub fn with_extension<N, V>(mut self, name: N, value: V) -> Self
+        where N: Into<String>, V: Serialize
+    {
+        let name = name.into();
+        if STANDARD_MEMBERS.contains(&name.as_str()) {
+            warn_!("Ignoring problem extension with reserved name `{}`.", name);
+            return self;
+        }
+
+        match Value::serialize(value) {
+            Ok(value) => { self.extensions.insert(name, value); }
+            Err(e) => warn_!("Ignoring problem extension `{}`: {}", name, e),
+        }
+
+        self
+    }
+
+    /// Returns the `status` member.
+    pub fn status(&self) -> Status {
+        self.status
+    }
+
+    /// Returns the `type` member, if it is set.
+    pub fn type_uri(&self) -> Option<&str> {
+        self.kind.as_deref()
+    }
+
+    /// Returns the `title` member, if it is set.
+    pub fn title(&self) -> Option<&str> {
+        self.title.as_deref()
+    }
+
+    /// Returns the `detail` member, if it is set.
+    pub fn detail(&self) -> Option<&str> {
+        self.detail.as_deref()
+    }
+
+    /// Returns the `instance` member, if it is set.
+    pub fn instance(&self) -> Option<&str> {
+        self.instance.as_deref()
+    }
+
+    /// Returns the value of the extension member `name`, if it is set.
+    pub fn extension(&self, name: &str) -> Option<&Value> {
+        self.extensions.get(name)
+    }
+
+    /// Returns the problem document as a JSON string.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::http::Status;
+    /// use rocket::response::Problem;
+    ///
+    /// let problem = Problem::new(Status::NotFound).with_extension("id", 7);
+    /// assert_eq!(problem.to_json(), r#"{"title":"Not Found","status":404,"id":7}"#);
+    /// ```
+    pub fn to_json(&self) -> String {
+        fn member(json: &mut String, name: &str, value: Option<&str>) {
+            if let Some(value) = value {
+                let _ = write!(json, "{}:{},", json_string(name), json_string(value));
+            }
+        }
+
+        let mut json = String::from("{");
+        member(&mut json, "type", self.type_uri());
+        member(&mut json, "title", self.title());
+        let _ = write!(json, "\"status\":{},", self.status.code);
+        member(&mut json, "detail", self.detail());
+        member(&mut json, "instance", self.instance());
+
+        for (name, value) in &self.extensions {
+            let _ = write!(json, "{}:", json_string(name));
+            write_json_value(&mut json, value);
+            json.push(',');
+        }
+
+        json.pop();
+        json.push('}');
+        json
+    }
+
+    /// Records `problem` as the cause of `req`'s failure for use by the default
+    /// catcher, if problem details are enabled and no problem was recorded.
+    pub(crate) fn record<F: FnOnce() -> Problem>(req: &Request<'_>, problem: F) {
+        if req.rocket().config().problem_details {
+            req.local_cache(|| GuardProblem(Some(problem())));
+        }
+    }
+
+    /// Returns the problem recorded for `req`, if any.
+    pub(crate) fn recorded<'r>(req: &'r Request<'_>) -> Option<&'r Problem> {
+        req.local_cache(|| GuardProblem(None)).0.as_ref()
+    }
+}
+
+/// Maps form errors to a problem with the errors' status. Each error is listed
+/// in the `errors` extension as an object with a `message` member and, if they
+/// are known, the field's `name` and `value`.
+impl From<&form::Errors<'_>> for Problem {
+    fn from(errors: &form::Errors<'_>) -> Self {
+        let members = errors.iter()
+            .map(|error| {
+                let mut member = Dict::new();
+                if let Some(name) = &error.name {
+                    member.insert("name".into(), name.to_string().into());
+                }
+
+                if let Some(value) = &error.value {
+                    member.insert("value".into(), value.as_ref().into());
+                }
+
+                member.insert("message".into(), error.kind.to_string().into());
+                Value::from(member)
+            })
+            .collect::<Vec<_>>();
+
+        Problem::new(errors.status())
+            .with_detail("The request's form data is invalid.")
+            .with_extension("errors", members)
+    }
+}
+
+/// Returns `string` as a JSON string literal.
+pub(crate) fn json_string(string: &str) -> String {
+    let mut json = String::with_capacity(string.len() + 2);
+    json.push('"');
+    for c in string.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if c.is_control() => { let _ = write!(json, "\\u{:04x}", c as u32); }
+            c => json.push(c),
+        }
+    }
+
+    json.push('"');
+    json
+}
+
+fn write_json_value(json: &mut String, value: &Value) {
+    match value {
+        Value::String(_, s) => json.push_str(&json_string(s)),
+        Value::Char(_, c) => json.push_str(&json_string(c.encode_utf8(&mut [0; 4]))),
+        Value::Bool(_, b) => { let _ = write!(json, "{}", b); }
+        Value::Num(_, num) => write_json_num(json, *num),
+        Value::Empty(_, Empty::None | Empty::Unit) => json.push_str("null"),
+        Value::Dict(_, dict) => {
+            json.push('{');
+            for (i, (key, value)) in dict.iter().enumerate() {
+                if i > 0 { json.push(','); }
+                let _ = write!(json, "{}:", json_string(key));
+                write_json_value(json, value);
+            }
+
+            json.push('}');
+        }
+        Value::Array(_, values) => {
+            json.push('[');
+            for (i, value) in values.iter().enumerate() {
+                if i > 0 { json.push(','); }
+                write_json_value(json, value);
+            }
+
+            json.push(']');
+        }
+    }
+}
+
+fn write_json_num(json: &mut String, num: Num) {
+    let _ = match num {
+        Num::U8(n) => write!(json, "{}", n),
+        Num::U16(n) => write!(json, "{}", n),
+        Num::U32(n) => write!(json, "{}", n),
+        Num::U64(n) => write!(json, "{}", n),
+        Num::U128(n) => write!(json, "{}", n),
+        Num::USize(n) => write!(json, "{}", n),
+        Num::I8(n) => write!(json, "{}", n),
+        Num::I16(n) => write!(json, "{}", n),
+        Num::I32(n) => write!(json, "{}", n),
+        Num::I64(n) => write!(json, "{}", n),
+        Num::I128(n) => write!(json, "{}", n),
+        Num::ISize(n) => write!(json, "{}", n),
+        Num::F32(n) if n.is_finite() => write!(json, "{}", n),
+        Num::F64(n) if n.is_finite() => write!(json, "{}", n),
+        Num::F32(_) | Num::F64(_) => write!(json, "null"),
+    };
+}
+
+/// Responds with the problem's status and its problem document as the body,
+/// with a Content-Type of `application/problem+json`.
+impl<'r> Responder<'r, 'static> for Problem {
+    fn respond_to(self, req: &'r Request<'_>) -> response::Result<'static> {
+        let content_type = ContentType(Problem::MEDIA_TYPE);
+        let mut response = (content_type, self.to_json()).respond_to(req)?;
+        response.set_status(self.status);
+        Ok(response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::json_string;
+
+    #[test]
+    fn test_json_string() {
+        assert_eq!(json_string("hello.txt"), r#""hello.txt""#);
+        assert_eq!(json_string(r#"a "quoted" \ name"#), r#""a \"quoted\" \\ name""#);
+        assert_eq!(json_string("tab\tnew\nline\u{1}"), r#""tab\tnew\nline\u0001""#);
+        assert_eq!(json_string("ünïcödé"), r#""ünïcödé""#);
+    }
+}
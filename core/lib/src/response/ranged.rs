@@ -0,0 +1,297 @@
+use std::io::{self, SeekFrom};
+
+use bytes::Bytes;
+use futures::stream::{BoxStream, StreamExt};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncSeek, AsyncSeekExt};
+use tokio_util::io::{ReaderStream, StreamReader};
+
+use crate::request::Request;
+use crate::response::{self, Body, Responder, Response};
+use crate::http::{Header, Method, Status};
+
+/// Maximum number of ranges served in one response. Requests for more ranges
+/// receive the full body.
+const MAX_RANGES: usize = 16;
+
+/// A responder that serves a seekable body in byte ranges as requested by the
+/// client.
+///
+/// `Ranged` responds with `R` as a sized body and advertises support for range
+/// requests via `Accept-Ranges: bytes`. When a `GET` request contains a valid
+/// `Range` header for `bytes`, and its `If-Range` precondition, if any, holds,
+/// the response is:
+///
+///   * **`206 Partial Content`** with a `Content-Range` and only the requested
+///     bytes when a single range is satisfiable.
+///   * **`206 Partial Content`** with a `multipart/byteranges` body containing
+///     each range when multiple ranges are satisfiable. Overlapping ranges are
+///     coalesced.
+///   * **`416 Range Not Satisfiable`** with a `Content-Range` of `bytes
+///     */{len}` and no body when no range is satisfiable.
+///
+/// Otherwise, including when the request asks for more than 16 ranges, the full
+/// body is sent as usual.
+///
+/// Ranges are resolved after the route's response is complete, so a
+/// `Content-Type` set by an enclosing responder is used in `multipart/byteranges`
+/// parts. An `If-Range` date must exactly match the response's `Last-Modified`
+/// header, and an `If-Range` entity tag must strongly match its `ETag`.
+///
+/// [`NamedFile`](crate::fs::NamedFile) and thus
+/// [`FileServer`](crate::fs::FileServer) support range requests by default.
+///
+/// # Example
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// use std::io::Cursor;
+///
+/// use rocket::http::ContentType;
+/// use rocket::response::Ranged;
+///
+/// #[get("/video")]
+/// fn video() -> (ContentType, Ranged<Cursor<Vec<u8>>>) {
+///     let data = vec![0; 1024];
+///     (ContentType::MP4, Ranged(Cursor::new(data)))
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ranged<R>(pub R);
+
+impl<'r, 'o: 'r, R> Responder<'r, 'o> for Ranged<R>
+    where R: AsyncRead + AsyncSeek + Send + 'o
+{
+    fn respond_to(self, _: &'r Request<'_>) -> response::Result<'o> {
+        let mut response = Response::build().sized_body(None, self.0).finalize();
+        response.body_mut().allow_ranges();
+        response.set_header(Header::new("Accept-Ranges", "bytes"));
+        Ok(response)
+    }
+}
+
+/// A parsed `byte-range-spec` or `suffix-byte-range-spec`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RangeSpec {
+    /// `first-[last]`.
+    Bounded(u64, Option<u64>),
+    /// `-len`.
+    Suffix(u64),
+}
+
+/// Parses a `Range` header value. Returns `None` if the value is invalid or
+/// isn't for the `bytes` unit.
+fn parse_range(value: &str) -> Option<Vec<RangeSpec>> {
+    let (unit, set) = value.trim().split_once('=')?;
+    if !unit.trim().eq_ignore_ascii_case("bytes") {
+        return None;
+    }
+
+    let mut specs = vec![];
+    for spec in set.split(',').map(|s| s.trim()).filter(|s| !s.is_empty()) {
+        let (first, last) = spec.split_once('-')?;
+        let (first, last) = (first.trim(), last.trim());
+        let spec = match (first.is_empty(), last.is_empty()) {
+            (true, true) => return None,
+            (true, false) => RangeSpec::Suffix(last.parse().ok()?),
+            (false, true) => RangeSpec::Bounded(first.parse().ok()?, None),
+            (false, false) => {
+                let (first, last) = (first.parse().ok()?, last.parse().ok()?);
+                if last < first {
+                    return None;
+                }
+
+                RangeSpec::Bounded(first, Some(last))
+            }
+        };
+
+        specs.push(spec);
+    }
+
+    if specs.is_empty() { None } else { Some(specs) }
+}
+
+/// Resolves `specs` against a body of `len` bytes into sorted, coalesced,
+/// inclusive ranges. Unsatisfiable specs are dropped.
+fn resolve(specs: &[RangeSpec], len: u64) -> Vec<(u64, u64)> {
+    let mut ranges: Vec<(u64, u64)> = specs.iter()
+        .filter_map(|spec| match *spec {
+            RangeSpec::Bounded(first, _) if first >= len => None,
+            RangeSpec::Bounded(first, last) => {
+                Some((first, last.map_or(len - 1, |last| last.min(len - 1))))
+            }
+            RangeSpec::Suffix(n) if n == 0 || len == 0 => None,
+            RangeSpec::Suffix(n) => Some((len.saturating_sub(n), len - 1)),
+        })
+        .collect();
+
+    ranges.sort_unstable();
+    let mut coalesced: Vec<(u64, u64)> = Vec::with_capacity(ranges.len());
+    for (first, last) in ranges {
+        match coalesced.last_mut() {
+            Some(prev) if first <= prev.1.saturating_add(1) => prev.1 = prev.1.max(last),
+            _ => coalesced.push((first, last)),
+        }
+    }
+
+    coalesced
+}
+
+/// Returns `true` if the `If-Range` value `if_range` matches `response`.
+fn if_range_matches(if_range: &str, response: &Response<'_>) -> bool {
+    let if_range = if_range.trim();
+    if if_range.starts_with('"') || if_range.starts_with("W/") {
+        // Entity tags must match strongly; weak tags never match.
+        let etag = response.headers().get_one("ETag").map(|e| e.trim());
+        return !if_range.starts_with("W/") && etag == Some(if_range);
+    }
+
+    response.headers().get_one("Last-Modified").map(|d| d.trim()) == Some(if_range)
+}
+
+/// Resolves the request's `Range` header, if any, against `response`'s body if
+/// the body allows ranges.
+pub(crate) async fn resolve_ranges(req: &Request<'_>, response: &mut Response<'_>) {
+    if req.method() != Method::Get
+        || response.status() != Status::Ok
+        || !response.body().is_ranged()
+    {
+        return;
+    }
+
+    let specs = match req.headers().get_one("Range").and_then(parse_range) {
+        Some(specs) if specs.len() <= MAX_RANGES => specs,
+        _ => return,
+    };
+
+    if let Some(if_range) = req.headers().get_one("If-Range") {
+        if !if_range_matches(if_range, response) {
+            return;
+        }
+    }
+
+    let len = match response.body_mut().size().await {
+        Some(len) => len as u64,
+        None => return,
+    };
+
+    let ranges = resolve(&specs, len);
+    if ranges.is_empty() {
+        info_!("Requested ranges are unsatisfiable.");
+        response.set_status(Status::RangeNotSatisfiable);
+        response.set_header(Header::new("Content-Range", format!("bytes */{}", len)));
+        response.remove_header("Content-Type");
+        response.body_mut().take();
+        return;
+    }
+
+    let mut body = match response.body_mut().take_seekable() {
+        Some(body) => body,
+        None => return,
+    };
+
+    let start = match body.seek(SeekFrom::Current(0)).await {
+        Ok(start) => start,
+        Err(e) => {
+            warn_!("Failed to seek response body: {}.", e);
+            return;
+        }
+    };
+
+    let content_range = |(first, last): (u64, u64)| format!("bytes {}-{}/{}", first, last, len);
+    let max_chunk = response.body().max_chunk_size();
+    response.set_status(Status::PartialContent);
+    let parts = if ranges.len() == 1 {
+        response.set_header(Header::new("Content-Range", content_range(ranges[0])));
+        vec![Part::Range(ranges[0])]
+    } else {
+        let boundary = format!("{:016x}", rand::random::<u64>());
+        let content_type = response.content_type().map(|ct| ct.to_string());
+        let mut parts = vec![];
+        for (i, &range) in ranges.iter().enumerate() {
+            let mut head = if i == 0 { String::new() } else { "\r\n".into() };
+            head.push_str(&format!("--{}\r\n", boundary));
+            if let Some(ref content_type) = content_type {
+                head.push_str(&format!("Content-Type: {}\r\n", content_type));
+            }
+
+            head.push_str(&format!("Content-Range: {}\r\n\r\n", content_range(range)));
+            parts.push(Part::Text(head));
+            parts.push(Part::Range(range));
+        }
+
+        parts.push(Part::Text(format!("\r\n--{}--\r\n", boundary)));
+        let multipart = format!("multipart/byteranges; boundary={}", boundary);
+        response.set_raw_header("Content-Type", multipart);
+        parts
+    };
+
+    let size = parts.iter().map(|part| match part {
+        Part::Text(text) => text.len() as u64,
+        Part::Range((first, last)) => last - first + 1,
+    }).sum::<u64>();
+
+    let stream = async_stream::try_stream! {
+        for part in parts {
+            match part {
+                Part::Text(text) => yield Bytes::from(text),
+                Part::Range((first, last)) => {
+                    body.seek(SeekFrom::Start(start + first)).await?;
+                    let mut chunks = ReaderStream::new((&mut body).take(last - first + 1));
+                    while let Some(chunk) = chunks.next().await {
+                        yield chunk?;
+                    }
+                }
+            }
+        }
+    };
+
+    let stream: BoxStream<'_, io::Result<Bytes>> = Box::pin(stream);
+    let body = Body::with_unsized_of_size(StreamReader::new(stream), size as usize);
+    *response.body_mut() = body;
+    response.body_mut().set_max_chunk_size(max_chunk);
+}
+
+/// A part of a ranged response body.
+enum Part {
+    Text(String),
+    Range((u64, u64)),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_range, resolve, RangeSpec::*};
+
+    #[test]
+    fn test_parse_range() {
+        assert_eq!(parse_range("bytes=0-499"), Some(vec![Bounded(0, Some(499))]));
+        assert_eq!(parse_range("bytes=500-"), Some(vec![Bounded(500, None)]));
+        assert_eq!(parse_range("bytes=-500"), Some(vec![Suffix(500)]));
+        assert_eq!(parse_range(" Bytes = 0-0, -1 "), Some(vec![Bounded(0, Some(0)), Suffix(1)]));
+        assert_eq!(parse_range("bytes=0-1,,4-5"), Some(vec![Bounded(0, Some(1)), Bounded(4, Some(5))]));
+
+        assert_eq!(parse_range("bytes=5-4"), None);
+        assert_eq!(parse_range("bytes=-"), None);
+        assert_eq!(parse_range("bytes="), None);
+        assert_eq!(parse_range("bytes=a-b"), None);
+        assert_eq!(parse_range("items=0-1"), None);
+        assert_eq!(parse_range("0-1"), None);
+    }
+
+    #[test]
+    fn test_resolve() {
+        assert_eq!(resolve(&[Bounded(0, Some(499))], 1000), [(0, 499)]);
+        assert_eq!(resolve(&[Bounded(500, Some(5000))], 1000), [(500, 999)]);
+        assert_eq!(resolve(&[Bounded(500, None)], 1000), [(500, 999)]);
+        assert_eq!(resolve(&[Suffix(100)], 1000), [(900, 999)]);
+        assert_eq!(resolve(&[Suffix(5000)], 1000), [(0, 999)]);
+        assert_eq!(resolve(&[Bounded(1000, None)], 1000), []);
+        assert_eq!(resolve(&[Suffix(0)], 1000), []);
+        assert_eq!(resolve(&[Suffix(10)], 0), []);
+
+        let specs = [Bounded(10, Some(19)), Bounded(0, Some(4)), Bounded(15, Some(29))];
+        assert_eq!(resolve(&specs, 1000), [(0, 4), (10, 29)]);
+
+        let specs = [Bounded(0, Some(4)), Bounded(5, Some(9))];
+        assert_eq!(resolve(&specs, 1000), [(0, 9)]);
+    }
+}
@@ -0,0 +1,348 @@
+use std::fmt;
+use std::pin::Pin;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, Weak};
+use std::task::{Context, Poll};
+
+use futures::stream::Stream;
+use tokio::sync::broadcast::{self, error::RecvError};
+
+use crate::Shutdown;
+
+/// A publish/subscribe hub: broadcasts values to subscribers of named topics.
+///
+/// A `Hub<T>` is intended to be [managed](crate::Rocket::manage()) and shared
+/// by the routes that publish and subscribe to values. Values are
+/// [`publish`](Hub::publish())ed to a named topic and received by every
+/// [`Subscription`] to that topic, each of which is a [`Stream`] that can be
+/// converted directly into an [`EventStream`] when `T` is an [`Event`] or
+/// into a [`TextStream`] when `T: AsRef<str>`. A topic is created when it is
+/// first subscribed to and removed when its last subscription is dropped.
+///
+/// [`Stream`]: https://docs.rs/futures/0.3/futures/stream/trait.Stream.html
+/// [`EventStream`]: crate::response::stream::EventStream
+/// [`TextStream`]: crate::response::stream::TextStream
+/// [`Event`]: crate::response::stream::Event
+///
+/// # Buffering and Lag
+///
+/// Each topic has a single buffer, shared by all of its subscribers, that
+/// holds the [`capacity`](Hub::capacity()) most recently published values.
+/// The capacity is thus per topic, not per subscriber: memory use grows with
+/// the number of topics but not with the number of subscribers. Each
+/// subscriber receives values at its own pace from the shared buffer. A
+/// subscriber that falls more than `capacity` values behind the most recently
+/// published value _lags_, and what happens next is determined by the hub's
+/// [`Lag`] policy: by default, the oldest values the subscriber missed are
+/// dropped and it continues with the oldest value still buffered. A lagging
+/// subscriber never slows down publishers or other subscribers.
+///
+/// # Shutdown
+///
+/// Every subscription is tied to a [`Shutdown`] future and ends as soon as
+/// [graceful shutdown](crate::response::stream#graceful-shutdown) is
+/// initiated, so that streams built from subscriptions never prolong shutdown.
+/// A subscription also ends when its topic is [`close`](Hub::close())d after
+/// the subscriber receives the remaining buffered values.
+///
+/// # Example
+///
+/// A multi-room chat where every message posted to a room is sent, as a
+/// Server-Sent Event, to everyone in the room:
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// use rocket::{State, Shutdown};
+/// use rocket::response::stream::{Event, EventStream, Hub, Subscription};
+///
+/// #[post("/rooms/<room>", data = "<message>")]
+/// fn send(room: &str, message: String, hub: &State<Hub<Event>>) {
+///     hub.publish(room, Event::data(message));
+/// }
+///
+/// #[get("/rooms/<room>")]
+/// fn events(
+///     room: &str,
+///     hub: &State<Hub<Event>>,
+///     end: Shutdown,
+/// ) -> EventStream<Subscription<Event>> {
+///     EventStream::from(hub.subscribe(room, end))
+/// }
+///
+/// #[launch]
+/// fn rocket() -> _ {
+///     rocket::build()
+///         .manage(Hub::<Event>::new(1024))
+///         .mount("/", routes![send, events])
+/// }
+/// ```
+pub struct Hub<T> {
+    capacity: usize,
+    lag: Lag,
+    topics: Arc<Topics<T>>,
+}
+
+type Topics<T> = Mutex<HashMap<String, broadcast::Sender<T>>>;
+
+/// What happens to a [`Hub`] [`Subscription`] that falls behind.
+///
+/// See [Buffering and Lag](Hub#buffering-and-lag) for details.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Lag {
+    /// Drop the oldest values the subscriber missed and continue with the
+    /// oldest value still buffered. This is the default.
+    DropOldest,
+    /// End the subscription.
+    Disconnect,
+}
+
+impl Default for Lag {
+    fn default() -> Self {
+        Lag::DropOldest
+    }
+}
+
+/// A subscription to a [`Hub`] topic: a [`Stream`] of the values published to
+/// the topic after subscribing.
+///
+/// Returned by [`Hub::subscribe()`]. The stream ends when shutdown is
+/// initiated, when the topic is closed, or when the subscriber lags and the
+/// hub's [`Lag`] policy is [`Lag::Disconnect`]. Dropping the last subscription
+/// to a topic removes the topic from the hub.
+///
+/// [`Stream`]: https://docs.rs/futures/0.3/futures/stream/trait.Stream.html
+pub struct Subscription<T> {
+    /// `None` only while being dropped.
+    stream: Option<Pin<Box<dyn Stream<Item = T> + Send>>>,
+    topics: Weak<Topics<T>>,
+    topic: String,
+}
+
+impl<T: Clone + Send + 'static> Hub<T> {
+    /// Creates a new `Hub` that buffers up to `capacity` values per topic,
+    /// shared by the topic's subscribers, with the default [`Lag`] policy,
+    /// [`Lag::DropOldest`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is `0`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::response::stream::Hub;
+    ///
+    /// let hub = Hub::<String>::new(128);
+    /// assert_eq!(hub.capacity(), 128);
+    /// ```
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0, "hub capacity must be nonzero");
+        Hub { capacity, lag: Lag::default(), topics: Arc::new(Mutex::new(HashMap::new())) }
+    }
+
+    /// Sets the policy applied to subscribers that lag.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::response::stream::{Hub, Lag};
+    ///
+    /// let hub = Hub::<String>::new(128).lag(Lag::Disconnect);
+    /// ```
+    pub fn lag(mut self, lag: Lag) -> Self {
+        self.lag = lag;
+        self
+    }
+
+    /// Returns the number of values buffered per topic. The buffer is shared
+    /// by all of a topic's subscribers.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::response::stream::Hub;
+    ///
+    /// let hub = Hub::<String>::new(16);
+    /// assert_eq!(hub.capacity(), 16);
+    /// ```
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Publishes `value` to every subscriber of `topic` and returns the number
+    /// of subscribers it was sent to. If `topic` has no subscribers, `value` is
+    /// dropped and `0` is returned.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::response::stream::Hub;
+    ///
+    /// let hub = Hub::<String>::new(16);
+    /// assert_eq!(hub.publish("news", "hello".into()), 0);
+    /// ```
+    pub fn publish(&self, topic: &str, value: T) -> usize {
+        let mut topics = self.topics.lock().expect("hub lock");
+        match topics.get(topic).map(|sender| sender.send(value)) {
+            Some(Ok(n)) => n,
+            Some(Err(_)) => {
+                topics.remove(topic);
+                0
+            }
+            None => 0,
+        }
+    }
+
+    /// Subscribes to `topic`, returning a [`Subscription`] that yields every
+    /// value published to `topic` from now on. The subscription ends when
+    /// `shutdown` resolves.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use rocket::get;
+    /// use rocket::{State, Shutdown};
+    /// use rocket::response::stream::{Hub, Subscription, TextStream};
+    ///
+    /// #[get("/news")]
+    /// fn news(hub: &State<Hub<String>>, end: Shutdown) -> TextStream<Subscription<String>> {
+    ///     TextStream::from(hub.subscribe("news", end))
+    /// }
+    /// ```
+    pub fn subscribe(&self, topic: &str, shutdown: Shutdown) -> Subscription<T> {
+        let mut topics = self.topics.lock().expect("hub lock");
+        let receiver = match topics.get(topic) {
+            Some(sender) => sender.subscribe(),
+            None => {
+                let (sender, receiver) = broadcast::channel(self.capacity);
+                topics.insert(topic.into(), sender);
+                receiver
+            }
+        };
+
+        let topics = Arc::downgrade(&self.topics);
+        Subscription::new(receiver, shutdown, self.lag, topics, topic.into())
+    }
+
+    /// Returns the number of subscribers to `topic`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::response::stream::Hub;
+    ///
+    /// let hub = Hub::<String>::new(16);
+    /// assert_eq!(hub.subscribers("news"), 0);
+    /// ```
+    pub fn subscribers(&self, topic: &str) -> usize {
+        let topics = self.topics.lock().expect("hub lock");
+        topics.get(topic).map_or(0, |sender| sender.receiver_count())
+    }
+
+    /// Returns the number of open topics: those with at least one
+    /// subscription.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::response::stream::Hub;
+    ///
+    /// let hub = Hub::<String>::new(16);
+    /// assert_eq!(hub.topics(), 0);
+    /// ```
+    pub fn topics(&self) -> usize {
+        self.topics.lock().expect("hub lock").len()
+    }
+
+    /// Closes `topic`. Every current subscription to `topic` ends after
+    /// yielding the values that were already published to it. Subscribing to
+    /// `topic` again opens it anew.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::response::stream::Hub;
+    ///
+    /// let hub = Hub::<String>::new(16);
+    /// hub.close("news");
+    /// ```
+    pub fn close(&self, topic: &str) {
+        self.topics.lock().expect("hub lock").remove(topic);
+    }
+}
+
+impl<T: Clone + Send + 'static> Subscription<T> {
+    fn new(
+        mut receiver: broadcast::Receiver<T>,
+        mut shutdown: Shutdown,
+        lag: Lag,
+        topics: Weak<Topics<T>>,
+        topic: String,
+    ) -> Self {
+        let stream = async_stream::stream! {
+            loop {
+                let result = tokio::select! {
+                    result = receiver.recv() => result,
+                    _ = &mut shutdown => break,
+                };
+
+                match result {
+                    Ok(value) => yield value,
+                    Err(RecvError::Lagged(n)) if lag == Lag::DropOldest => {
+                        debug_!("Hub subscriber lagged: dropped {} values.", n);
+                    }
+                    Err(RecvError::Lagged(n)) => {
+                        debug_!("Hub subscriber lagged by {} values: disconnecting.", n);
+                        break;
+                    }
+                    Err(RecvError::Closed) => break,
+                }
+            }
+        };
+
+        Subscription { stream: Some(Box::pin(stream)), topics, topic }
+    }
+}
+
+impl<T> Stream for Subscription<T> {
+    type Item = T;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        match self.stream.as_mut() {
+            Some(stream) => stream.as_mut().poll_next(cx),
+            None => Poll::Ready(None),
+        }
+    }
+}
+
+impl<T> Drop for Subscription<T> {
+    fn drop(&mut self) {
+        // Drop the stream, and thus its receiver, first so it isn't counted.
+        self.stream = None;
+
+        let topics = match self.topics.upgrade() {
+            Some(topics) => topics,
+            None => return,
+        };
+
+        let mut topics = topics.lock().unwrap_or_else(|e| e.into_inner());
+        if topics.get(&self.topic).map_or(false, |s| s.receiver_count() == 0) {
+            topics.remove(&self.topic);
+        }
+    }
+}
+
+impl<T> fmt::Debug for Hub<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Hub")
+            .field("capacity", &self.capacity)
+            .field("lag", &self.lag)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T> fmt::Debug for Subscription<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Subscription").finish_non_exhaustive()
+    }
+}
@@ -43,6 +43,8 @@
 //!   * [`struct@ByteStream`] ([`ByteStream!`]) - streams of `T: AsRef<[u8]>`
 //!   * [`struct@TextStream`] ([`TextStream!`]) - streams of `T: AsRef<str>`
 //!   * [`struct@EventStream`] ([`EventStream!`]) - Server-Sent [`Event`] stream
+//!   * [`struct@JsonLines`] ([`JsonLines!`]) - newline-delimited JSON stream of
+//!     `T: Serialize`, with the `json` feature
 //!
 //! Each type implements `Responder`; each macro can be invoked to generate a
 //! typed stream, exactly like [`stream!`] above. Additionally, each macro is
@@ -194,6 +196,11 @@ mod text;
 mod one;
 mod sse;
 mod raw_sse;
+mod replay;
+mod hub;
+
+#[cfg(feature = "json")]
+mod ndjson;
 
 pub(crate) use self::raw_sse::*;
 
@@ -202,6 +209,12 @@ pub use self::text::TextStream;
 pub use self::bytes::ByteStream;
 pub use self::reader::ReaderStream;
 pub use self::sse::{Event, EventStream};
+pub use self::replay::{LastEventId, ReplayBuffer};
+pub use self::hub::{Hub, Lag, Subscription};
+
+#[cfg(feature = "json")]
+#[cfg_attr(nightly, doc(cfg(feature = "json")))]
+pub use self::ndjson::JsonLines;
 
 crate::export! {
     /// Retrofitted support for [`Stream`]s with `yield`, `for await` syntax.
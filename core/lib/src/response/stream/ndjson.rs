@@ -0,0 +1,116 @@
+use std::io::Cursor;
+
+use futures::future::ready;
+use futures::stream::{Stream, StreamExt};
+use serde::Serialize;
+
+use crate::request::Request;
+use crate::response::{self, Response, Responder};
+use crate::http::ContentType;
+use crate::response::stream::ReaderStream;
+
+/// A potentially infinite stream of newline-delimited JSON: `T: Serialize`.
+///
+/// A `JsonLines` can be constructed from any [`Stream`] of items of type `T`
+/// where `T: Serialize`. The stream can be constructed directly, via
+/// `JsonLines(..)` or [`JsonLines::from()`], or through generator syntax via
+/// [`JsonLines!`].
+///
+/// [`Stream`]: https://docs.rs/futures/0.3/futures/stream/trait.Stream.html
+///
+/// # Responder
+///
+/// `JsonLines` is a (potentially infinite) responder. The response
+/// `Content-Type` is set to [`NDJSON`](ContentType::NDJSON). The body is
+/// [unsized](crate::response::Body#unsized). Each value is serialized as
+/// compact JSON followed by a `\n` and sent as soon as it is yielded by the
+/// internal stream. If a value fails to serialize, the error is logged and the
+/// stream is ended early.
+///
+/// To read a newline-delimited JSON request body record by record, use the
+/// [`Lines`](crate::serde::json::Lines) data guard.
+///
+/// # Example
+///
+/// Use [`JsonLines!`] to yield 10 records, one every second:
+///
+/// ```rust
+/// # use rocket::*;
+/// use rocket::serde::Serialize;
+/// use rocket::response::stream::JsonLines;
+/// use rocket::futures::stream::{iter, StreamExt};
+/// use rocket::tokio::time::{self, Duration};
+///
+/// #[derive(Serialize)]
+/// #[serde(crate = "rocket::serde")]
+/// struct Record {
+///     id: usize,
+/// }
+///
+/// #[get("/records")]
+/// fn records() -> JsonLines![Record] {
+///     JsonLines(iter(0..10).map(|id| Record { id }))
+/// }
+///
+/// #[get("/records/stream")]
+/// fn stream() -> JsonLines![Record] {
+///     JsonLines! {
+///         let mut interval = time::interval(Duration::from_secs(1));
+///         for id in 0..10 {
+///             yield Record { id };
+///             interval.tick().await;
+///         }
+///     }
+/// }
+/// ```
+///
+/// The syntax of [`JsonLines!`] as an expression is identical to that of
+/// [`stream!`](crate::response::stream::stream).
+#[derive(Debug, Clone)]
+pub struct JsonLines<S>(pub S);
+
+impl<S> From<S> for JsonLines<S> {
+    /// Creates a `JsonLines` from any `S: Stream`.
+    fn from(stream: S) -> Self {
+        JsonLines(stream)
+    }
+}
+
+impl<'r, S: Stream> Responder<'r, 'r> for JsonLines<S>
+    where S: Send + 'r, S::Item: Serialize
+{
+    fn respond_to(self, _: &'r Request<'_>) -> response::Result<'r> {
+        let lines = self.0
+            .map(|value| serde_json::to_vec(&value))
+            .take_while(|line| ready(match line {
+                Ok(_) => true,
+                Err(e) => {
+                    error_!("JSON failed to serialize: {:?}", e);
+                    false
+                }
+            }))
+            .filter_map(|line| ready(line.ok()))
+            .map(|mut line| {
+                line.push(b'\n');
+                Cursor::new(line)
+            });
+
+        Response::build()
+            .header(ContentType::NDJSON)
+            .streamed_body(ReaderStream::from(lines))
+            .ok()
+    }
+}
+
+crate::export! {
+    /// Type and stream expression macro for [`struct@JsonLines`].
+    ///
+    /// See [`stream!`](crate::response::stream::stream) for the syntax
+    /// supported by this macro.
+    ///
+    /// See [`struct@JsonLines`] and the [module level
+    /// docs](crate::response::stream#typed-streams) for usage details.
+    macro_rules! JsonLines {
+        ($($s:tt)*) => ($crate::_typed_stream!(JsonLines, $($s)*));
+    }
+}
@@ -0,0 +1,467 @@
+use std::fmt;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use futures::future::ready;
+use futures::stream::{self, Stream, StreamExt};
+
+use crate::request::{Request, FromRequest, Outcome};
+use crate::response::stream::Event;
+use crate::http::Status;
+
+/// A request guard for the `Last-Event-ID` header sent by reconnecting
+/// Server-Sent Event clients.
+///
+/// When a client like JavaScript's `EventSource` loses its connection, it
+/// reconnects and sends the [`id`](Event::id()) of the last event it received
+/// in a `Last-Event-ID` header. This guard succeeds with the value of that
+/// header when it is present and nonempty and forwards otherwise. Use
+/// `Option<LastEventId>` to accept requests both with and without the header.
+///
+/// See [`ReplayBuffer`] for sending a reconnecting client the events it missed.
+///
+/// # Example
+///
+/// ```rust
+/// # use rocket::get;
+/// use rocket::response::stream::{Event, EventStream, LastEventId};
+///
+/// #[get("/events")]
+/// fn events(last: Option<LastEventId<'_>>) -> EventStream![] {
+///     let last: Option<u32> = last.and_then(|id| id.as_str().parse().ok());
+///     let start = last.map_or(0, |id| id + 1);
+///     EventStream! {
+///         for id in start..10 {
+///             yield Event::data("tick").id(id.to_string());
+///         }
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LastEventId<'r>(&'r str);
+
+impl<'r> LastEventId<'r> {
+    /// The name of the header this guard reads: `Last-Event-ID`.
+    pub const HEADER: &'static str = "Last-Event-ID";
+
+    /// Returns the ID of the last event the client received.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use rocket::get;
+    /// use rocket::response::stream::LastEventId;
+    ///
+    /// #[get("/events")]
+    /// fn events(last: LastEventId<'_>) -> String {
+    ///     format!("resuming after {}", last.as_str())
+    /// }
+    /// ```
+    pub fn as_str(&self) -> &'r str {
+        self.0
+    }
+}
+
+impl<'r> From<&'r str> for LastEventId<'r> {
+    /// Creates a `LastEventId` for the event with ID `id`.
+    fn from(id: &'r str) -> Self {
+        LastEventId(id)
+    }
+}
+
+impl fmt::Display for LastEventId<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+#[crate::async_trait]
+impl<'r> FromRequest<'r> for LastEventId<'r> {
+    type Error = std::convert::Infallible;
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        match request.headers().get_one(LastEventId::HEADER) {
+            Some(id) if !id.is_empty() => Outcome::Success(LastEventId(id)),
+            _ => Outcome::Forward(Status::NotFound),
+        }
+    }
+}
+
+/// A bounded buffer of recent events per channel for resuming event streams.
+///
+/// A `ReplayBuffer` records the most recent [`Event`]s sent on each of any
+/// number of named channels, keeping up to [`capacity`](Self::capacity())
+/// events per channel. When a client reconnects with a [`LastEventId`], the
+/// buffer can [`replay`](Self::replay()) the events the client missed or
+/// [`resume`](Self::resume()) a stream by sending the missed events before
+/// live ones. A `ReplayBuffer` is typically [managed](crate::Rocket::manage())
+/// and shared by the routes that send and stream events.
+///
+/// Events are identified by their `id`: an event [`push`](Self::push())ed
+/// without one is assigned a unique ID made of the buffer's
+/// [`id_prefix`](Self::id_prefix()) and an increasing number. The prefix is
+/// random by default, so IDs generated by a buffer in a restarted application
+/// don't collide with IDs a reconnecting client received before the restart.
+/// Event IDs should be unique within a channel.
+///
+/// The buffer keeps at most [`max_channels`](Self::max_channels()) channels,
+/// by default [`DEFAULT_MAX_CHANNELS`](Self::DEFAULT_MAX_CHANNELS). Pushing to
+/// a new channel when the buffer is full evicts the channel that was least
+/// recently pushed to. Channels can also be dropped explicitly with
+/// [`remove()`](Self::remove()) and [`clear()`](Self::clear()).
+///
+/// # Example
+///
+/// The following implements a multi-room chat where a reconnecting client
+/// receives the messages it missed in its room. Messages are sent to clients
+/// through a [`broadcast`](crate::tokio::sync::broadcast) channel and recorded
+/// in a managed `ReplayBuffer`:
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// use rocket::{State, Shutdown};
+/// use rocket::response::stream::{stream, Event, EventStream};
+/// use rocket::response::stream::{LastEventId, ReplayBuffer};
+/// use rocket::tokio::select;
+/// use rocket::tokio::sync::broadcast::{channel, Sender, error::RecvError};
+///
+/// type Queue = Sender<(String, Event)>;
+///
+/// #[post("/rooms/<room>", data = "<message>")]
+/// fn send(room: &str, message: String, queue: &State<Queue>, replay: &State<ReplayBuffer>) {
+///     let event = replay.push(room, Event::data(message));
+///     let _ = queue.send((room.to_string(), event));
+/// }
+///
+/// #[get("/rooms/<room>")]
+/// fn events<'r>(
+///     room: &'r str,
+///     last: Option<LastEventId<'_>>,
+///     queue: &State<Queue>,
+///     replay: &State<ReplayBuffer>,
+///     mut end: Shutdown,
+/// ) -> EventStream![Event + 'r] {
+///     // Subscribe _before_ resuming so that no event is missed.
+///     let mut rx = queue.subscribe();
+///     let live = stream! {
+///         loop {
+///             let (channel, event) = select! {
+///                 msg = rx.recv() => match msg {
+///                     Ok(msg) => msg,
+///                     Err(RecvError::Closed) => break,
+///                     Err(RecvError::Lagged(_)) => continue,
+///                 },
+///                 _ = &mut end => break,
+///             };
+///
+///             if channel == room {
+///                 yield event;
+///             }
+///         }
+///     };
+///
+///     EventStream::from(replay.resume(room, last.as_ref(), live))
+/// }
+///
+/// #[launch]
+/// fn rocket() -> _ {
+///     rocket::build()
+///         .manage(channel::<(String, Event)>(1024).0)
+///         .manage(ReplayBuffer::new(64))
+///         .mount("/", routes![send, events])
+/// }
+/// ```
+pub struct ReplayBuffer {
+    capacity: usize,
+    max_channels: usize,
+    id_prefix: String,
+    next_id: AtomicU64,
+    channels: Mutex<Channels>,
+}
+
+#[derive(Default)]
+struct Channels {
+    /// Incremented on every push to order channels by recency.
+    clock: u64,
+    /// The time of the last push to, and the events in, each channel.
+    map: HashMap<String, (u64, VecDeque<Event>)>,
+}
+
+impl ReplayBuffer {
+    /// The default maximum number of channels: `1024`.
+    pub const DEFAULT_MAX_CHANNELS: usize = 1024;
+
+    /// Creates a new `ReplayBuffer` that keeps up to `capacity` of the most
+    /// recent events in each channel.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::response::stream::ReplayBuffer;
+    ///
+    /// let buffer = ReplayBuffer::new(128);
+    /// assert_eq!(buffer.capacity(), 128);
+    /// ```
+    pub fn new(capacity: usize) -> Self {
+        ReplayBuffer {
+            capacity,
+            max_channels: Self::DEFAULT_MAX_CHANNELS,
+            id_prefix: format!("{:08x}-", rand::random::<u32>()),
+            next_id: AtomicU64::new(0),
+            channels: Mutex::new(Channels::default()),
+        }
+    }
+
+    /// Sets the maximum number of channels kept to `max`. Pushing to a new
+    /// channel when `max` channels are kept evicts the channel that was least
+    /// recently pushed to.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::response::stream::ReplayBuffer;
+    ///
+    /// let buffer = ReplayBuffer::new(128).with_max_channels(16);
+    /// assert_eq!(buffer.max_channels(), 16);
+    /// ```
+    pub fn with_max_channels(mut self, max: usize) -> Self {
+        self.max_channels = max;
+        self
+    }
+
+    /// Sets the prefix of the IDs assigned to events pushed without one to
+    /// `prefix`. Generated IDs are `prefix` followed by an increasing number
+    /// starting at `0`. Only use a fixed prefix when IDs from a previous run of
+    /// the application can't be confused with new ones, for instance, because
+    /// the prefix is derived from a persisted counter.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::response::stream::{Event, ReplayBuffer};
+    ///
+    /// let buffer = ReplayBuffer::new(16).with_id_prefix("epoch7-");
+    /// assert_eq!(buffer.id_prefix(), "epoch7-");
+    ///
+    /// let event = buffer.push("news", Event::data("hello"));
+    /// assert_eq!(event, Event::data("hello").id("epoch7-0"));
+    /// ```
+    pub fn with_id_prefix<P: Into<String>>(mut self, prefix: P) -> Self {
+        self.id_prefix = prefix.into();
+        self
+    }
+
+    /// Returns the prefix of the IDs assigned to events pushed without one.
+    /// Unless set with [`with_id_prefix()`](Self::with_id_prefix()), the prefix
+    /// is randomly chosen when the buffer is created.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::response::stream::ReplayBuffer;
+    ///
+    /// let (a, b) = (ReplayBuffer::new(16), ReplayBuffer::new(16));
+    /// assert_ne!(a.id_prefix(), b.id_prefix());
+    /// ```
+    pub fn id_prefix(&self) -> &str {
+        &self.id_prefix
+    }
+
+    /// Returns the maximum number of events kept in each channel.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::response::stream::ReplayBuffer;
+    ///
+    /// let buffer = ReplayBuffer::new(16);
+    /// assert_eq!(buffer.capacity(), 16);
+    /// ```
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the maximum number of channels kept.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::response::stream::ReplayBuffer;
+    ///
+    /// let buffer = ReplayBuffer::new(16);
+    /// assert_eq!(buffer.max_channels(), ReplayBuffer::DEFAULT_MAX_CHANNELS);
+    /// ```
+    pub fn max_channels(&self) -> usize {
+        self.max_channels
+    }
+
+    /// Records `event` as the most recent event in `channel` and returns it,
+    /// evicting the oldest event in the channel if it is full and the least
+    /// recently pushed to channel if `channel` is new and the buffer is full.
+    /// If `event` has no `id`, it is first assigned a unique ID: the buffer's
+    /// [`id_prefix`](Self::id_prefix()) followed by an increasing number. The
+    /// returned event should be sent to live subscribers.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::response::stream::{Event, ReplayBuffer};
+    ///
+    /// let buffer = ReplayBuffer::new(16);
+    /// let event = buffer.push("news", Event::data("hello"));
+    /// let id = format!("{}0", buffer.id_prefix());
+    /// assert_eq!(event, Event::data("hello").id(id));
+    ///
+    /// let event = buffer.push("news", Event::data("bye").id("last"));
+    /// assert_eq!(event, Event::data("bye").id("last"));
+    /// ```
+    pub fn push(&self, channel: &str, mut event: Event) -> Event {
+        if event.id.is_none() {
+            let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+            event = event.id(format!("{}{}", self.id_prefix, id));
+        }
+
+        if self.capacity == 0 || self.max_channels == 0 {
+            return event;
+        }
+
+        let mut channels = self.channels.lock().expect("replay buffer lock");
+        let Channels { clock, map } = &mut *channels;
+        *clock += 1;
+
+        if !map.contains_key(channel) && map.len() >= self.max_channels {
+            let oldest = map.iter()
+                .min_by_key(|(_, (pushed, _))| *pushed)
+                .map(|(name, _)| name.clone());
+
+            if let Some(oldest) = oldest {
+                map.remove(&oldest);
+            }
+        }
+
+        let (pushed, events) = map.entry(channel.into()).or_default();
+        *pushed = *clock;
+        if events.len() == self.capacity {
+            events.pop_front();
+        }
+
+        events.push_back(event.clone());
+        event
+    }
+
+    /// Removes `channel` and its recorded events. Returns `true` if the
+    /// channel existed.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::response::stream::{Event, LastEventId, ReplayBuffer};
+    ///
+    /// let buffer = ReplayBuffer::new(16);
+    /// buffer.push("news", Event::data("hello"));
+    /// assert!(buffer.remove("news"));
+    /// assert!(!buffer.remove("news"));
+    /// assert!(buffer.replay("news", &LastEventId::from("none")).is_empty());
+    /// ```
+    pub fn remove(&self, channel: &str) -> bool {
+        let mut channels = self.channels.lock().expect("replay buffer lock");
+        channels.map.remove(channel).is_some()
+    }
+
+    /// Removes every channel and its recorded events.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::response::stream::{Event, LastEventId, ReplayBuffer};
+    ///
+    /// let buffer = ReplayBuffer::new(16);
+    /// buffer.push("news", Event::data("hello"));
+    /// buffer.push("sports", Event::data("goal"));
+    /// buffer.clear();
+    /// assert!(buffer.replay("news", &LastEventId::from("none")).is_empty());
+    /// assert!(buffer.replay("sports", &LastEventId::from("none")).is_empty());
+    /// ```
+    pub fn clear(&self) {
+        self.channels.lock().expect("replay buffer lock").map.clear();
+    }
+
+    /// Returns the events in `channel` recorded after the event with ID
+    /// `last`, oldest first.
+    ///
+    /// If no recorded event has ID `last`, all of the events in `channel` are
+    /// returned. This is the case when the event has been evicted, in which
+    /// case the client also missed events no longer recorded, and when the
+    /// event was recorded by another buffer, such as one in an earlier run of
+    /// the application, in which case the client missed, at most, every event
+    /// recorded since. Since generated IDs are unique to a buffer, an ID from
+    /// an earlier run never matches a newer event.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use rocket::get;
+    /// use rocket::State;
+    /// use rocket::response::stream::{Event, LastEventId, ReplayBuffer};
+    ///
+    /// #[get("/missed")]
+    /// fn missed(last: LastEventId<'_>, replay: &State<ReplayBuffer>) -> String {
+    ///     let missed = replay.replay("news", &last);
+    ///     format!("missed {} events", missed.len())
+    /// }
+    /// ```
+    pub fn replay(&self, channel: &str, last: &LastEventId<'_>) -> Vec<Event> {
+        let channels = self.channels.lock().expect("replay buffer lock");
+        let events = match channels.map.get(channel) {
+            Some((_, events)) => events,
+            None => return vec![],
+        };
+
+        let start = events.iter()
+            .rposition(|event| event.id.as_deref() == Some(last.as_str()))
+            .map_or(0, |i| i + 1);
+
+        events.iter().skip(start).cloned().collect()
+    }
+
+    /// Returns a stream of the events in `channel` missed by a client that last
+    /// received the event with ID `last`, as returned by
+    /// [`replay()`](Self::replay()), followed by the events in `live`. If
+    /// `last` is `None`, the returned stream is equivalent to `live`.
+    ///
+    /// To avoid missing events sent while resuming, `live` should be subscribed
+    /// to _before_ calling this method. Events in `live` that were already
+    /// replayed are skipped.
+    ///
+    /// See the [type-level docs](Self#example) for an example.
+    pub fn resume<S>(
+        &self,
+        channel: &str,
+        last: Option<&LastEventId<'_>>,
+        live: S
+    ) -> impl Stream<Item = Event>
+        where S: Stream<Item = Event>
+    {
+        let missed = last.map(|last| self.replay(channel, last)).unwrap_or_default();
+        let replayed: HashSet<String> = missed.iter()
+            .filter_map(|event| event.id.as_deref().map(String::from))
+            .collect();
+
+        let live = live.skip_while(move |event| {
+            ready(event.id.as_deref().map_or(false, |id| replayed.contains(id)))
+        });
+
+        stream::iter(missed).chain(live)
+    }
+}
+
+impl fmt::Debug for ReplayBuffer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ReplayBuffer")
+            .field("capacity", &self.capacity)
+            .field("max_channels", &self.max_channels)
+            .field("id_prefix", &self.id_prefix)
+            .finish_non_exhaustive()
+    }
+}
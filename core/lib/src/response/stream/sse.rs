@@ -115,7 +115,7 @@ use crate::http::ContentType;
 pub struct Event {
     comment: Option<Cow<'static, str>>,
     retry: Option<Duration>,
-    id: Option<Cow<'static, str>>,
+    pub(super) id: Option<Cow<'static, str>>,
     event: Option<Cow<'static, str>>,
     data: Option<Cow<'static, str>>,
 }
@@ -434,6 +434,17 @@ impl Event {
 /// See [`stream#borrowing`](crate::response::stream#borrowing) for further
 /// details on borrowing in streams.
 ///
+/// # Resumption
+///
+/// When its connection is lost, a client like JavaScript's `EventSource`
+/// reconnects and sends the `id` of the last event it received in a
+/// `Last-Event-ID` header. That header can be read with the [`LastEventId`]
+/// request guard, and a [`ReplayBuffer`] can be used to send a reconnecting
+/// client the events it missed before switching to live events.
+///
+/// [`LastEventId`]: crate::response::stream::LastEventId
+/// [`ReplayBuffer`]: crate::response::stream::ReplayBuffer
+///
 /// # Pitfalls
 ///
 /// Server-Sent Events are a rather simple mechanism, though there are some
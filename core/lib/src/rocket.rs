@@ -15,6 +15,7 @@ use crate::phase::{Stateful, StateRef, State};
 use crate::http::uri::{self, Origin};
 use crate::http::ext::IntoOwned;
 use crate::error::{Error, ErrorKind};
+use crate::listener::Listener;
 use crate::log_utils::PaintExt;
 
 /// The application server itself.
@@ -648,6 +649,10 @@ impl Rocket<Ignite> {
             config: self.0.config,
             state: self.0.state,
             shutdown: self.0.shutdown,
+            addresses: vec![],
+            connections: Default::default(),
+            #[cfg(feature = "tls")]
+            tls_reloader: Default::default(),
         })
     }
 
@@ -665,9 +670,24 @@ impl Rocket<Ignite> {
             .default_tcp_http_server(|rkt| Box::pin(async move {
                 rkt.fairings.handle_liftoff(&rkt).await;
 
-                let proto = rkt.config.tls_enabled().then(|| "https").unwrap_or("http");
-                let socket_addr = SocketAddr::new(rkt.config.address, rkt.config.port);
-                let addr = format!("{}://{}", proto, socket_addr);
+                let addr = match rkt.config.unix_socket() {
+                    _ if rkt.config.socket_activation() => "activated sockets".into(),
+                    Some(path) => format!("unix:{}", path.display()),
+                    None if !rkt.config.endpoints.is_empty() => rkt.config.endpoints.iter()
+                        .zip(rkt.addresses())
+                        .map(|(endpoint, addr)| match endpoint.tls_enabled() {
+                            true => format!("https://{}", addr),
+                            false => format!("http://{}", addr),
+                        })
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                    None => {
+                        let proto = rkt.config.tls_enabled().then(|| "https").unwrap_or("http");
+                        let socket_addr = SocketAddr::new(rkt.config.address, rkt.config.port);
+                        format!("{}://{}", proto, socket_addr)
+                    }
+                };
+
                 launch_info!("{}{} {}",
                     Paint::emoji("🚀 "),
                     Paint::default("Rocket has launched from").bold(),
@@ -676,6 +696,29 @@ impl Rocket<Ignite> {
             .await
             .map(|rocket| rocket.into_ignite())
     }
+
+    async fn _launch_on<L>(self, listener: L) -> Result<Rocket<Ignite>, Error>
+        where L: Listener + Send, <L as Listener>::Connection: Send + Unpin + 'static
+    {
+        let mut rocket = self.into_orbit();
+        let endpoint = match listener.local_addr() {
+            Some(addr) => {
+                rocket.config.address = addr.ip();
+                rocket.config.port = addr.port();
+                rocket.addresses.push(addr);
+                addr.to_string()
+            }
+            None => "a custom listener".into(),
+        };
+
+        rocket.fairings.handle_liftoff(&rocket).await;
+        launch_info!("{}{} {}",
+            Paint::emoji("🚀 "),
+            Paint::default("Rocket has launched from").bold(),
+            Paint::default(endpoint).bold().underline());
+
+        rocket.http_server(listener).await.map(|rocket| rocket.into_ignite())
+    }
 }
 
 impl Rocket<Orbit> {
@@ -742,6 +785,59 @@ impl Rocket<Orbit> {
     pub fn shutdown(&self) -> Shutdown {
         self.shutdown.clone()
     }
+
+    /// Returns the socket addresses Rocket is serving on.
+    ///
+    /// When serving on several [`Endpoint`](crate::config::Endpoint)s, the
+    /// addresses are in the order the endpoints are configured in. Listeners
+    /// without a socket address, such as Unix domain sockets, don't contribute
+    /// an address. When launched via a local client, this is empty.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # #[macro_use] extern crate rocket;
+    /// use rocket::fairing::AdHoc;
+    ///
+    /// #[launch]
+    /// fn rocket() -> _ {
+    ///     rocket::build()
+    ///         .attach(AdHoc::on_liftoff("Addresses", |rocket| Box::pin(async move {
+    ///             for address in rocket.addresses() {
+    ///                 println!("Serving on {}", address);
+    ///             }
+    ///         })))
+    /// }
+    /// ```
+    pub fn addresses(&self) -> &[SocketAddr] {
+        &self.addresses
+    }
+
+    /// Returns a handle which can be used to reload the certificates of the
+    /// TLS listeners Rocket is serving on. See [`TlsReloader`] for details.
+    ///
+    /// [`TlsReloader`]: crate::tls::TlsReloader
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # #[macro_use] extern crate rocket;
+    /// use rocket::fairing::AdHoc;
+    ///
+    /// #[launch]
+    /// fn rocket() -> _ {
+    ///     rocket::build()
+    ///         .attach(AdHoc::on_liftoff("TLS Reloader", |rocket| Box::pin(async move {
+    ///             let reloader = rocket.tls_reloader();
+    ///             // Keep `reloader` to call `reloader.reload()` later.
+    ///         })))
+    /// }
+    /// ```
+    #[cfg(feature = "tls")]
+    #[cfg_attr(nightly, doc(cfg(feature = "tls")))]
+    pub fn tls_reloader(&self) -> crate::tls::TlsReloader {
+        self.tls_reloader.clone()
+    }
 }
 
 impl<P: Phase> Rocket<P> {
@@ -906,6 +1002,50 @@ impl<P: Phase> Rocket<P> {
             State::Orbit(s) => Ok(Rocket::from(s).into_ignite())
         }
     }
+
+    /// Launches `self` on the connections yielded by `listener`.
+    ///
+    /// This method is identical to [`Rocket::launch()`] except that instead of
+    /// binding a socket according to the active configuration, Rocket serves
+    /// requests from the connections `listener` accepts. The configured
+    /// `address`, `port`, `tls`, and `unix` parameters are not used to bind;
+    /// when `listener` reports a local address, `address` and `port` are
+    /// updated to reflect it before liftoff. Liftoff fairings, [`Shutdown`],
+    /// and graceful shutdown all behave as they do with [`Rocket::launch()`].
+    ///
+    /// See [`listener`](crate::listener) for the available listeners and the
+    /// traits required of custom ones.
+    ///
+    /// # Error
+    ///
+    /// If there is a problem igniting the application or the application fails
+    /// unexpectedly while running, an [`Error`] is returned. Note that a value
+    /// of type `Error` panics if dropped without first being inspected. See the
+    /// [`Error`] documentation for more information.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rocket::listener::TcpListener;
+    ///
+    /// #[rocket::main]
+    /// async fn main() {
+    ///     let listener = TcpListener::bind("127.0.0.1:8000").await.unwrap();
+    ///     let result = rocket::build().launch_on(listener).await;
+    ///
+    ///     // this is reachable only after `Shutdown::notify()` or `Ctrl+C`.
+    ///     println!("Rocket: deorbit.");
+    /// }
+    /// ```
+    pub async fn launch_on<L>(self, listener: L) -> Result<Rocket<Ignite>, Error>
+        where L: Listener + Send, <L as Listener>::Connection: Send + Unpin + 'static
+    {
+        match self.0.into_state() {
+            State::Build(s) => Rocket::from(s).ignite().await?._launch_on(listener).await,
+            State::Ignite(s) => Rocket::from(s)._launch_on(listener).await,
+            State::Orbit(s) => Ok(Rocket::from(s).into_ignite())
+        }
+    }
 }
 
 #[doc(hidden)]
@@ -1,6 +1,7 @@
 //! Automatic JSON (de)serialization support.
 //!
-//! See [`Json`](Json) for details.
+//! See [`Json`](Json) for details and [`Lines`](Lines) for reading
+//! newline-delimited JSON.
 //!
 //! # Enabling
 //!
@@ -26,15 +27,20 @@
 
 use std::{io, fmt, error};
 use std::ops::{Deref, DerefMut};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures::stream::Stream;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, BufReader};
 
 use crate::request::{Request, local_cache};
 use crate::data::{Limits, Data, FromData, Outcome};
-use crate::response::{self, Responder, content};
+use crate::response::{self, Responder, Problem, content};
 use crate::form::prelude as form;
 use crate::http::uri::fmt::{UriDisplay, FromUriParam, Query, Formatter as UriFormatter};
 use crate::http::Status;
 
-use serde::{Serialize, Deserialize};
+use serde::{Serialize, Deserialize, de::DeserializeOwned};
 
 #[doc(hidden)]
 pub use serde_json;
@@ -157,6 +163,34 @@ impl<'a> error::Error for Error<'a> {
     }
 }
 
+impl Error<'_> {
+    /// The status the [`Json`] guard fails with for this error.
+    fn status(&self) -> Status {
+        match self {
+            Self::Io(e) if e.kind() == io::ErrorKind::UnexpectedEof => Status::PayloadTooLarge,
+            Self::Parse(_, e) if e.classify() == serde_json::error::Category::Data => {
+                Status::UnprocessableEntity
+            }
+            _ => Status::BadRequest,
+        }
+    }
+}
+
+/// Maps a JSON error to a problem with the status the [`Json`] guard fails with
+/// and the error message as its `detail`. Parse errors additionally set the
+/// `line` and `column` extensions to the location of the error.
+impl From<&Error<'_>> for Problem {
+    fn from(error: &Error<'_>) -> Self {
+        let problem = Problem::new(error.status()).with_detail(error.to_string());
+        match error {
+            Error::Parse(_, e) => problem
+                .with_extension("line", e.line())
+                .with_extension("column", e.column()),
+            Error::Io(_) => problem,
+        }
+    }
+}
+
 impl<T> Json<T> {
     /// Consumes the JSON wrapper and returns the wrapped item.
     ///
@@ -200,14 +234,10 @@ impl<'r, T: Deserialize<'r>> FromData<'r> for Json<T> {
     async fn from_data(req: &'r Request<'_>, data: Data<'r>) -> Outcome<'r, Self> {
         match Self::from_data(req, data).await {
             Ok(value) => Outcome::Success(value),
-            Err(Error::Io(e)) if e.kind() == io::ErrorKind::UnexpectedEof => {
-                Outcome::Failure((Status::PayloadTooLarge, Error::Io(e)))
-            },
-            Err(Error::Parse(s, e)) if e.classify() == serde_json::error::Category::Data => {
-                Outcome::Failure((Status::UnprocessableEntity, Error::Parse(s, e)))
-            },
-            Err(e) => Outcome::Failure((Status::BadRequest, e)),
-
+            Err(e) => {
+                Problem::record(req, || Problem::from(&e));
+                Outcome::Failure((e.status(), e))
+            }
         }
     }
 }
@@ -295,6 +325,154 @@ impl<'v, T: Deserialize<'v> + Send> form::FromFormField<'v> for Json<T> {
     }
 }
 
+/// The newline-delimited JSON guard: consume JSON records one line at a time.
+///
+/// `Lines<'r, T>` is a data guard that never fails. It is a [`Stream`] of
+/// `Result<T, Error<'r>>` which reads the request body incrementally, yielding
+/// one value for every nonempty line, each of which is deserialized as a `T`.
+/// At most one line is buffered in memory at a time. Lines may be terminated by
+/// `\n` or `\r\n`; the final line need not be terminated.
+///
+/// The stream ends after yielding its first `Err`, which occurs when:
+///
+///   * a line fails to parse as a `T`, yielding [`Error::Parse`] with the raw
+///     contents of the offending line,
+///   * reading fails, a line isn't valid UTF-8, or a limit is exceeded,
+///     yielding [`Error::Io`]. An exceeded limit is reported as an error of
+///     kind [`UnexpectedEof`](io::ErrorKind::UnexpectedEof).
+///
+/// To respond with a stream of newline-delimited JSON, use
+/// [`JsonLines`](crate::response::stream::JsonLines).
+///
+/// [`Stream`]: https://docs.rs/futures/0.3/futures/stream/trait.Stream.html
+///
+/// # Example
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// use rocket::futures::StreamExt;
+/// use rocket::serde::Deserialize;
+/// use rocket::serde::json::Lines;
+///
+/// #[derive(Deserialize)]
+/// #[serde(crate = "rocket::serde")]
+/// struct Record {
+///     id: usize,
+/// }
+///
+/// #[post("/records", format = "ndjson", data = "<records>")]
+/// async fn import(mut records: Lines<'_, Record>) -> String {
+///     let mut count = 0;
+///     while let Some(record) = records.next().await {
+///         match record {
+///             Ok(record) => count += record.id,
+///             Err(e) => return format!("error after {} records: {}", count, e),
+///         }
+///     }
+///
+///     format!("imported {} records", count)
+/// }
+/// ```
+///
+/// # Incoming Data Limits
+///
+/// The entire stream is limited by the `ndjson` limit, 8MiB by default, while
+/// each line is limited by the `ndjson/line` limit, 1MiB by default. For
+/// instance, to allow streams of up to 1GiB with lines of up to 64KiB, you may
+/// add the following to your `Rocket.toml`:
+///
+/// ```toml
+/// [global.limits]
+/// ndjson = "1 GiB"
+/// "ndjson/line" = "64 KiB"
+/// ```
+pub struct Lines<'r, T> {
+    stream: Pin<Box<dyn Stream<Item = Result<T, Error<'r>>> + Send + 'r>>,
+}
+
+impl<'r, T: DeserializeOwned + Send + 'r> Lines<'r, T> {
+    fn new(req: &'r Request<'_>, data: Data<'r>) -> Self {
+        let limit = req.limits().get("ndjson").unwrap_or(Limits::NDJSON).as_u64();
+        let line_limit = req.limits().get("ndjson/line").unwrap_or(Limits::NDJSON_LINE);
+        let limit_exceeded = |what| {
+            Error::Io(io::Error::new(io::ErrorKind::UnexpectedEof, what))
+        };
+
+        let stream = async_stream::stream! {
+            // Read one byte past the limit to determine if it was exceeded.
+            let mut reader = BufReader::new(data.open(limit.saturating_add(1).into()));
+            let mut read = 0;
+            loop {
+                let mut line = Vec::new();
+                let mut taken = (&mut reader).take(line_limit.as_u64().saturating_add(1));
+                let n = match taken.read_until(b'\n', &mut line).await {
+                    Ok(0) => break,
+                    Ok(n) => n as u64,
+                    Err(e) => {
+                        yield Err(Error::Io(e));
+                        break;
+                    }
+                };
+
+                read += n;
+                if read > limit {
+                    yield Err(limit_exceeded("data limit exceeded"));
+                    break;
+                }
+
+                if line.last() == Some(&b'\n') {
+                    line.pop();
+                } else if n > line_limit {
+                    yield Err(limit_exceeded("line limit exceeded"));
+                    break;
+                }
+
+                let line = match String::from_utf8(line) {
+                    Ok(line) if line.trim().is_empty() => continue,
+                    Ok(line) => line,
+                    Err(e) => {
+                        yield Err(Error::Io(io::Error::new(io::ErrorKind::InvalidData, e)));
+                        break;
+                    }
+                };
+
+                match serde_json::from_str(&line) {
+                    Ok(value) => yield Ok(value),
+                    Err(e) => {
+                        yield Err(Error::Parse(local_cache!(req, line), e));
+                        break;
+                    }
+                }
+            }
+        };
+
+        Lines { stream: Box::pin(stream) }
+    }
+}
+
+#[crate::async_trait]
+impl<'r, T: DeserializeOwned + Send + 'r> FromData<'r> for Lines<'r, T> {
+    type Error = std::convert::Infallible;
+
+    async fn from_data(req: &'r Request<'_>, data: Data<'r>) -> Outcome<'r, Self> {
+        Outcome::Success(Lines::new(req, data))
+    }
+}
+
+impl<'r, T> Stream for Lines<'r, T> {
+    type Item = Result<T, Error<'r>>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.stream.as_mut().poll_next(cx)
+    }
+}
+
+impl<T> fmt::Debug for Lines<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Lines").finish_non_exhaustive()
+    }
+}
+
 /// Serializes the value into JSON. Returns a response with Content-Type JSON
 /// and a fixed-size body with the serialized value.
 impl<'r> Responder<'r, 'static> for Value {
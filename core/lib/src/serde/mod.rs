@@ -2,7 +2,10 @@
 //!
 //! * JSON support is provided by the [`Json`](json::Json) type.
 //! * MessagePack support is provided by the [`MsgPack`](msgpack::MsgPack) type.
+//! * CBOR support is provided by the [`Cbor`](cbor::Cbor) type.
 //! * UUID support is provided by the [`UUID`](uuid) type.
+//! * Content negotiation across the enabled formats is provided by the
+//!   [`Negotiated`] type.
 //!
 //! Types implement one or all of [`FromParam`](crate::request::FromParam),
 //! [`FromForm`](crate::form::FromForm), [`FromData`](crate::data::FromData),
@@ -50,6 +53,17 @@ pub mod json;
 #[cfg_attr(nightly, doc(cfg(feature = "msgpack")))]
 pub mod msgpack;
 
+#[cfg(feature = "cbor")]
+#[cfg_attr(nightly, doc(cfg(feature = "cbor")))]
+pub mod cbor;
+
 #[cfg(feature = "uuid")]
 #[cfg_attr(nightly, doc(cfg(feature = "uuid")))]
 pub mod uuid;
+
+#[cfg(any(feature = "json", feature = "msgpack", feature = "cbor"))]
+mod negotiated;
+
+#[cfg(any(feature = "json", feature = "msgpack", feature = "cbor"))]
+#[cfg_attr(nightly, doc(cfg(any(feature = "json", feature = "msgpack", feature = "cbor"))))]
+pub use negotiated::{Negotiated, NegotiatedError};
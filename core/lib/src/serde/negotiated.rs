@@ -0,0 +1,286 @@
+use std::{fmt, error};
+use std::ops::{Deref, DerefMut};
+
+use crate::request::Request;
+use crate::data::{Data, FromData, Outcome};
+use crate::response::{self, Responder};
+use crate::http::{ContentType, Header, MediaType, Status};
+
+#[cfg(feature = "json")]
+use crate::serde::json::{self, Json};
+
+#[cfg(feature = "msgpack")]
+use crate::serde::msgpack::{self, MsgPack};
+
+#[cfg(feature = "cbor")]
+use crate::serde::cbor::{self, Cbor};
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+/// A content-negotiated guard: consume and return data in any enabled serde
+/// format.
+///
+/// `Negotiated` reads and writes every format whose feature is enabled. The
+/// formats, in order of preference, are:
+///
+///   * [`Json`], with the `json` feature: `application/json`
+///   * [`MsgPack`], with the `msgpack` feature: `application/msgpack`
+///   * [`Cbor`], with the `cbor` feature: `application/cbor`
+///
+/// ## Sending
+///
+/// To respond with data serialized in the format the client prefers, return
+/// a `Negotiated<T>`, where `T` implements [`Serialize`]. The format is chosen
+/// according to the request's `Accept` header, including its q-values: the
+/// format given the highest weight by its most specific matching media range
+/// is chosen. Ties, as well as requests without a valid `Accept` header, are
+/// resolved in favor of the earliest format in the list above. If the client
+/// accepts no enabled format, the response fails with `406 Not Acceptable`.
+/// The response is otherwise exactly that of the chosen format's responder
+/// with an added `Vary: Accept` header.
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// # type User = usize;
+/// use rocket::serde::Negotiated;
+///
+/// #[get("/users/<id>")]
+/// fn user(id: usize) -> Negotiated<User> {
+///     let user_from_id = User::from(id);
+///     /* ... */
+///     Negotiated(user_from_id)
+/// }
+/// ```
+///
+/// ## Receiving
+///
+/// To deserialize request body data in any enabled format, add a `data` route
+/// argument with a target type of `Negotiated<T>`, where `T` implements
+/// [`DeserializeOwned`]. Because not every format can borrow from the request
+/// data, `T` cannot borrow either. The format is chosen according to the
+/// request's `Content-Type`. If the request has no `Content-Type`, or one that
+/// names no enabled format, the guard fails with `415 Unsupported Media Type`.
+/// Otherwise, the guard behaves exactly as the chosen format's data guard,
+/// including with respect to its data limit.
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// # type User = usize;
+/// use rocket::serde::Negotiated;
+///
+/// #[post("/users", data = "<user>")]
+/// fn new_user(user: Negotiated<User>) -> Negotiated<User> {
+///     /* ... */
+///     user
+/// }
+/// ```
+#[repr(transparent)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Negotiated<T>(pub T);
+
+/// Error returned by the [`Negotiated`] guard when deserialization fails.
+#[derive(Debug)]
+pub enum NegotiatedError<'a> {
+    /// The request's `Content-Type`, if any, names no enabled format.
+    Unsupported(Option<&'a ContentType>),
+
+    /// The request's data failed to deserialize as JSON.
+    #[cfg(feature = "json")]
+    #[cfg_attr(nightly, doc(cfg(feature = "json")))]
+    Json(json::Error<'a>),
+
+    /// The request's data failed to deserialize as MessagePack.
+    #[cfg(feature = "msgpack")]
+    #[cfg_attr(nightly, doc(cfg(feature = "msgpack")))]
+    MsgPack(msgpack::Error),
+
+    /// The request's data failed to deserialize as CBOR.
+    #[cfg(feature = "cbor")]
+    #[cfg_attr(nightly, doc(cfg(feature = "cbor")))]
+    Cbor(cbor::Error),
+}
+
+impl fmt::Display for NegotiatedError<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unsupported(Some(ct)) => write!(f, "unsupported content type: {}", ct),
+            Self::Unsupported(None) => write!(f, "missing content type"),
+            #[cfg(feature = "json")]
+            Self::Json(err) => write!(f, "json: {}", err),
+            #[cfg(feature = "msgpack")]
+            Self::MsgPack(err) => write!(f, "msgpack: {}", err),
+            #[cfg(feature = "cbor")]
+            Self::Cbor(err) => write!(f, "cbor: {}", err),
+        }
+    }
+}
+
+impl error::Error for NegotiatedError<'_> {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::Unsupported(_) => None,
+            #[cfg(feature = "json")]
+            Self::Json(err) => err.source(),
+            #[cfg(feature = "msgpack")]
+            Self::MsgPack(err) => Some(err),
+            #[cfg(feature = "cbor")]
+            Self::Cbor(err) => Some(err),
+        }
+    }
+}
+
+/// A format that [`Negotiated`] reads and writes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    #[cfg(feature = "json")]
+    Json,
+    #[cfg(feature = "msgpack")]
+    MsgPack,
+    #[cfg(feature = "cbor")]
+    Cbor,
+}
+
+impl Format {
+    /// The enabled formats, in order of preference.
+    const ALL: &'static [Format] = &[
+        #[cfg(feature = "json")]
+        Format::Json,
+        #[cfg(feature = "msgpack")]
+        Format::MsgPack,
+        #[cfg(feature = "cbor")]
+        Format::Cbor,
+    ];
+
+    fn media_type(self) -> MediaType {
+        match self {
+            #[cfg(feature = "json")]
+            Format::Json => MediaType::JSON,
+            #[cfg(feature = "msgpack")]
+            Format::MsgPack => MediaType::MsgPack,
+            #[cfg(feature = "cbor")]
+            Format::Cbor => MediaType::CBOR,
+        }
+    }
+
+    /// The format named by `content_type`, if any.
+    fn from_content_type(content_type: &ContentType) -> Option<Format> {
+        Format::ALL.iter().copied().find(|format| format.media_type() == **content_type)
+    }
+
+    /// The format `req` prefers, if it accepts any.
+    fn negotiate(req: &Request<'_>) -> Option<Format> {
+        let accept = match req.accept() {
+            Some(accept) => accept,
+            None => return Format::ALL.first().copied(),
+        };
+
+        let mut best: Option<(Format, f32)> = None;
+        for &format in Format::ALL {
+            let media_type = format.media_type();
+            let weight = accept.iter()
+                .filter(|range| {
+                    (range.top() == "*" || range.top() == media_type.top())
+                        && (range.sub() == "*" || range.sub() == media_type.sub())
+                })
+                .max_by_key(|range| range.specificity())
+                .map_or(0.0, |range| range.weight_or(1.0));
+
+            if weight > 0.0 && best.map_or(true, |(_, best)| weight > best) {
+                best = Some((format, weight));
+            }
+        }
+
+        best.map(|(format, _)| format)
+    }
+}
+
+impl<T> Negotiated<T> {
+    /// Consumes the `Negotiated` wrapper and returns the wrapped item.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use rocket::serde::Negotiated;
+    /// let string = "Hello".to_string();
+    /// let my_value = Negotiated(string);
+    /// assert_eq!(my_value.into_inner(), "Hello".to_string());
+    /// ```
+    #[inline(always)]
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+#[crate::async_trait]
+impl<'r, T: DeserializeOwned> FromData<'r> for Negotiated<T> {
+    type Error = NegotiatedError<'r>;
+
+    async fn from_data(req: &'r Request<'_>, data: Data<'r>) -> Outcome<'r, Self> {
+        let content_type = req.content_type();
+        match content_type.and_then(Format::from_content_type) {
+            #[cfg(feature = "json")]
+            Some(Format::Json) => <Json<T> as FromData<'r>>::from_data(req, data).await
+                .map(|Json(value)| Negotiated(value))
+                .map_failure(|(status, e)| (status, NegotiatedError::Json(e))),
+            #[cfg(feature = "msgpack")]
+            Some(Format::MsgPack) => <MsgPack<T> as FromData<'r>>::from_data(req, data).await
+                .map(|MsgPack(value)| Negotiated(value))
+                .map_failure(|(status, e)| (status, NegotiatedError::MsgPack(e))),
+            #[cfg(feature = "cbor")]
+            Some(Format::Cbor) => <Cbor<T> as FromData<'r>>::from_data(req, data).await
+                .map(|Cbor(value)| Negotiated(value))
+                .map_failure(|(status, e)| (status, NegotiatedError::Cbor(e))),
+            None => {
+                let error = NegotiatedError::Unsupported(content_type);
+                Outcome::Failure((Status::UnsupportedMediaType, error))
+            }
+        }
+    }
+}
+
+/// Serializes the wrapped value in the format the request prefers. Returns the
+/// response of that format's responder with a `Vary: Accept` header. If the
+/// request accepts no enabled format, an `Err` of `Status::NotAcceptable` is
+/// returned.
+impl<'r, T: Serialize> Responder<'r, 'static> for Negotiated<T> {
+    fn respond_to(self, req: &'r Request<'_>) -> response::Result<'static> {
+        let format = Format::negotiate(req).ok_or_else(|| {
+            info_!("Request accepts none of the available formats.");
+            Status::NotAcceptable
+        })?;
+
+        let mut response = match format {
+            #[cfg(feature = "json")]
+            Format::Json => Json(self.0).respond_to(req)?,
+            #[cfg(feature = "msgpack")]
+            Format::MsgPack => MsgPack(self.0).respond_to(req)?,
+            #[cfg(feature = "cbor")]
+            Format::Cbor => Cbor(self.0).respond_to(req)?,
+        };
+
+        response.adjoin_header(Header::new("Vary", "Accept"));
+        Ok(response)
+    }
+}
+
+impl<T> From<T> for Negotiated<T> {
+    fn from(value: T) -> Self {
+        Negotiated(value)
+    }
+}
+
+impl<T> Deref for Negotiated<T> {
+    type Target = T;
+
+    #[inline(always)]
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for Negotiated<T> {
+    #[inline(always)]
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
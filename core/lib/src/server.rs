@@ -1,5 +1,6 @@
 use std::io;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::time::Duration;
 use std::pin::Pin;
 
@@ -9,14 +10,19 @@ use tokio::time::sleep;
 use futures::stream::StreamExt;
 use futures::future::{FutureExt, Future, BoxFuture};
 
-use crate::{route, Rocket, Orbit, Request, Response, Data, Config};
+use crate::{route, response, Rocket, Orbit, Request, Response, Data, Config};
 use crate::form::Form;
 use crate::outcome::Outcome;
 use crate::error::{Error, ErrorKind};
 use crate::ext::{AsyncReadExt, CancellableListener, CancellableIo};
 use crate::request::ConnectionMeta;
+use crate::listener::{Listeners, ProxyListener};
+use crate::connection::{LimitedListener, LimitedIo};
 use crate::data::IoHandler;
 
+#[cfg(feature = "http2")]
+use crate::data::ByteUnit;
+
 use crate::http::{hyper, uncased, Method, Status, Header};
 use crate::http::private::{TcpListener, Listener, Connection, Incoming};
 
@@ -63,9 +69,12 @@ async fn handle<Fut, T, F>(name: Option<&str>, run: F) -> Option<T>
 // converts Hyper types into Rocket types, then calls the `dispatch` function,
 // which knows nothing about Hyper. Because responding depends on the
 // `HyperResponse` type, this function does the actual response processing.
+//
+// If `close` is `true`, the connection is closed after responding.
 async fn hyper_service_fn(
     rocket: Arc<Rocket<Orbit>>,
     conn: ConnectionMeta,
+    close: bool,
     mut hyp_req: hyper::Request<hyper::Body>,
 ) -> Result<hyper::Response<hyper::Body>, io::Error> {
     // This future must return a hyper::Response, but the response body might
@@ -80,12 +89,50 @@ async fn hyper_service_fn(
 
         // Convert a Hyper request into a Rocket request.
         let (h_parts, mut h_body) = hyp_req.into_parts();
+
+        // Only HTTP/1 can close a connection via a header; HTTP/2 forbids it.
+        let http1 = matches!(h_parts.version, hyper::Version::HTTP_10 | hyper::Version::HTTP_11);
+        let close = close && http1;
         match Request::from_hyp(&rocket, &h_parts, Some(conn)) {
             Ok(mut req) => {
+                // Reject requests with too many or too large headers.
+                let config = &rocket.config;
+                let header_size = h_parts.headers.iter()
+                    .map(|(name, value)| name.as_str().len() + value.len() + 4)
+                    .sum::<usize>();
+
+                if h_parts.headers.len() > config.max_headers
+                    || header_size as u64 > config.max_header_size.as_u64()
+                {
+                    warn_!("Request headers exceed configured limits.");
+                    let status = Status::RequestHeaderFieldsTooLarge;
+                    let mut response = rocket.handle_error(status, &req).await;
+                    close_connection(&mut response, http1);
+                    return rocket.send_response(response, tx).await;
+                }
+
                 // Convert into Rocket `Data`, dispatch request, write response.
                 let mut data = Data::from(&mut h_body);
-                let token = rocket.preprocess_request(&mut req, &mut data).await;
-                let mut response = rocket.dispatch(token, &req, data).await;
+                let deadline = match config.request_timeout {
+                    0 => None,
+                    n => Some(tokio::time::Instant::now() + Duration::from_secs(n.into())),
+                };
+
+                let preprocess = rocket.preprocess_request(&mut req, &mut data);
+                let response = match timed(deadline, preprocess).await {
+                    Some(token) => timed(deadline, rocket.dispatch(token, &req, data)).await,
+                    None => None,
+                };
+
+                let mut response = match response {
+                    Some(response) => response,
+                    None => {
+                        warn_!("Request timed out after {}s.", config.request_timeout);
+                        rocket.handle_error(Status::RequestTimeout, &req).await
+                    }
+                };
+
+                close_connection(&mut response, close);
                 let upgrade = response.take_upgrade(req.headers().get("upgrade"));
                 if let Ok(Some((proto, handler))) = upgrade {
                     rocket.handle_upgrade(response, proto, handler, pending_upgrade, tx).await;
@@ -103,7 +150,8 @@ async fn hyper_service_fn(
                 e.errors.iter().for_each(|e| warn_!("Error: {}.", e));
                 warn_!("Dispatching salvaged request to catcher: {}.", e.request);
 
-                let response = rocket.handle_error(Status::BadRequest, &e.request).await;
+                let mut response = rocket.handle_error(Status::BadRequest, &e.request).await;
+                close_connection(&mut response, close);
                 rocket.send_response(response, tx).await;
             }
         }
@@ -113,6 +161,45 @@ async fn hyper_service_fn(
     rx.await.map_err(|e| io::Error::new(io::ErrorKind::BrokenPipe, e))
 }
 
+// Runs `fut` to completion or until `deadline`, if any, returning `None` if the
+// deadline passes first.
+async fn timed<F: Future>(deadline: Option<tokio::time::Instant>, fut: F) -> Option<F::Output> {
+    match deadline {
+        Some(deadline) => tokio::time::timeout_at(deadline, fut).await.ok(),
+        None => Some(fut.await),
+    }
+}
+
+// Closes the connection after `response` if `close` is `true`.
+fn close_connection(response: &mut Response<'_>, close: bool) {
+    if close {
+        response.set_raw_header("Connection", "close");
+    }
+}
+
+// Returns a permanent redirect to the HTTPS equivalent of `req`'s URI.
+fn https_redirect(req: &hyper::Request<hyper::Body>, https_port: u16) -> hyper::Response<hyper::Body> {
+    use crate::http::uri::Host;
+
+    let host = req.headers().get("Host")
+        .and_then(|host| host.to_str().ok())
+        .and_then(|host| Host::parse(host).ok());
+
+    let path = req.uri().path_and_query().map_or("/", |p| p.as_str());
+    let response = match (host, https_port) {
+        (Some(host), 443) => hyper::Response::builder()
+            .status(Status::PermanentRedirect.code)
+            .header("Location", format!("https://{}{}", host.domain(), path)),
+        (Some(host), port) => hyper::Response::builder()
+            .status(Status::PermanentRedirect.code)
+            .header("Location", format!("https://{}:{}{}", host.domain(), port, path)),
+        (None, _) => hyper::Response::builder().status(Status::BadRequest.code),
+    };
+
+    // The host and path were parsed from valid header and URI values.
+    response.body(hyper::Body::empty()).expect("valid redirect response")
+}
+
 impl Rocket<Orbit> {
     /// Wrapper around `_send_response` to log a success or failure.
     #[inline]
@@ -219,6 +306,19 @@ impl Rocket<Orbit> {
         req: &mut Request<'_>,
         data: &mut Data<'_>
     ) -> RequestToken {
+        // Decode the body, if requested, before anything reads from it. The
+        // request then describes the decoded body.
+        #[cfg(feature = "compression")]
+        if self.config.decompress {
+            let codings = req.headers().get("Content-Encoding");
+            let encodings = crate::compression::Encoding::parse_applied(codings).ok();
+            if let Some(encodings) = encodings.filter(|e| !e.is_empty()) {
+                data.decode(&encodings);
+                req.remove_header("Content-Encoding");
+                req.remove_header("Content-Length");
+            }
+        }
+
         // Check if this is a form and if the form contains the special _method
         // field which we use to reinterpret the request's method.
         let (min_len, max_len) = ("_method=get".len(), "_method=delete".len());
@@ -257,6 +357,13 @@ impl Rocket<Orbit> {
         // Route the request and run the user's handlers.
         let mut response = self.route_and_process(request, data).await;
 
+        // Evaluate the request's preconditions, then serve only the requested
+        // ranges if the response allows it.
+        response::conditional::evaluate_preconditions(request, &mut response).await;
+        if !was_head_request {
+            response::ranged::resolve_ranges(request, &mut response).await;
+        }
+
         // Add a default 'Server' header if it isn't already there.
         // TODO: If removing Hyper, write out `Date` header too.
         if let Some(ident) = request.rocket().config.ident.as_str() {
@@ -281,7 +388,12 @@ impl Rocket<Orbit> {
         request: &'r Request<'s>,
         data: Data<'r>
     ) -> Response<'r> {
-        let mut response = match self.route(request, data).await {
+        let outcome = match self.reject_content_coding(request) {
+            Some(status) => Outcome::Failure(status),
+            None => self.route(request, data).await,
+        };
+
+        let mut response = match outcome {
             Outcome::Success(response) => response,
             Outcome::Forward((data, _)) if request.method() == Method::Head => {
                 info_!("Autohandling {} request.", Paint::default("HEAD").bold());
@@ -308,6 +420,23 @@ impl Rocket<Orbit> {
         response
     }
 
+    /// Returns `415 Unsupported Media Type` if request bodies are decoded but
+    /// `request` has a `Content-Encoding` that `preprocess_request()` couldn't
+    /// decode.
+    #[cfg_attr(not(feature = "compression"), allow(unused_variables))]
+    fn reject_content_coding(&self, request: &Request<'_>) -> Option<Status> {
+        #[cfg(feature = "compression")]
+        if self.config.decompress {
+            let codings = request.headers().get("Content-Encoding");
+            if let Err(coding) = crate::compression::Encoding::parse_applied(codings) {
+                warn_!("Request has unsupported content coding '{}'.", coding);
+                return Some(Status::UnsupportedMediaType);
+            }
+        }
+
+        None
+    }
+
     /// Tries to find a `Responder` for a given `request`. It does this by
     /// routing the request and calling the handler for each matching route
     /// until one of the handlers returns success or failure, or there are no
@@ -411,6 +540,39 @@ impl Rocket<Orbit> {
     {
         use std::net::ToSocketAddrs;
 
+        // Serve on sockets passed via socket activation if there are any.
+        #[cfg(unix)]
+        if self.config.socket_activation {
+            use crate::http::private::activated_listeners;
+
+            let activated = activated_listeners().map_err(ErrorKind::Bind)?;
+            if !activated.is_empty() {
+                return self.activated_http_server(activated, ready).await;
+            }
+
+            warn!("Socket activation is enabled but no sockets were passed.");
+            warn_!("Binding sockets as configured instead.");
+            self.config.socket_activation = false;
+        }
+
+        // Serve on a Unix domain socket instead of TCP if one is configured.
+        #[cfg(unix)]
+        if let Some(unix) = self.config.unix.clone() {
+            let l = unix.bind().map_err(ErrorKind::Bind)?;
+            ready(&mut self).await;
+            let result = self.http_server(l).await;
+            if let Err(e) = std::fs::remove_file(unix.path()) {
+                warn!("failed to remove unix socket file: {}", e);
+            }
+
+            return result;
+        }
+
+        // Serve on every configured endpoint if any are configured.
+        if !self.config.endpoints.is_empty() {
+            return self.endpoints_http_server(ready).await;
+        }
+
         // Determine the address we're going to serve on.
         let addr = format!("{}:{}", self.config.address, self.config.port);
         let mut addr = addr.to_socket_addrs()
@@ -423,10 +585,13 @@ impl Rocket<Orbit> {
                 use crate::http::tls::TlsListener;
 
                 let conf = config.to_native_config().map_err(ErrorKind::Io)?;
-                let l = TlsListener::bind(addr, conf).await.map_err(ErrorKind::Bind)?;
+                self.tls_reloader.watch(config, conf.resolver.clone(), self.shutdown());
+                let l = TcpListener::bind(addr).await.map_err(ErrorKind::Bind)?;
+                let l = TlsListener::from_listener(self.proxied(l), conf).map_err(ErrorKind::Bind)?;
                 addr = l.local_addr().unwrap_or(addr);
                 self.config.address = addr.ip();
                 self.config.port = addr.port();
+                self.addresses.push(addr);
                 ready(&mut self).await;
                 return self.http_server(l).await;
             }
@@ -436,13 +601,177 @@ impl Rocket<Orbit> {
         addr = l.local_addr().unwrap_or(addr);
         self.config.address = addr.ip();
         self.config.port = addr.port();
+        self.addresses.push(addr);
         ready(&mut self).await;
+        let l = self.proxied(l);
         self.http_server(l).await
     }
 
-    // TODO.async: Solidify the Listener APIs and make this function public
+    /// Wraps the TCP listener `l` to read PROXY protocol headers from the
+    /// configured trusted upstreams, if any. TLS, if enabled, is layered on
+    /// top so that headers sent ahead of the TLS handshake are read.
+    fn proxied(&self, l: TcpListener) -> ProxyListener<TcpListener> {
+        let trusted = self.config.proxy_protocol.as_ref()
+            .map_or_else(Vec::new, |proxy| proxy.trusted.clone());
+
+        let timeout = match self.config.header_timeout {
+            0 => None,
+            n => Some(Duration::from_secs(n.into())),
+        };
+
+        ProxyListener::new(l, trusted).header_timeout(timeout)
+    }
+
+    #[cfg(unix)]
+    async fn activated_http_server<C>(
+        mut self,
+        activated: Vec<(Option<String>, crate::http::private::ActivatedListener)>,
+        ready: C
+    ) -> Result<Self, Error>
+        where C: for<'a> Fn(&'a Self) -> BoxFuture<'a, ()>
+    {
+        use crate::http::private::ActivatedListener;
+
+        let mut listeners = Listeners::default();
+        for (name, listener) in activated {
+            let name = name.as_deref().unwrap_or("unnamed");
+            match listener {
+                ActivatedListener::Tcp(l) => {
+                    let addr = l.local_addr().map_err(ErrorKind::Bind)?;
+                    info_!("adopted TCP socket {} ({})", Paint::default(addr).bold(), name);
+                    self.addresses.push(addr);
+
+                    #[cfg(feature = "tls")]
+                    if self.config.tls_enabled() {
+                        if let Some(ref config) = self.config.tls {
+                            use crate::http::tls::TlsListener;
+
+                            let conf = config.to_native_config().map_err(ErrorKind::Io)?;
+                            self.tls_reloader.watch(config, conf.resolver.clone(), self.shutdown());
+                            let l = TlsListener::from_listener(self.proxied(l), conf)
+                                .map_err(ErrorKind::Bind)?;
+
+                            listeners.push(l);
+                            continue;
+                        }
+                    }
+
+                    listeners.push(self.proxied(l));
+                }
+                ActivatedListener::Unix(l) => {
+                    info_!("adopted Unix domain socket ({})", name);
+                    listeners.push(l);
+                }
+            }
+        }
+
+        if let Some(addr) = self.addresses.first().copied() {
+            self.config.address = addr.ip();
+            self.config.port = addr.port();
+        }
+
+        ready(&mut self).await;
+        self.http_server(listeners).await
+    }
+
+    async fn endpoints_http_server<C>(mut self, ready: C) -> Result<Self, Error>
+        where C: for<'a> Fn(&'a Self) -> BoxFuture<'a, ()>
+    {
+        let mut listeners = Listeners::default();
+        let mut redirects = Listeners::default();
+        for endpoint in self.config.endpoints.clone() {
+            let addr = endpoint.socket_addr();
+
+            #[cfg(feature = "tls")]
+            if endpoint.tls_enabled() {
+                if let Some(config) = endpoint.tls() {
+                    use crate::http::tls::TlsListener;
+
+                    let conf = config.to_native_config().map_err(ErrorKind::Io)?;
+                    self.tls_reloader.watch(config, conf.resolver.clone(), self.shutdown());
+                    let l = TcpListener::bind(addr).await.map_err(ErrorKind::Bind)?;
+                    let l = TlsListener::from_listener(self.proxied(l), conf)
+                        .map_err(ErrorKind::Bind)?;
+
+                    self.addresses.push(l.local_addr().unwrap_or(addr));
+                    listeners.push(l);
+                    continue;
+                }
+            }
+
+            let l = TcpListener::bind(addr).await.map_err(ErrorKind::Bind)?;
+            self.addresses.push(l.local_addr().unwrap_or(addr));
+            match endpoint.https_redirect() {
+                true => redirects.push(self.proxied(l)),
+                false => listeners.push(self.proxied(l)),
+            }
+        }
+
+        // Each endpoint's bound address was pushed in order.
+        let https_port = self.config.endpoints.iter()
+            .zip(self.addresses.iter())
+            .find(|(endpoint, _)| endpoint.tls_enabled())
+            .map(|(_, addr)| addr.port());
+
+        if !redirects.is_empty() && https_port.is_none() {
+            let msg = "`https_redirect` is enabled but no endpoint has TLS enabled";
+            return Err(ErrorKind::Io(io::Error::new(io::ErrorKind::InvalidInput, msg)).into());
+        }
+
+        // The first endpoint stands in for `address` and `port`.
+        if let Some(addr) = self.addresses.first().copied() {
+            self.config.address = addr.ip();
+            self.config.port = addr.port();
+        }
+
+        ready(&mut self).await;
+        match https_port {
+            Some(port) if !redirects.is_empty() => {
+                self.serve(listeners, Some((redirects, port))).await
+            }
+            _ => self.http_server(listeners).await,
+        }
+    }
+
+    /// Spawns a server on `listener` that responds to every request with a
+    /// redirect to the same host and path at HTTPS port `https_port`. The
+    /// listener's connections count toward the application-wide connection
+    /// limit, draw from the same identifiers, and run the same fairings as
+    /// those of every other listener.
+    fn https_redirect_server(rocket: &Arc<Self>, listener: Listeners, https_port: u16) {
+        let shutdown = rocket.shutdown();
+        let grace = rocket.config.shutdown.grace as u64;
+        let mercy = rocket.config.shutdown.mercy as u64;
+
+        let listener = LimitedListener::new(listener, rocket);
+        let listener = CancellableListener::new(shutdown.clone(), listener, grace, mercy);
+        let server = hyper::server::Server::builder(Incoming::new(listener).nodelay(true))
+            .serve(hyper::service::make_service_fn(move |_| async move {
+                Ok::<_, std::convert::Infallible>(hyper::service::service_fn(move |req| {
+                    futures::future::ok::<_, std::convert::Infallible>(
+                        https_redirect(&req, https_port)
+                    )
+                }))
+            }))
+            .with_graceful_shutdown(shutdown);
+
+        tokio::spawn(async move {
+            if let Err(e) = server.await {
+                warn!("HTTPS redirect server failed: {}", e);
+            }
+        });
+    }
+
     pub(crate) async fn http_server<L>(self, listener: L) -> Result<Self, Error>
         where L: Listener + Send, <L as Listener>::Connection: Send + Unpin + 'static
+    {
+        self.serve(listener, None).await
+    }
+
+    /// Serves on `listener` and, if `redirect` is `Some((listener, port))`,
+    /// redirects every request on `listener` to HTTPS port `port`.
+    async fn serve<L>(self, listener: L, redirect: Option<(Listeners, u16)>) -> Result<Self, Error>
+        where L: Listener + Send, <L as Listener>::Connection: Send + Unpin + 'static
     {
         // Emit a warning if we're not running inside of Rocket's async runtime.
         if self.config.profile == Config::DEBUG_PROFILE {
@@ -481,38 +810,77 @@ impl Rocket<Orbit> {
             });
         }
 
-        // Save the keep-alive value for later use; we're about to move `self`.
+        // Save config values for later use; we're about to move `self`.
         let keep_alive = self.config.keep_alive;
+        let max_requests = self.config.max_requests_per_connection;
+        #[cfg(feature = "http2")]
+        let http2 = self.config.http2.clone();
+
+        // Hyper rejects request heads larger than its read buffer with a 431.
+        // Leave room for the request line; Rocket checks the limit precisely.
+        let max_buf_size = self.config.max_header_size.as_u64()
+            .try_into()
+            .map_or(usize::MAX, |size: usize| size.saturating_add(8192));
 
         // Create the Hyper `Service`.
         let rocket = Arc::new(self);
-        let service_fn = |conn: &CancellableIo<_, L::Connection>| {
+        if let Some((listener, port)) = redirect {
+            Rocket::https_redirect_server(&rocket, listener, port);
+        }
+
+        let service_fn = |conn: &CancellableIo<_, LimitedIo<L::Connection>>| {
             let rocket = rocket.clone();
             let connection = ConnectionMeta {
                 remote: conn.peer_address(),
                 client_certificates: conn.peer_certificates(),
+                peer_credentials: conn.peer_credentials(),
+                proxy_header: conn.proxy_header(),
+                info: conn.io().map(|io| io.info().clone()),
             };
 
+            let requests = AtomicUsize::new(0);
             async move {
                 Ok::<_, std::convert::Infallible>(hyper::service::service_fn(move |req| {
-                    hyper_service_fn(rocket.clone(), connection.clone(), req)
+                    let n = requests.fetch_add(1, Ordering::AcqRel) + 1;
+                    let close = max_requests != 0 && n >= max_requests;
+                    hyper_service_fn(rocket.clone(), connection.clone(), close, req)
                 }))
             }
         };
 
         // NOTE: `hyper` uses `tokio::spawn()` as the default executor.
+        let listener = LimitedListener::new(listener, &rocket);
         let listener = CancellableListener::new(shutdown.clone(), listener, grace, mercy);
         let builder = hyper::server::Server::builder(Incoming::new(listener).nodelay(true));
 
         #[cfg(feature = "http2")]
-        let builder = builder.http2_keep_alive_interval(match keep_alive {
-            0 => None,
-            n => Some(Duration::from_secs(n as u64))
-        });
+        let builder = {
+            let size = |size: Option<ByteUnit>| size.map(|s| s.as_u64() as u32);
+            let keep_alive = http2.keep_alive_interval.unwrap_or(keep_alive);
+            let builder = builder
+                .http2_max_concurrent_streams(http2.max_concurrent_streams)
+                .http2_initial_stream_window_size(size(http2.initial_stream_window_size))
+                .http2_initial_connection_window_size(size(http2.initial_connection_window_size))
+                .http2_max_frame_size(size(http2.max_frame_size))
+                .http2_keep_alive_interval(match keep_alive {
+                    0 => None,
+                    n => Some(Duration::from_secs(n as u64))
+                })
+                .http2_keep_alive_timeout(Duration::from_secs(http2.keep_alive_timeout.into()));
+
+            let builder = match http2.max_header_list_size {
+                Some(size) => builder.http2_max_header_list_size(size.as_u64() as u32),
+                None => builder,
+            };
+
+            // Setting a window size disables the adaptive window, so go last.
+            builder.http2_adaptive_window(http2.adaptive_window)
+        };
 
         let server = builder
             .http1_keepalive(keep_alive != 0)
             .http1_preserve_header_case(true)
+            .http1_max_buf_size(max_buf_size)
             .serve(hyper::service::make_service_fn(service_fn))
             .with_graceful_shutdown(shutdown.clone());
 
@@ -0,0 +1,210 @@
+//! Reloading of TLS certificates.
+//!
+//! Rocket loads the certificates configured via
+//! [`TlsConfig`](crate::config::TlsConfig) when a TLS listener is bound. They
+//! can be reloaded without a restart in two ways:
+//!
+//!   * By setting [`reload_interval`](crate::config::TlsConfig::reload_interval)
+//!     to periodically check the certificate and key files for changes.
+//!
+//!   * By calling [`TlsReloader::reload()`] with a handle retrieved via
+//!     [`Rocket::tls_reloader()`](crate::Rocket::tls_reloader()) or as a
+//!     request guard.
+//!
+//! In either case, handshakes that begin after a successful reload use the new
+//! certificates while established connections are unaffected. If reloading
+//! fails, say because a file is missing or malformed, the previous certificates
+//! remain in use. Mutual TLS CA certificates are not reloaded.
+//!
+//! # Example
+//!
+//! Reloading certificates on `SIGHUP`:
+//!
+//! ```rust,no_run
+//! # #[macro_use] extern crate rocket;
+//! use rocket::fairing::AdHoc;
+//! # #[cfg(unix)]
+//! use rocket::tokio::signal::unix::{signal, SignalKind};
+//!
+//! #[launch]
+//! fn rocket() -> _ {
+//!     rocket::build().attach(AdHoc::on_liftoff("Reload on SIGHUP", |rocket| {
+//!         let reloader = rocket.tls_reloader();
+//!         Box::pin(async move {
+//!             # #[cfg(unix)]
+//!             let mut hangup = signal(SignalKind::hangup()).expect("SIGHUP handler");
+//!             # #[cfg(unix)]
+//!             rocket::tokio::spawn(async move {
+//!                 while hangup.recv().await.is_some() {
+//!                     if let Err(e) = reloader.reload() {
+//!                         eprintln!("failed to reload TLS certificates: {}", e);
+//!                     }
+//!                 }
+//!             });
+//!         })
+//!     }))
+//! }
+//! ```
+
+use std::{fmt, fs, io};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+
+use futures::StreamExt;
+use tokio_stream::wrappers::IntervalStream;
+
+use crate::Shutdown;
+use crate::config::TlsConfig;
+use crate::http::tls::CertResolver;
+use crate::request::{FromRequest, Outcome, Request};
+
+/// A handle to reload the TLS certificates of a running application.
+///
+/// A `TlsReloader` can be retrieved via
+/// [`Rocket::tls_reloader()`](crate::Rocket::tls_reloader()) or as a request
+/// guard. See the [module level docs](self) for details.
+///
+/// # Example
+///
+/// ```rust
+/// # #[macro_use] extern crate rocket;
+/// use rocket::http::Status;
+/// use rocket::tls::TlsReloader;
+///
+/// #[post("/reload")]
+/// fn reload(reloader: TlsReloader) -> Status {
+///     match reloader.reload() {
+///         Ok(()) => Status::NoContent,
+///         Err(_) => Status::InternalServerError,
+///     }
+/// }
+/// ```
+#[derive(Clone, Default)]
+#[cfg_attr(nightly, doc(cfg(feature = "tls")))]
+pub struct TlsReloader(Arc<Mutex<Vec<Watched>>>);
+
+/// The configuration and resolver for one TLS listener.
+struct Watched {
+    config: TlsConfig,
+    resolver: Arc<CertResolver>,
+    modified: Vec<Option<SystemTime>>,
+}
+
+impl Watched {
+    fn new(config: TlsConfig, resolver: Arc<CertResolver>) -> Self {
+        let modified = Self::modified(&config);
+        Watched { config, resolver, modified }
+    }
+
+    fn modified(config: &TlsConfig) -> Vec<Option<SystemTime>> {
+        config.paths()
+            .map(|path| fs::metadata(path).and_then(|m| m.modified()).ok())
+            .collect()
+    }
+
+    /// Reloads the certificates. The modification times are snapshotted
+    /// before loading, so a write racing the load triggers another reload,
+    /// but only recorded on success, so a failed load, say of a half-written
+    /// pair, is retried on the next tick.
+    fn reload(&mut self) -> io::Result<()> {
+        let modified = Self::modified(&self.config);
+        let (default, sni) = self.config.load_certs()?;
+        self.resolver.set(default, sni);
+        self.modified = modified;
+        Ok(())
+    }
+}
+
+impl TlsReloader {
+    /// Reloads the certificates of every TLS listener from their configured
+    /// sources. Returns the first error encountered, if any. Listeners that
+    /// fail to reload continue to use their previous certificates.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # let rocket = rocket::build();
+    /// # let client = rocket::local::blocking::Client::debug(rocket).unwrap();
+    /// # let rocket = client.rocket();
+    /// let reloader = rocket.tls_reloader();
+    /// if let Err(e) = reloader.reload() {
+    ///     eprintln!("failed to reload TLS certificates: {}", e);
+    /// }
+    /// ```
+    pub fn reload(&self) -> io::Result<()> {
+        let mut result = Ok(());
+        for watched in self.0.lock().unwrap_or_else(|e| e.into_inner()).iter_mut() {
+            if let Err(e) = watched.reload() {
+                result = result.and(Err(e));
+            }
+        }
+
+        result
+    }
+
+    /// Reloads the certificates of the `i`th listener if any of its files have
+    /// changed since they were last loaded. Returns `true` if it reloaded.
+    fn reload_modified(&self, i: usize) -> io::Result<bool> {
+        let mut watched = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        let watched = &mut watched[i];
+        if Watched::modified(&watched.config) == watched.modified {
+            return Ok(false);
+        }
+
+        watched.reload().map(|_| true)
+    }
+
+    /// Registers a TLS listener configured with `config` that resolves
+    /// certificates with `resolver`. If the config has a nonzero reload
+    /// interval, spawns a task to reload on file changes until `shutdown`.
+    pub(crate) fn watch(
+        &self,
+        config: &TlsConfig,
+        resolver: Arc<CertResolver>,
+        shutdown: Shutdown,
+    ) {
+        let i = {
+            let mut watched = self.0.lock().unwrap_or_else(|e| e.into_inner());
+            watched.push(Watched::new(config.clone(), resolver));
+            watched.len() - 1
+        };
+
+        if config.reload_interval() == 0 {
+            return;
+        }
+
+        let reloader = self.clone();
+        let period = Duration::from_secs(config.reload_interval().into());
+        let start = tokio::time::Instant::now() + period;
+        let mut ticks = IntervalStream::new(tokio::time::interval_at(start, period))
+            .take_until(shutdown);
+
+        tokio::spawn(async move {
+            while ticks.next().await.is_some() {
+                match reloader.reload_modified(i) {
+                    Ok(true) => info!("TLS certificates reloaded."),
+                    Ok(false) => {},
+                    Err(e) => warn!("Failed to reload TLS certificates: {}", e),
+                }
+            }
+        });
+    }
+}
+
+impl fmt::Debug for TlsReloader {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let watched = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        f.debug_struct("TlsReloader")
+            .field("listeners", &watched.len())
+            .finish()
+    }
+}
+
+#[crate::async_trait]
+impl<'r> FromRequest<'r> for TlsReloader {
+    type Error = std::convert::Infallible;
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        Outcome::Success(request.rocket().tls_reloader())
+    }
+}
@@ -0,0 +1,106 @@
+#[macro_use] extern crate rocket;
+
+use rocket::{Config, Build, Rocket};
+use rocket::config::CatcherFormat;
+use rocket::http::{Accept, ContentType, Header, Status};
+use rocket::local::blocking::{Client, LocalResponse};
+
+#[get("/locked")]
+fn locked() -> Status {
+    Status::Locked
+}
+
+#[get("/custom")]
+fn custom() -> Status {
+    Status::new(599)
+}
+
+fn rocket(catcher_format: CatcherFormat) -> Rocket<Build> {
+    let config = Config { catcher_format, ..Config::debug_default() };
+    rocket::custom(config).mount("/", routes![locked, custom])
+}
+
+fn get<'c>(client: &'c Client, uri: &'static str, accept: Option<Accept>) -> LocalResponse<'c> {
+    let mut request = client.get(uri).header(Header::new("X-Request-ID", "req-42"));
+    if let Some(accept) = accept {
+        request.add_header(accept);
+    }
+
+    request.dispatch()
+}
+
+#[test]
+fn negotiates_format() {
+    let client = Client::debug(rocket(CatcherFormat::Auto)).unwrap();
+    let response = get(&client, "/missing", Some(Accept::JSON));
+    assert_eq!(response.status(), Status::NotFound);
+    assert_eq!(response.content_type(), Some(ContentType::JSON));
+    assert_eq!(response.into_string().unwrap(), r#"{
+  "error": {
+    "code": 404,
+    "reason": "Not Found",
+    "description": "The requested resource could not be found.",
+    "request_id": "req-42"
+  }
+}"#);
+
+    let response = get(&client, "/missing", Some(Accept::Plain));
+    assert_eq!(response.content_type(), Some(ContentType::Plain));
+    assert_eq!(response.into_string().unwrap(),
+        "404 Not Found: The requested resource could not be found.\nRequest ID: req-42\n");
+
+    for accept in [Some(Accept::HTML), Some(Accept::Any), None] {
+        let response = get(&client, "/missing", accept);
+        assert_eq!(response.content_type(), Some(ContentType::HTML));
+
+        let html = response.into_string().unwrap();
+        assert!(html.contains("<h1>404: Not Found</h1>"));
+        assert!(html.contains("Request ID: req-42"));
+    }
+}
+
+#[test]
+fn configured_format_is_used() {
+    let client = Client::debug(rocket(CatcherFormat::Json)).unwrap();
+    let response = get(&client, "/missing", Some(Accept::HTML));
+    assert_eq!(response.content_type(), Some(ContentType::JSON));
+
+    let client = Client::debug(rocket(CatcherFormat::Plain)).unwrap();
+    let response = get(&client, "/missing", Some(Accept::JSON));
+    assert_eq!(response.content_type(), Some(ContentType::Plain));
+
+    let client = Client::debug(rocket(CatcherFormat::Html)).unwrap();
+    let response = get(&client, "/missing", Some(Accept::Plain));
+    assert_eq!(response.content_type(), Some(ContentType::HTML));
+}
+
+#[test]
+fn includes_reason_and_id() {
+    let client = Client::debug(rocket(CatcherFormat::Plain)).unwrap();
+    let response = get(&client, "/locked", None);
+    assert_eq!(response.status(), Status::Locked);
+    assert_eq!(response.into_string().unwrap(),
+        "423 Locked: An unknown error has occurred.\nRequest ID: req-42\n");
+
+    let response = get(&client, "/custom", None);
+    assert_eq!(response.into_string().unwrap(),
+        "599 Unknown Error: An unknown error has occurred.\nRequest ID: req-42\n");
+
+    let response = client.get("/missing").header(Header::new("X-Request-ID", "<b>")).dispatch();
+    let body = response.into_string().unwrap();
+    let id = body.rsplit("Request ID: ").next().unwrap().trim_end();
+    assert_eq!(id.len(), 16);
+    assert!(id.chars().all(|c| c.is_ascii_hexdigit()));
+}
+
+#[test]
+fn format_is_configurable() {
+    use rocket::figment::{Figment, providers::Serialized};
+
+    let figment = Figment::from(Config::debug_default())
+        .merge(Serialized::default("catcher_format", "plain"));
+
+    let config = Config::from(figment);
+    assert_eq!(config.catcher_format, CatcherFormat::Plain);
+    assert_eq!(Config::default().catcher_format, CatcherFormat::Auto);
+}
@@ -0,0 +1,105 @@
+#![cfg(feature = "cbor")]
+
+#[macro_use] extern crate rocket;
+
+use rocket::{Config, Build, Rocket};
+use rocket::data::{Limits, ToByteUnit};
+use rocket::form::Form;
+use rocket::http::{ContentType, Status};
+use rocket::local::blocking::Client;
+use rocket::serde::{Serialize, Deserialize, cbor::Cbor};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(crate = "rocket::serde")]
+struct Task {
+    title: String,
+    done: bool,
+}
+
+impl Task {
+    fn new(title: &str, done: bool) -> Task {
+        Task { title: title.into(), done }
+    }
+}
+
+#[derive(FromForm)]
+struct Upload {
+    task: Cbor<Task>,
+}
+
+#[post("/task", format = "cbor", data = "<task>")]
+fn toggle(task: Cbor<Task>) -> Cbor<Task> {
+    Cbor(Task { done: !task.done, ..task.into_inner() })
+}
+
+#[post("/upload", data = "<form>")]
+fn upload(form: Form<Upload>) -> String {
+    format!("{}: {}", form.task.title, form.task.done)
+}
+
+fn rocket() -> Rocket<Build> {
+    let config = Config {
+        limits: Limits::default().limit("cbor", 32.bytes()),
+        ..Config::debug_default()
+    };
+
+    rocket::custom(config).mount("/", routes![toggle, upload])
+}
+
+#[test]
+fn cbor_round_trips() {
+    let client = Client::debug(rocket()).unwrap();
+    let response = client.post("/task")
+        .cbor(&Task::new("write docs", false))
+        .dispatch();
+
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(response.content_type(), Some(ContentType::CBOR));
+
+    let bytes = response.into_bytes().unwrap();
+    let task: Task = rocket::serde::cbor::from_slice(&bytes).unwrap();
+    assert_eq!(task, Task::new("write docs", true));
+    assert_eq!(rocket::serde::cbor::to_vec(&task).unwrap(), bytes);
+}
+
+#[test]
+fn cbor_failures() {
+    let client = Client::debug(rocket()).unwrap();
+    // A reserved initial byte and a truncated map, respectively.
+    for body in [&[0x1c][..], &[0xa2, 0x65]] {
+        let response = client.post("/task")
+            .header(ContentType::CBOR)
+            .body(body)
+            .dispatch();
+
+        assert_eq!(response.status(), Status::BadRequest);
+    }
+
+    let response = client.post("/task").cbor(&5).dispatch();
+    assert_eq!(response.status(), Status::UnprocessableEntity);
+
+    let title = "a".repeat(64);
+    let response = client.post("/task")
+        .cbor(&Task::new(&title, false))
+        .dispatch();
+
+    assert_eq!(response.status(), Status::PayloadTooLarge);
+}
+
+#[test]
+fn cbor_form_field() {
+    let client = Client::debug(rocket()).unwrap();
+    let task = rocket::serde::cbor::to_vec(&Task::new("ship", true)).unwrap();
+
+    let mut body = b"--X\r\nContent-Disposition: form-data; name=\"task\"\r\n".to_vec();
+    body.extend_from_slice(b"Content-Type: application/cbor\r\n\r\n");
+    body.extend_from_slice(&task);
+    body.extend_from_slice(b"\r\n--X--\r\n");
+
+    let response = client.post("/upload")
+        .header("multipart/form-data; boundary=X".parse::<ContentType>().unwrap())
+        .body(body)
+        .dispatch();
+
+    assert_eq!(response.into_string().unwrap(), "ship: true");
+}
@@ -0,0 +1,102 @@
+//! Fixtures shared by tests that launch Rocket and talk to it over sockets.
+//!
+//! Each test crate uses only some of these, so unused ones aren't warned about.
+#![allow(dead_code)]
+
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use rocket::{Build, Config, Rocket, Shutdown};
+use rocket::fairing::AdHoc;
+use rocket::futures::channel::oneshot;
+use rocket::tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use rocket::tokio::net::TcpStream;
+use rocket::tokio::time::timeout;
+
+/// How long to wait for a response before failing a test.
+pub const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The state of an application as it lifted off.
+pub struct Liftoff {
+    /// A handle to shut the application down.
+    pub shutdown: Shutdown,
+    /// The addresses the application is serving on.
+    pub addresses: Vec<SocketAddr>,
+    /// The application's final configuration.
+    pub config: Config,
+}
+
+/// Attaches a liftoff fairing to `rocket` that sends a [`Liftoff`] on the
+/// returned channel. Use when launching other than via [`launch()`].
+pub fn notify_liftoff(rocket: Rocket<Build>) -> (Rocket<Build>, oneshot::Receiver<Liftoff>) {
+    let (tx, rx) = oneshot::channel();
+    let rocket = rocket.attach(AdHoc::on_liftoff("Liftoff Notify", move |rocket| {
+        Box::pin(async move {
+            let _ = tx.send(Liftoff {
+                shutdown: rocket.shutdown(),
+                addresses: rocket.addresses().to_vec(),
+                config: rocket.config().clone(),
+            });
+        })
+    }));
+
+    (rocket, rx)
+}
+
+/// Launches `rocket` in the background, returning once it has lifted off.
+pub async fn launch(rocket: Rocket<Build>) -> Liftoff {
+    let (rocket, liftoff) = notify_liftoff(rocket);
+    rocket::tokio::spawn(rocket.launch());
+    liftoff.await.expect("liftoff")
+}
+
+/// Launches `rocket` in the background on an ephemeral TCP port, returning
+/// once it has lifted off with its shutdown handle and address.
+pub async fn launch_ephemeral(rocket: Rocket<Build>) -> (Shutdown, SocketAddr) {
+    let figment = rocket.figment().clone().merge((Config::PORT, 0));
+    let liftoff = launch(rocket.configure(figment)).await;
+    (liftoff.shutdown, liftoff.addresses[0])
+}
+
+/// A `GET` request for `uri` with a `Host` of `host` that asks the server to
+/// close the connection once it responds.
+pub fn get_request(host: &str, uri: &str) -> String {
+    format!("GET {} HTTP/1.1\r\nHost: {}\r\nConnection: close\r\n\r\n", uri, host)
+}
+
+/// Reads from `stream` until it's closed, returning everything read.
+pub async fn read_to_end<S: AsyncRead + Unpin>(stream: &mut S) -> String {
+    let mut response = vec![];
+    let read = stream.read_to_end(&mut response);
+    timeout(TIMEOUT, read).await.expect("response in time").unwrap();
+    String::from_utf8_lossy(&response).into_owned()
+}
+
+/// Writes `request` to `stream` and reads the response until it's closed.
+pub async fn exchange<S>(mut stream: S, request: &[u8]) -> String
+    where S: AsyncRead + AsyncWrite + Unpin
+{
+    stream.write_all(request).await.unwrap();
+    read_to_end(&mut stream).await
+}
+
+/// Sends `request` on a new TCP connection to `addr`, returning the response.
+pub async fn request(addr: SocketAddr, request: &str) -> String {
+    exchange(TcpStream::connect(addr).await.unwrap(), request.as_bytes()).await
+}
+
+/// Connects to `addr` with a blocking socket that times out reads.
+pub fn connect(addr: SocketAddr) -> std::net::TcpStream {
+    let stream = std::net::TcpStream::connect(addr).unwrap();
+    stream.set_read_timeout(Some(TIMEOUT)).unwrap();
+    stream
+}
+
+/// Like [`exchange()`], but for blocking streams. Errors while reading, such
+/// as a TLS peer closing without `close_notify`, end the response.
+pub fn exchange_blocking<S: std::io::Read + std::io::Write>(mut stream: S, request: &[u8]) -> String {
+    stream.write_all(request).unwrap();
+    let mut response = vec![];
+    let _ = stream.read_to_end(&mut response);
+    String::from_utf8_lossy(&response).into_owned()
+}
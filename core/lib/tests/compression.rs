@@ -0,0 +1,255 @@
+#![cfg(feature = "compression")]
+
+#[macro_use] extern crate rocket;
+
+use std::io::Cursor;
+use std::time::Duration;
+
+use async_compression::tokio::bufread::{BrotliDecoder, GzipDecoder, ZlibDecoder, ZstdDecoder};
+use rocket::compression::{Compression, Encoding, Uncompressed};
+use rocket::data::ToByteUnit;
+use rocket::http::{ContentType, Header, MediaType, Status};
+use rocket::local::asynchronous::{Client, LocalResponse};
+use rocket::response::Ranged;
+use rocket::response::stream::{Event, EventStream};
+use rocket::tokio::io::{AsyncRead, AsyncReadExt, BufReader};
+use rocket::tokio::time::{sleep, timeout};
+
+fn text() -> String {
+    "Hello, compression! ".repeat(200)
+}
+
+#[get("/")]
+fn index() -> String {
+    text()
+}
+
+#[get("/small")]
+fn small() -> &'static str {
+    "tiny"
+}
+
+#[get("/png")]
+fn png() -> (ContentType, Vec<u8>) {
+    (ContentType::PNG, vec![0; 4096])
+}
+
+#[get("/svg")]
+fn svg() -> (ContentType, String) {
+    (ContentType::SVG, text())
+}
+
+#[get("/opt-out")]
+fn opt_out() -> Uncompressed<String> {
+    Uncompressed(text())
+}
+
+#[get("/ranged")]
+fn ranged() -> Ranged<Cursor<Vec<u8>>> {
+    Ranged(Cursor::new(text().into_bytes()))
+}
+
+#[derive(Responder)]
+struct Varying(String, Header<'static>);
+
+#[get("/vary")]
+fn vary() -> Varying {
+    Varying(text(), Header::new("Vary", "Origin"))
+}
+
+#[get("/events")]
+fn events() -> EventStream![] {
+    let stream = EventStream! {
+        yield Event::data("a");
+        sleep(Duration::from_secs(60 * 60)).await;
+        yield Event::data("b");
+    };
+
+    stream.heartbeat(None)
+}
+
+async fn client(compression: Compression) -> Client {
+    let rocket = rocket::build()
+        .mount("/", routes![index, small, png, svg, opt_out, ranged, vary, events])
+        .attach(compression);
+
+    Client::debug(rocket).await.unwrap()
+}
+
+async fn decode<R: AsyncRead + Unpin>(encoding: Option<&str>, body: R) -> String {
+    let body = BufReader::new(body);
+    let mut reader: Box<dyn AsyncRead + Unpin> = match encoding {
+        None => Box::new(body),
+        Some("gzip") => Box::new(GzipDecoder::new(body)),
+        Some("deflate") => Box::new(ZlibDecoder::new(body)),
+        Some("br") => Box::new(BrotliDecoder::new(body)),
+        Some("zstd") => Box::new(ZstdDecoder::new(body)),
+        Some(e) => panic!("unexpected encoding: {}", e),
+    };
+
+    let mut string = String::new();
+    reader.read_to_string(&mut string).await.unwrap();
+    string
+}
+
+async fn get<'c>(client: &'c Client, uri: &'static str, accept: &'static str) -> LocalResponse<'c> {
+    let response = client.get(uri)
+        .header(Header::new("Accept-Encoding", accept))
+        .dispatch().await;
+
+    assert_eq!(response.status(), Status::Ok);
+    response
+}
+
+fn encoding<'a>(response: &'a LocalResponse<'_>) -> Option<&'a str> {
+    response.headers().get_one("Content-Encoding")
+}
+
+#[rocket::async_test]
+async fn negotiates_encoding() {
+    let client = client(Compression::new()).await;
+    let cases = [
+        ("gzip", Some("gzip")),
+        ("deflate", Some("deflate")),
+        ("br", Some("br")),
+        ("zstd", Some("zstd")),
+        ("gzip, deflate, br, zstd", Some("br")),
+        ("gzip;q=1.0, br;q=0.5", Some("gzip")),
+        ("gzip;q=0.5, zstd;q=0.5, deflate", Some("deflate")),
+        ("X-GZIP", Some("gzip")),
+        ("*", Some("br")),
+        ("br;q=0, *;q=0.1", Some("zstd")),
+        ("gzip;q=0", None),
+        ("identity", None),
+        ("compress, *;q=0", None),
+        ("", None),
+    ];
+
+    for (accept, expected) in cases {
+        let response = get(&client, "/", accept).await;
+        assert_eq!(encoding(&response), expected, "Accept-Encoding: {}", accept);
+        assert_eq!(response.headers().get_one("Vary"), Some("Accept-Encoding"));
+        if expected.is_some() {
+            assert!(response.headers().get_one("Content-Length").is_none());
+        }
+
+        let expected = expected.map(|s| s.to_string());
+        assert_eq!(decode(expected.as_deref(), response).await, text());
+    }
+
+    let response = client.get("/").dispatch().await;
+    assert_eq!(encoding(&response), None);
+    assert_eq!(response.into_string().await.unwrap(), text());
+}
+
+#[rocket::async_test]
+async fn respects_enabled_encodings() {
+    let compression = Compression::new().with_encodings([Encoding::Deflate, Encoding::Gzip]);
+    let client = client(compression).await;
+
+    let response = get(&client, "/", "gzip, deflate, br, zstd").await;
+    assert_eq!(encoding(&response), Some("deflate"));
+    assert_eq!(decode(Some("deflate"), response).await, text());
+
+    let response = get(&client, "/", "br, zstd, gzip;q=0.1").await;
+    assert_eq!(encoding(&response), Some("gzip"));
+
+    let response = get(&client, "/", "br, zstd").await;
+    assert_eq!(encoding(&response), None);
+
+    let client = self::client(Compression::new().with_encodings([])).await;
+    let response = get(&client, "/", "gzip").await;
+    assert_eq!(encoding(&response), None);
+}
+
+#[rocket::async_test]
+async fn skips_small_and_excluded_bodies() {
+    let client = client(Compression::new().exclude(MediaType::SVG)).await;
+
+    let response = get(&client, "/small", "gzip").await;
+    assert_eq!(encoding(&response), None);
+    assert_eq!(response.headers().get_one("Vary"), Some("Accept-Encoding"));
+    assert_eq!(response.into_string().await.unwrap(), "tiny");
+
+    let response = get(&client, "/png", "gzip").await;
+    assert_eq!(encoding(&response), None);
+    assert_eq!(response.headers().get_one("Vary"), None);
+
+    let response = get(&client, "/svg", "gzip").await;
+    assert_eq!(encoding(&response), None);
+
+    let client = self::client(Compression::new().with_min_size(0.bytes())).await;
+    let response = get(&client, "/small", "gzip").await;
+    assert_eq!(encoding(&response), Some("gzip"));
+    assert_eq!(decode(Some("gzip"), response).await, "tiny");
+
+    let response = get(&client, "/svg", "gzip").await;
+    assert_eq!(encoding(&response), Some("gzip"));
+}
+
+#[rocket::async_test]
+async fn routes_can_opt_out() {
+    let client = client(Compression::new()).await;
+    let response = get(&client, "/opt-out", "gzip").await;
+    assert_eq!(encoding(&response), None);
+    assert_eq!(response.into_string().await.unwrap(), text());
+}
+
+#[rocket::async_test]
+async fn head_requests_match_get_requests() {
+    let client = client(Compression::new()).await;
+    let response = client.head("/")
+        .header(Header::new("Accept-Encoding", "gzip"))
+        .dispatch().await;
+
+    assert_eq!(encoding(&response), Some("gzip"));
+    assert_eq!(response.headers().get_one("Vary"), Some("Accept-Encoding"));
+    assert!(response.headers().get_one("Content-Length").is_none());
+    assert!(response.into_bytes().await.unwrap_or_default().is_empty());
+}
+
+#[rocket::async_test]
+async fn compressed_responses_do_not_accept_ranges() {
+    let client = client(Compression::new()).await;
+    let response = get(&client, "/ranged", "identity").await;
+    assert_eq!(response.headers().get_one("Accept-Ranges"), Some("bytes"));
+
+    let response = get(&client, "/ranged", "gzip").await;
+    assert_eq!(encoding(&response), Some("gzip"));
+    assert!(response.headers().get_one("Accept-Ranges").is_none());
+    assert_eq!(decode(Some("gzip"), response).await, text());
+
+    // Ranges are served from the uncompressed representation.
+    let response = client.get("/ranged")
+        .header(Header::new("Accept-Encoding", "gzip"))
+        .header(Header::new("Range", "bytes=0-4"))
+        .dispatch().await;
+
+    assert_eq!(response.status(), Status::PartialContent);
+    assert_eq!(encoding(&response), None);
+    assert_eq!(response.headers().get_one("Content-Range"), Some("bytes 0-4/4000"));
+    assert_eq!(response.into_string().await.unwrap(), "Hello");
+}
+
+#[rocket::async_test]
+async fn vary_is_merged() {
+    let client = client(Compression::new()).await;
+    let response = get(&client, "/vary", "gzip").await;
+    let vary: Vec<_> = response.headers().get("Vary").collect();
+    assert_eq!(vary, ["Origin", "Accept-Encoding"]);
+}
+
+#[rocket::async_test]
+async fn event_streams_are_flushed_per_event() {
+    let client = client(Compression::new()).await;
+    let response = get(&client, "/events", "gzip").await;
+    assert_eq!(encoding(&response), Some("gzip"));
+
+    let mut decoder = GzipDecoder::new(BufReader::new(response));
+    let mut buf = [0; 64];
+    let n = timeout(Duration::from_secs(5), decoder.read(&mut buf)).await
+        .expect("first event to be flushed")
+        .unwrap();
+
+    assert_eq!(std::str::from_utf8(&buf[..n]).unwrap(), "data:a\n\n");
+}
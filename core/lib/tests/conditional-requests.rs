@@ -0,0 +1,220 @@
+#[macro_use] extern crate rocket;
+
+use rocket::fs::{relative, FileServer};
+use rocket::http::{Header, Status};
+use rocket::local::blocking::{Client, LocalResponse};
+use rocket::response::Conditional;
+
+const MODIFIED: &str = "Wed, 21 Oct 2015 07:28:00 GMT";
+
+#[derive(Responder)]
+struct Validated(&'static str, Header<'static>, Header<'static>);
+
+#[get("/strong")]
+fn strong() -> Conditional<String> {
+    Conditional::new("Hello, world!".into())
+}
+
+#[get("/weak")]
+fn weak() -> Conditional<&'static str> {
+    Conditional::weak("Hello, world!")
+}
+
+#[get("/tagged")]
+fn tagged() -> Conditional<Validated> {
+    Conditional::new(Validated(
+        "Hello, world!",
+        Header::new("ETag", "\"v1\""),
+        Header::new("Last-Modified", MODIFIED),
+    ))
+}
+
+#[get("/failure")]
+fn failure() -> Conditional<Status> {
+    Conditional::new(Status::NotFound)
+}
+
+#[get("/plain")]
+fn plain() -> &'static str {
+    "Hello, world!"
+}
+
+#[post("/strong")]
+fn post() -> Conditional<&'static str> {
+    Conditional::new("Posted!")
+}
+
+fn client() -> Client {
+    let rocket = rocket::build()
+        .mount("/", routes![strong, weak, tagged, failure, plain, post])
+        .mount("/static", FileServer::from(relative!("tests/static")));
+
+    Client::debug(rocket).unwrap()
+}
+
+fn etag<'a>(response: &'a LocalResponse<'_>) -> &'a str {
+    response.headers().get_one("ETag").expect("ETag header")
+}
+
+fn get<'c>(client: &'c Client, uri: &'static str, headers: &[(&'static str, &str)]) -> Status {
+    let mut request = client.get(uri);
+    for (name, value) in headers {
+        request.add_header(Header::new(*name, value.to_string()));
+    }
+
+    request.dispatch().status()
+}
+
+#[test]
+fn conditional_computes_etags() {
+    let client = client();
+    let response = client.get("/strong").dispatch();
+    let strong = etag(&response).to_string();
+    assert_eq!(response.into_string().unwrap(), "Hello, world!");
+
+    // The length and 64-bit FNV-1a hash of the body: stable across compilers.
+    assert_eq!(strong, "\"d-38d1334144987bf4\"");
+
+    let response = client.get("/strong").dispatch();
+    assert_eq!(etag(&response), strong);
+
+    let response = client.get("/weak").dispatch();
+    assert_eq!(etag(&response), format!("W/{}", strong));
+
+    let response = client.get("/tagged").dispatch();
+    assert_eq!(etag(&response), "\"v1\"");
+
+    let response = client.get("/failure").dispatch();
+    assert_eq!(response.status(), Status::NotFound);
+    assert!(response.headers().get_one("ETag").is_none());
+}
+
+#[test]
+fn if_none_match() {
+    let client = client();
+    let strong = etag(&client.get("/strong").dispatch()).to_string();
+    let weak = etag(&client.get("/weak").dispatch()).to_string();
+
+    let response = client.get("/strong")
+        .header(Header::new("If-None-Match", strong.clone()))
+        .dispatch();
+
+    assert_eq!(response.status(), Status::NotModified);
+    assert_eq!(etag(&response), strong);
+    assert!(response.content_type().is_none());
+    assert!(response.into_string().unwrap_or_default().is_empty());
+
+    assert_eq!(get(&client, "/weak", &[("If-None-Match", &weak)]), Status::NotModified);
+    assert_eq!(get(&client, "/weak", &[("If-None-Match", &strong)]), Status::NotModified);
+    assert_eq!(get(&client, "/strong", &[("If-None-Match", "*")]), Status::NotModified);
+    assert_eq!(get(&client, "/strong", &[("If-None-Match", "\"x\", \"y\"")]), Status::Ok);
+
+    let response = client.head("/strong")
+        .header(Header::new("If-None-Match", strong.clone()))
+        .dispatch();
+
+    assert_eq!(response.status(), Status::NotModified);
+}
+
+#[test]
+fn if_modified_since() {
+    let client = client();
+    let cases = [
+        (MODIFIED, Status::NotModified),
+        ("Thu, 22 Oct 2015 07:28:00 GMT", Status::NotModified),
+        ("Tue, 20 Oct 2015 07:28:00 GMT", Status::Ok),
+        ("not a date", Status::Ok),
+    ];
+
+    for (date, status) in cases {
+        assert_eq!(get(&client, "/tagged", &[("If-Modified-Since", date)]), status, "{}", date);
+    }
+
+    // `If-None-Match` takes precedence over `If-Modified-Since`.
+    let headers = [("If-None-Match", "\"v2\""), ("If-Modified-Since", MODIFIED)];
+    assert_eq!(get(&client, "/tagged", &headers), Status::Ok);
+}
+
+#[test]
+fn if_match_and_if_unmodified_since() {
+    let client = client();
+    let cases = [
+        (("If-Match", "\"v1\""), Status::Ok),
+        (("If-Match", "\"v0\", \"v1\""), Status::Ok),
+        (("If-Match", "*"), Status::Ok),
+        (("If-Match", "W/\"v1\""), Status::PreconditionFailed),
+        (("If-Match", "\"v2\""), Status::PreconditionFailed),
+        (("If-Unmodified-Since", MODIFIED), Status::Ok),
+        (("If-Unmodified-Since", "Tue, 20 Oct 2015 07:28:00 GMT"), Status::PreconditionFailed),
+        (("If-Unmodified-Since", "garbage"), Status::Ok),
+    ];
+
+    for (header, status) in cases {
+        assert_eq!(get(&client, "/tagged", &[header]), status, "{:?}", header);
+    }
+
+    // `If-Match` takes precedence over `If-Unmodified-Since`.
+    let headers = [
+        ("If-Match", "\"v1\""),
+        ("If-Unmodified-Since", "Tue, 20 Oct 2015 07:28:00 GMT"),
+    ];
+    assert_eq!(get(&client, "/tagged", &headers), Status::Ok);
+
+    // A failed `If-Match` precludes a `304`.
+    let headers = [("If-Match", "\"v2\""), ("If-None-Match", "\"v1\"")];
+    assert_eq!(get(&client, "/tagged", &headers), Status::PreconditionFailed);
+}
+
+#[test]
+fn unsafe_methods_are_not_evaluated() {
+    let client = client();
+    let response = client.post("/strong")
+        .header(Header::new("If-Match", "\"nope\""))
+        .dispatch();
+
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(response.into_string().unwrap(), "Posted!");
+}
+
+#[test]
+fn responses_without_validators_are_not_evaluated() {
+    let client = client();
+    let response = client.get("/plain")
+        .header(Header::new("If-Match", "\"nope\""))
+        .dispatch();
+
+    assert!(response.headers().get_one("ETag").is_none());
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(response.into_string().unwrap(), "Hello, world!");
+
+    assert_eq!(get(&client, "/plain", &[("If-None-Match", "*")]), Status::Ok);
+    assert_eq!(get(&client, "/plain", &[("If-Unmodified-Since", MODIFIED)]), Status::Ok);
+}
+
+#[test]
+fn files_have_validators() {
+    let client = client();
+    let response = client.get("/static/index.html").dispatch();
+    let etag = etag(&response).to_string();
+    let modified = response.headers().get_one("Last-Modified").unwrap().to_string();
+    assert!(etag.starts_with("\"1e-"));
+    assert!(modified.ends_with(" GMT"));
+
+    let status = get(&client, "/static/index.html", &[("If-None-Match", &etag)]);
+    assert_eq!(status, Status::NotModified);
+
+    let status = get(&client, "/static/index.html", &[("If-Modified-Since", &modified)]);
+    assert_eq!(status, Status::NotModified);
+
+    let status = get(&client, "/static/index.html", &[("If-Match", "\"other\"")]);
+    assert_eq!(status, Status::PreconditionFailed);
+
+    // A matching `If-Range` still yields a partial response.
+    let response = client.get("/static/index.html")
+        .header(Header::new("Range", "bytes=0-3"))
+        .header(Header::new("If-Range", etag))
+        .dispatch();
+
+    assert_eq!(response.status(), Status::PartialContent);
+    assert_eq!(response.into_string().unwrap(), "Just");
+}
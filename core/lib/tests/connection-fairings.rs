@@ -0,0 +1,207 @@
+#[macro_use] extern crate rocket;
+
+mod common;
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::time::Duration;
+
+use rocket::{Config, Shutdown, Rocket, Build};
+use rocket::fairing::AdHoc;
+use rocket::listener::{ConnectionId, ConnectionInfo};
+use rocket::local::blocking::Client;
+use rocket::http::Status;
+use rocket::tokio::io::{AsyncReadExt, AsyncWriteExt};
+use rocket::tokio::net::TcpStream;
+use rocket::tokio::time::{sleep, timeout};
+
+#[get("/")]
+fn index(id: ConnectionId) -> String {
+    id.to_string()
+}
+
+#[get("/info")]
+fn info(conn: &ConnectionInfo) -> String {
+    format!("{:?}", conn.remote().map(|addr| addr.ip()))
+}
+
+async fn launch(rocket: Rocket<Build>) -> (Shutdown, SocketAddr) {
+    common::launch_ephemeral(rocket.mount("/", routes![index, info])).await
+}
+
+/// Reads from `stream` until it's closed, returning the response body. A reset
+/// connection has an empty body.
+async fn read_body(stream: &mut TcpStream) -> String {
+    let mut response = vec![];
+    let read = stream.read_to_end(&mut response);
+    if timeout(common::TIMEOUT, read).await.unwrap().is_err() {
+        return String::new();
+    }
+
+    let response = String::from_utf8(response).unwrap();
+    response.split("\r\n\r\n").nth(1).unwrap_or("").to_string()
+}
+
+/// Sends `n` requests for `path` on one connection, returning the bodies.
+async fn requests(addr: SocketAddr, path: &str, n: usize) -> Vec<String> {
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+    let mut bodies = vec![];
+    for i in 1..=n {
+        let close = if i == n { "Connection: close\r\n" } else { "" };
+        let request = format!("GET {} HTTP/1.1\r\nHost: localhost\r\n{}\r\n", path, close);
+        stream.write_all(request.as_bytes()).await.unwrap();
+        if i == n {
+            bodies.push(read_body(&mut stream).await);
+            continue;
+        }
+
+        let mut buf = vec![0; 1024];
+        let n = timeout(common::TIMEOUT, stream.read(&mut buf)).await.unwrap().unwrap();
+        let response = String::from_utf8_lossy(&buf[..n]).to_string();
+        bodies.push(response.split("\r\n\r\n").nth(1).unwrap().to_string());
+    }
+
+    bodies
+}
+
+#[rocket::async_test]
+async fn connect_fairings_can_reject() {
+    let deny = Arc::new(AtomicBool::new(true));
+    let fairing_deny = deny.clone();
+    let rocket = rocket::custom(Config::debug_default())
+        .attach(AdHoc::on_connect("Deny List", move |conn| {
+            let deny = fairing_deny.clone();
+            Box::pin(async move {
+                let local = conn.remote().map_or(false, |addr| addr.ip().is_loopback());
+                match local && deny.load(Ordering::Acquire) {
+                    true => Err(()),
+                    false => Ok(()),
+                }
+            })
+        }))
+        .attach(AdHoc::on_connect("Accept All", |_| Box::pin(async { Ok(()) })));
+
+    let (shutdown, addr) = launch(rocket).await;
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+    let _ = stream.write_all(b"GET / HTTP/1.1\r\nHost: localhost\r\n\r\n").await;
+    assert_eq!(read_body(&mut stream).await, "");
+
+    deny.store(false, Ordering::Release);
+    let bodies = requests(addr, "/info", 1).await;
+    assert_eq!(bodies, ["Some(127.0.0.1)"]);
+    shutdown.notify();
+}
+
+#[rocket::async_test]
+async fn disconnect_fairings_see_totals() {
+    let active = Arc::new(AtomicUsize::new(0));
+    let closed = Arc::new(AtomicUsize::new(0));
+    let read = Arc::new(AtomicU64::new(0));
+    let written = Arc::new(AtomicU64::new(0));
+
+    let (on_connect, on_disconnect) = (active.clone(), active.clone());
+    let (c, r, w) = (closed.clone(), read.clone(), written.clone());
+    let rocket = rocket::custom(Config::debug_default())
+        .attach(AdHoc::on_connect("Count", move |_| {
+            on_connect.fetch_add(1, Ordering::AcqRel);
+            Box::pin(async { Ok(()) })
+        }))
+        .attach(AdHoc::on_disconnect("Uncount", move |conn| {
+            on_disconnect.fetch_sub(1, Ordering::AcqRel);
+            r.fetch_add(conn.bytes_read(), Ordering::AcqRel);
+            w.fetch_add(conn.bytes_written(), Ordering::AcqRel);
+            c.fetch_add(1, Ordering::AcqRel);
+            Box::pin(async { })
+        }));
+
+    let (shutdown, addr) = launch(rocket).await;
+    let stream = TcpStream::connect(addr).await.unwrap();
+    for _ in 0..50 {
+        if active.load(Ordering::Acquire) == 1 { break; }
+        sleep(Duration::from_millis(50)).await;
+    }
+
+    assert_eq!(active.load(Ordering::Acquire), 1);
+    assert_eq!(requests(addr, "/", 2).await.len(), 2);
+    drop(stream);
+
+    for _ in 0..50 {
+        if closed.load(Ordering::Acquire) == 2 { break; }
+        sleep(Duration::from_millis(50)).await;
+    }
+
+    assert_eq!(closed.load(Ordering::Acquire), 2);
+    assert_eq!(active.load(Ordering::Acquire), 0);
+    assert!(read.load(Ordering::Acquire) > 2 * 30);
+    assert!(written.load(Ordering::Acquire) > 2 * 30);
+    shutdown.notify();
+}
+
+#[rocket::async_test]
+async fn requests_are_tagged_with_connection_id() {
+    let (shutdown, addr) = launch(rocket::custom(Config::debug_default())).await;
+
+    let first = requests(addr, "/", 3).await;
+    assert!(first.iter().all(|id| id == &first[0]), "{:?}", first);
+
+    let second = requests(addr, "/", 2).await;
+    assert!(second.iter().all(|id| id == &second[0]), "{:?}", second);
+    assert_ne!(first[0], second[0]);
+    shutdown.notify();
+}
+
+#[test]
+fn local_requests_have_no_connection() {
+    let client = Client::debug_with(routes![index, info]).unwrap();
+    assert_eq!(client.get("/").dispatch().status(), Status::NotFound);
+    assert_eq!(client.get("/info").dispatch().status(), Status::NotFound);
+}
+
+#[cfg(feature = "tls")]
+#[rocket::async_test]
+async fn connect_fairings_see_tls_info() {
+    use std::io::Cursor;
+    use std::sync::Mutex;
+
+    use rocket::config::TlsConfig;
+    use rocket::http::tls::rustls::{self, ClientConfig, ClientConnection, ServerName};
+    use rocket::http::tls::util::load_ca_certs;
+
+    const PRIVATE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../../examples/tls/private");
+
+    let tls = TlsConfig::from_paths(
+        format!("{}/rsa_sha256_cert.pem", PRIVATE),
+        format!("{}/rsa_sha256_key.pem", PRIVATE),
+    );
+
+    let seen = Arc::new(Mutex::new(None));
+    let fairing_seen = seen.clone();
+    let rocket = rocket::custom(Config { tls: Some(tls), ..Config::debug_default() })
+        .attach(AdHoc::on_connect("TLS Info", move |conn| {
+            *fairing_seen.lock().unwrap() = conn.tls().cloned();
+            Box::pin(async { Ok(()) })
+        }));
+
+    let (shutdown, addr) = launch(rocket).await;
+    let body = rocket::tokio::task::spawn_blocking(move || {
+        let ca = std::fs::read(format!("{}/ca_cert.pem", PRIVATE)).unwrap();
+        let mut config = ClientConfig::builder()
+            .with_safe_defaults()
+            .with_root_certificates(load_ca_certs(&mut Cursor::new(ca)).unwrap())
+            .with_no_client_auth();
+
+        config.alpn_protocols = vec![b"http/1.1".to_vec()];
+        let name = ServerName::try_from("localhost").unwrap();
+        let mut conn = ClientConnection::new(Arc::new(config), name).unwrap();
+        let mut sock = common::connect(addr);
+        let stream = rustls::Stream::new(&mut conn, &mut sock);
+        common::exchange_blocking(stream, common::get_request("localhost", "/").as_bytes())
+    }).await.unwrap();
+
+    assert!(body.starts_with("HTTP/1.1 200 OK\r\n"), "{}", body);
+    let tls = seen.lock().unwrap().clone().expect("TLS info");
+    assert_eq!(tls.server_name(), Some("localhost"));
+    assert_eq!(tls.alpn_protocol(), Some(&b"http/1.1"[..]));
+    shutdown.notify();
+}
@@ -0,0 +1,151 @@
+#[macro_use] extern crate rocket;
+
+mod common;
+
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use rocket::{Config, Shutdown};
+use rocket::data::ToByteUnit;
+use rocket::tokio::io::{AsyncReadExt, AsyncWriteExt};
+use rocket::tokio::net::TcpStream;
+use rocket::tokio::time::{sleep, timeout};
+
+#[get("/")]
+fn index() -> &'static str {
+    "Hello, world!"
+}
+
+#[get("/slow")]
+async fn slow() -> &'static str {
+    sleep(Duration::from_secs(3)).await;
+    "Finally."
+}
+
+async fn launch(config: Config) -> (Shutdown, SocketAddr) {
+    common::launch_ephemeral(rocket::custom(config).mount("/", routes![index, slow])).await
+}
+
+/// Reads one response with a body of `Hello, world!` from `stream`.
+async fn read_response(stream: &mut TcpStream) -> String {
+    let mut response = vec![];
+    while !response.ends_with(b"Hello, world!") {
+        let mut buf = [0; 1024];
+        let n = timeout(common::TIMEOUT, stream.read(&mut buf)).await.unwrap().unwrap();
+        assert_ne!(n, 0, "connection closed: {:?}", String::from_utf8_lossy(&response));
+        response.extend_from_slice(&buf[..n]);
+    }
+
+    String::from_utf8(response).unwrap()
+}
+
+#[rocket::async_test]
+async fn slow_headers_time_out() {
+    let (shutdown, addr) = launch(Config { header_timeout: 1, ..Config::debug_default() }).await;
+
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+    stream.write_all(b"GET / HTTP/1.1\r\nHost: localhost\r\n").await.unwrap();
+    let response = common::read_to_end(&mut stream).await;
+    assert!(response.starts_with("HTTP/1.1 408 Request Timeout\r\n"), "{}", response);
+
+    // An idle connection is closed without a response.
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+    assert_eq!(common::read_to_end(&mut stream).await, "");
+
+    // A timely request is unaffected, as is a kept-alive connection.
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+    for _ in 0..2 {
+        stream.write_all(b"GET / HTTP/1.1\r\nHost: localhost\r\n\r\n").await.unwrap();
+        let response = read_response(&mut stream).await;
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"), "{}", response);
+        sleep(Duration::from_millis(1500)).await;
+    }
+
+    shutdown.notify();
+}
+
+#[rocket::async_test]
+async fn large_headers_are_rejected() {
+    let config = Config {
+        max_headers: 5,
+        max_header_size: 1.kibibytes(),
+        ..Config::debug_default()
+    };
+
+    let (shutdown, addr) = launch(config).await;
+
+    let ok = "GET / HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n";
+    let response = common::request(addr, ok).await;
+    assert!(response.starts_with("HTTP/1.1 200 OK\r\n"), "{}", response);
+
+    let headers: String = (0..5).map(|i| format!("X-Header-{}: {}\r\n", i, i)).collect();
+    let many = format!("GET / HTTP/1.1\r\nHost: localhost\r\n{}Connection: close\r\n\r\n", headers);
+    let response = common::request(addr, &many).await;
+    assert!(response.starts_with("HTTP/1.1 431 "), "{}", response);
+
+    let value = "a".repeat(1024);
+    let large = format!("GET / HTTP/1.1\r\nHost: localhost\r\nX-Large: {}\r\n\r\n", value);
+    let response = common::request(addr, &large).await;
+    assert!(response.starts_with("HTTP/1.1 431 "), "{}", response);
+
+    shutdown.notify();
+}
+
+#[rocket::async_test]
+async fn excess_connections_are_rejected() {
+    let (shutdown, addr) = launch(Config { max_connections: 1, ..Config::debug_default() }).await;
+
+    // Ensure the first connection has been accepted before opening another.
+    let mut first = TcpStream::connect(addr).await.unwrap();
+    first.write_all(b"GET / HTTP/1.1\r\nHost: localhost\r\n\r\n").await.unwrap();
+    read_response(&mut first).await;
+
+    let ok = "GET / HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n";
+    let response = common::request(addr, ok).await;
+    assert!(response.starts_with("HTTP/1.1 503 Service Unavailable\r\n"), "{}", response);
+
+    // Once the first connection closes, new connections are served.
+    drop(first);
+    for _ in 0..50 {
+        if common::request(addr, ok).await.starts_with("HTTP/1.1 200 OK\r\n") {
+            return shutdown.notify();
+        }
+
+        sleep(Duration::from_millis(100)).await;
+    }
+
+    panic!("connection was not released");
+}
+
+#[rocket::async_test]
+async fn connections_close_after_max_requests() {
+    let config = Config { max_requests_per_connection: 2, ..Config::debug_default() };
+    let (shutdown, addr) = launch(config).await;
+
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+    stream.write_all(b"GET / HTTP/1.1\r\nHost: localhost\r\n\r\n").await.unwrap();
+    let response = read_response(&mut stream).await;
+    assert!(!response.to_lowercase().contains("connection: close"), "{}", response);
+
+    stream.write_all(b"GET / HTTP/1.1\r\nHost: localhost\r\n\r\n").await.unwrap();
+    let response = common::read_to_end(&mut stream).await;
+    assert!(response.starts_with("HTTP/1.1 200 OK\r\n"), "{}", response);
+    assert!(response.to_lowercase().contains("connection: close"), "{}", response);
+
+    shutdown.notify();
+}
+
+#[rocket::async_test]
+async fn slow_requests_time_out() {
+    let (shutdown, addr) = launch(Config { request_timeout: 1, ..Config::debug_default() }).await;
+
+    let slow = "GET /slow HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n";
+    let response = common::request(addr, slow).await;
+    assert!(response.starts_with("HTTP/1.1 408 Request Timeout\r\n"), "{}", response);
+
+    let ok = "GET / HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n";
+    let response = common::request(addr, ok).await;
+    assert!(response.starts_with("HTTP/1.1 200 OK\r\n"), "{}", response);
+
+    shutdown.notify();
+}
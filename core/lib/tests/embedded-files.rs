@@ -0,0 +1,212 @@
+use std::path::Path;
+
+use rocket::{Rocket, Route, Build};
+use rocket::http::{Accept, ContentType, Header, Status};
+use rocket::local::blocking::Client;
+use rocket::fs::{embed, relative, Embedded, EmbeddedFileServer, Options};
+
+static STATIC: Embedded = embed!("tests/static");
+
+fn rocket() -> Rocket<Build> {
+    let precompressed = embed!("/tests/precompressed");
+    rocket::build()
+        .mount("/default", EmbeddedFileServer::from(STATIC))
+        .mount("/no_index", EmbeddedFileServer::new(STATIC, Options::None))
+        .mount("/dots", EmbeddedFileServer::new(STATIC, Options::DotFiles))
+        .mount("/both", EmbeddedFileServer::new(STATIC, Options::DotFiles | Options::Index))
+        .mount("/redir", EmbeddedFileServer::new(STATIC, Options::NormalizeDirs))
+        .mount("/listing", EmbeddedFileServer::new(STATIC, Options::Listing))
+        .mount("/gz", EmbeddedFileServer::new(precompressed, Options::Precompressed)
+            .cache_control("*.js", "immutable")
+            .default_cache_control("no-cache"))
+}
+
+static REGULAR_FILES: &[&str] = &[
+    "index.html",
+    "inner/goodbye",
+    "inner/index.html",
+    "other/hello.txt",
+];
+
+static HIDDEN_FILES: &[&str] = &[
+    ".hidden",
+    "inner/.hideme",
+];
+
+static INDEXED_DIRECTORIES: &[&str] = &[
+    "",
+    "inner/",
+];
+
+fn contents(path: &str) -> Vec<u8> {
+    let mut path = Path::new(relative!("tests/static")).join(path);
+    if path.is_dir() {
+        path = path.join("index.html");
+    }
+
+    std::fs::read(path).expect("read file")
+}
+
+fn assert_all(client: &Client, prefix: &str, paths: &[&str], exist: bool) {
+    for path in paths {
+        let response = client.get(format!("/{}/{}", prefix, path)).dispatch();
+        if exist {
+            assert_eq!(response.status(), Status::Ok, "{}", path);
+            assert_eq!(response.into_bytes().unwrap(), contents(path));
+        } else {
+            assert_eq!(response.status(), Status::NotFound, "{}", path);
+        }
+    }
+}
+
+#[test]
+fn test_embedded_files() {
+    let files = STATIC.iter().map(|file| file.path()).collect::<Vec<_>>();
+    assert_eq!(files, [".hidden", "index.html", "inner/.hideme", "inner/goodbye",
+        "inner/index.html", "other/hello.txt"]);
+
+    for file in STATIC.iter() {
+        assert_eq!(file.contents(), contents(file.path()));
+    }
+}
+
+#[test]
+fn test_options() {
+    let client = Client::debug(rocket()).expect("valid rocket");
+    assert_all(&client, "default", REGULAR_FILES, true);
+    assert_all(&client, "default", HIDDEN_FILES, false);
+    assert_all(&client, "default", INDEXED_DIRECTORIES, true);
+
+    assert_all(&client, "no_index", REGULAR_FILES, true);
+    assert_all(&client, "no_index", HIDDEN_FILES, false);
+    assert_all(&client, "no_index", INDEXED_DIRECTORIES, false);
+
+    assert_all(&client, "dots", REGULAR_FILES, true);
+    assert_all(&client, "dots", HIDDEN_FILES, true);
+    assert_all(&client, "dots", INDEXED_DIRECTORIES, false);
+
+    assert_all(&client, "both", REGULAR_FILES, true);
+    assert_all(&client, "both", HIDDEN_FILES, true);
+    assert_all(&client, "both", INDEXED_DIRECTORIES, true);
+
+    assert_all(&client, "default", &["nonexistent", "inner/nonexistent"], false);
+    assert_all(&client, "both", &["inn", "index", "other/hello"], false);
+}
+
+#[test]
+fn test_redirection() {
+    let client = Client::debug(rocket()).expect("valid rocket");
+    let response = client.get("/redir/inner").dispatch();
+    assert_eq!(response.status(), Status::PermanentRedirect);
+    assert_eq!(response.headers().get_one("Location"), Some("/redir/inner/"));
+
+    let response = client.get("/redir/inner?foo=bar").dispatch();
+    assert_eq!(response.headers().get_one("Location"), Some("/redir/inner/?foo=bar"));
+
+    let response = client.get("/redir/inner/").dispatch();
+    assert_eq!(response.status(), Status::NotFound);
+
+    let response = client.get("/redir/other/hello.txt").dispatch();
+    assert_eq!(response.status(), Status::Ok);
+
+    let response = client.get("/both/inner").dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(response.into_bytes().unwrap(), contents("inner/index.html"));
+}
+
+#[test]
+fn test_headers() {
+    let client = Client::debug(rocket()).expect("valid rocket");
+    let response = client.get("/default/index.html").dispatch();
+    let etag = response.headers().get_one("ETag").unwrap().to_string();
+    assert_eq!(response.content_type(), Some(ContentType::HTML));
+    assert_eq!(response.headers().get_one("Accept-Ranges"), Some("bytes"));
+    assert_eq!(etag, STATIC.get("index.html").unwrap().etag());
+
+    // The tag is the length and 64-bit FNV-1a hash of the contents, so it's
+    // stable across builds and compilers.
+    let bytes = contents("index.html");
+    let hash = bytes.iter().fold(0xcbf29ce484222325u64, |hash, &byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    });
+
+    assert_eq!(etag, format!("\"{:x}-{:x}\"", bytes.len(), hash));
+
+    let response = client.get("/default/inner/goodbye").dispatch();
+    assert_eq!(response.content_type(), None);
+
+    let response = client.get("/default/index.html")
+        .header(Header::new("If-None-Match", etag))
+        .dispatch();
+
+    assert_eq!(response.status(), Status::NotModified);
+
+    let response = client.get("/default/index.html")
+        .header(Header::new("Range", "bytes=5-10"))
+        .dispatch();
+
+    assert_eq!(response.status(), Status::PartialContent);
+    assert_eq!(response.into_string().unwrap(), "a file");
+}
+
+#[test]
+fn test_listing() {
+    let client = Client::debug(rocket()).expect("valid rocket");
+    let html = client.get("/listing/").dispatch().into_string().unwrap();
+    assert!(html.contains(">index.html<"));
+    assert!(html.contains(">inner/<"));
+    assert!(html.contains(">other/<"));
+    assert!(!html.contains(".hidden"));
+    assert!(!html.contains("../"));
+
+    let response = client.get("/listing/inner/").header(Accept::JSON).dispatch();
+    assert_eq!(response.content_type(), Some(ContentType::JSON));
+
+    let json = response.into_string().unwrap();
+    assert!(json.contains(r#""name": "goodbye", "type": "file", "size": 19, "modified": null"#));
+    assert!(json.contains(r#""name": "index.html", "type": "file", "size": 13"#));
+    assert!(!json.contains(".hideme"));
+}
+
+#[test]
+fn test_precompressed() {
+    let client = Client::debug(rocket()).expect("valid rocket");
+    let read = |path: &str| std::fs::read(Path::new(relative!("tests/precompressed")).join(path));
+
+    let response = client.get("/gz/app.js")
+        .header(Header::new("Accept-Encoding", "gzip, br"))
+        .dispatch();
+
+    assert_eq!(response.headers().get_one("Content-Encoding"), Some("br"));
+    assert_eq!(response.headers().get_one("Vary"), Some("Accept-Encoding"));
+    assert_eq!(response.headers().get_one("Cache-Control"), Some("immutable"));
+    assert_eq!(response.content_type(), Some(ContentType::JavaScript));
+    assert!(response.headers().get_one("ETag").unwrap().ends_with("-br\""));
+    assert_eq!(response.into_bytes().unwrap(), read("app.js.br").unwrap());
+
+    let response = client.get("/gz/style.css")
+        .header(Header::new("Accept-Encoding", "br;q=0.5, gzip"))
+        .dispatch();
+
+    assert_eq!(response.headers().get_one("Content-Encoding"), Some("gzip"));
+    assert_eq!(response.headers().get_one("Cache-Control"), Some("no-cache"));
+    assert_eq!(response.content_type(), Some(ContentType::CSS));
+    assert_eq!(response.into_bytes().unwrap(), read("style.css.gz").unwrap());
+
+    let response = client.get("/gz/app.js").dispatch();
+    assert_eq!(response.headers().get_one("Content-Encoding"), None);
+    assert_eq!(response.into_bytes().unwrap(), read("app.js").unwrap());
+}
+
+#[test]
+fn test_ranking() {
+    for rank in -128..128 {
+        let a = EmbeddedFileServer::new(STATIC, Options::None).rank(rank);
+        let b = EmbeddedFileServer::from(STATIC).rank(rank);
+
+        for handler in vec![a, b] {
+            let routes: Vec<Route> = handler.into();
+            assert!(routes.iter().all(|route| route.rank == rank), "{}", rank);
+        }
+    }
+}
@@ -2,7 +2,7 @@ use std::{io::Read, fs::File};
 use std::path::Path;
 
 use rocket::{Rocket, Route, Build};
-use rocket::http::Status;
+use rocket::http::{Accept, ContentType, Header, RawStr, Status};
 use rocket::local::blocking::Client;
 use rocket::fs::{FileServer, Options, relative};
 
@@ -20,6 +20,14 @@ fn rocket() -> Rocket<Build> {
         .mount("/both", FileServer::new(&root, Options::DotFiles | Options::Index))
         .mount("/redir", FileServer::new(&root, Options::NormalizeDirs))
         .mount("/redir_index", FileServer::new(&root, Options::NormalizeDirs | Options::Index))
+        .mount("/listing", FileServer::new(&root, Options::Listing))
+        .mount("/listing_dots", FileServer::new(&root, Options::Listing | Options::DotFiles))
+        .mount("/listing_index", FileServer::new(&root, Options::Listing | Options::Index))
+        .mount("/cached", FileServer::from(&root)
+            .cache_control("inner/*.html", "private")
+            .cache_control("index.html", "no-cache")
+            .cache_control("/other/**", "immutable, max-age=31536000")
+            .default_cache_control("max-age=60"))
 }
 
 static REGULAR_FILES: &[&str] = &[
@@ -190,3 +198,139 @@ fn test_redirection() {
     assert_eq!(response.status(), Status::PermanentRedirect);
     assert_eq!(response.headers().get("Location").next(), Some("/redir_index/other/"));
 }
+
+fn link(href: &str, text: &str) -> String {
+    format!("<a href=\"{}\">{}</a>", RawStr::new(href).html_escape(), text)
+}
+
+#[test]
+fn test_listing() {
+    let client = Client::debug(rocket()).expect("valid rocket");
+
+    let response = client.get("/listing/").dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(response.content_type(), Some(ContentType::HTML));
+    let html = response.into_string().unwrap();
+    assert!(html.contains("<title>Index of &#x2F;listing&#x2F;</title>"));
+    assert!(html.contains(&link("/listing/inner/", "inner/")));
+    assert!(html.contains(&link("/listing/other/", "other/")));
+    let index = link("/listing/index.html", "index.html");
+    assert!(html.contains(&format!("{}</td><td>30B</td>", index)));
+    assert!(!html.contains(".hidden"));
+    assert!(!html.contains("../"));
+
+    // Directories are listed first, then files, each by name.
+    let inner = html.find("inner/").unwrap();
+    let other = html.find("other/").unwrap();
+    let index = html.find("index.html</a>").unwrap();
+    assert!(inner < other && other < index);
+
+    // The parent link stays within the mount with or without a trailing `/`.
+    for uri in ["/listing/inner", "/listing/inner/"] {
+        let html = client.get(uri).dispatch().into_string().unwrap();
+        assert!(html.contains(&link("/listing/", "../")), "{}", uri);
+        assert!(html.contains(&link("/listing/inner/goodbye", "goodbye")));
+    }
+
+    let html = client.get("/listing/inner").dispatch().into_string().unwrap();
+    assert!(!html.contains(".hideme"));
+
+    let html = client.get("/listing_dots/inner/").dispatch().into_string().unwrap();
+    assert!(html.contains(&link("/listing_dots/inner/.hideme", ".hideme")));
+
+    // Files are still served, and paths are still checked.
+    assert_all(&client, "listing", REGULAR_FILES, true);
+    assert_all(&client, "listing", HIDDEN_FILES, false);
+    let response = client.get("/listing/.hidden/").dispatch();
+    assert_eq!(response.status(), Status::NotFound);
+
+    // Listings never escape the root.
+    let html = client.get("/listing/inner/../../").dispatch().into_string().unwrap();
+    assert!(html.contains(">inner/<"));
+    assert!(!html.contains("static"));
+}
+
+#[test]
+fn test_listing_sorting() {
+    let client = Client::debug(rocket()).expect("valid rocket");
+
+    let html = client.get("/listing/inner/?sort=name&order=desc").dispatch()
+        .into_string().unwrap();
+
+    assert!(html.find(">index.html<").unwrap() < html.find(">goodbye<").unwrap());
+    assert!(html.contains(r#"<a href="?sort=name&amp;order=asc">name &#x25BC;</a>"#));
+    assert!(html.contains(r#"<a href="?sort=size&amp;order=asc">size</a>"#));
+
+    // `index.html` is smaller than `goodbye`.
+    let html = client.get("/listing/inner/?sort=size&order=desc").dispatch()
+        .into_string().unwrap();
+
+    assert!(html.find(">goodbye<").unwrap() < html.find(">index.html<").unwrap());
+    assert!(html.contains(r#"<a href="?sort=size&amp;order=asc">size &#x25BC;</a>"#));
+
+    let html = client.get("/listing/inner/?sort=size").dispatch().into_string().unwrap();
+    assert!(html.find(">index.html<").unwrap() < html.find(">goodbye<").unwrap());
+    assert!(html.contains(r#"<a href="?sort=size&amp;order=desc">size &#x25B2;</a>"#));
+}
+
+#[test]
+fn test_listing_json() {
+    let client = Client::debug(rocket()).expect("valid rocket");
+
+    let response = client.get("/listing/other/").header(Accept::JSON).dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(response.content_type(), Some(ContentType::JSON));
+
+    let json = response.into_string().unwrap();
+    assert!(json.starts_with("{\n  \"path\": \"/listing/other/\",\n  \"entries\": ["));
+    assert!(json.contains(r#"{ "name": "hello.txt", "type": "file", "size": 4, "modified": "#));
+}
+
+#[test]
+fn test_listing_with_index() {
+    let client = Client::debug(rocket()).expect("valid rocket");
+
+    // Directories with an `index.html` render it; others are listed.
+    assert_all(&client, "listing_index", INDEXED_DIRECTORIES, true);
+    let html = client.get("/listing_index/other/").dispatch().into_string().unwrap();
+    assert!(html.contains(&link("/listing_index/other/hello.txt", "hello.txt")));
+
+    // Without `Listing`, such directories aren't found.
+    let response = client.get("/index/other/").dispatch();
+    assert_eq!(response.status(), Status::NotFound);
+}
+
+#[test]
+fn test_cache_control() {
+    let client = Client::debug(rocket()).expect("valid rocket");
+    let cases = [
+        ("/cached/", Some("no-cache")),
+        ("/cached/index.html", Some("no-cache")),
+        ("/cached/inner/", Some("private")),
+        ("/cached/inner/index.html", Some("private")),
+        ("/cached/inner/goodbye", Some("max-age=60")),
+        ("/cached/other/hello.txt", Some("immutable, max-age=31536000")),
+        ("/default/index.html", None),
+    ];
+
+    for (path, cache_control) in cases {
+        let response = client.get(path).dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.headers().get_one("Cache-Control"), cache_control, "{}", path);
+    }
+
+    // Revalidation responses carry the header, too.
+    let response = client.get("/cached/index.html").dispatch();
+    let etag = response.headers().get_one("ETag").unwrap().to_string();
+    let response = client.get("/cached/index.html")
+        .header(Header::new("If-None-Match", etag))
+        .dispatch();
+
+    assert_eq!(response.status(), Status::NotModified);
+    assert_eq!(response.headers().get_one("Cache-Control"), Some("no-cache"));
+
+    // Redirects don't.
+    let response = client.get("/cached/inner").dispatch();
+    assert_eq!(response.status(), Status::PermanentRedirect);
+    assert_eq!(response.headers().get_one("Cache-Control"), None);
+}
@@ -0,0 +1,144 @@
+#![cfg(feature = "http2")]
+
+#[macro_use] extern crate rocket;
+
+mod common;
+
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::net::SocketAddr;
+
+use rocket::config::{Config, Http2};
+use rocket::data::ToByteUnit;
+use rocket::error::ErrorKind;
+use rocket::Shutdown;
+
+#[get("/")]
+fn index() -> &'static str {
+    "Hello, world!"
+}
+
+const PREFACE: &[u8] = b"PRI * HTTP/2.0\r\n\r\nSM\r\n\r\n";
+
+// An empty SETTINGS frame: 0 length, type 0x4, no flags, stream 0.
+const SETTINGS: &[u8] = &[0, 0, 0, 0x4, 0, 0, 0, 0, 0];
+
+const MAX_CONCURRENT_STREAMS: u16 = 0x3;
+const INITIAL_WINDOW_SIZE: u16 = 0x4;
+const MAX_FRAME_SIZE: u16 = 0x5;
+const MAX_HEADER_LIST_SIZE: u16 = 0x6;
+
+fn tuned() -> Http2 {
+    Http2 {
+        max_concurrent_streams: Some(7),
+        initial_stream_window_size: Some(128.kibibytes()),
+        max_frame_size: Some(32.kibibytes()),
+        max_header_list_size: Some(8.kibibytes()),
+        ..Default::default()
+    }
+}
+
+async fn launch(config: Config) -> (Shutdown, SocketAddr) {
+    common::launch_ephemeral(rocket::custom(config).mount("/", routes![index])).await
+}
+
+/// Sends the HTTP/2 client preface on `stream` and returns the parameters in
+/// the server's initial SETTINGS frame.
+fn server_settings<S: Read + Write>(mut stream: S) -> HashMap<u16, u32> {
+    stream.write_all(PREFACE).unwrap();
+    stream.write_all(SETTINGS).unwrap();
+    stream.flush().unwrap();
+
+    let mut header = [0; 9];
+    stream.read_exact(&mut header).unwrap();
+    assert_eq!(header[3], 0x4, "expected a SETTINGS frame");
+
+    let len = u32::from_be_bytes([0, header[0], header[1], header[2]]) as usize;
+    let mut payload = vec![0; len];
+    stream.read_exact(&mut payload).unwrap();
+    payload.chunks(6)
+        .map(|p| (u16::from_be_bytes([p[0], p[1]]), u32::from_be_bytes([p[2], p[3], p[4], p[5]])))
+        .collect()
+}
+
+fn assert_tuned(settings: &HashMap<u16, u32>) {
+    assert_eq!(settings.get(&MAX_CONCURRENT_STREAMS), Some(&7));
+    assert_eq!(settings.get(&INITIAL_WINDOW_SIZE), Some(&(128 * 1024)));
+    assert_eq!(settings.get(&MAX_FRAME_SIZE), Some(&(32 * 1024)));
+    assert_eq!(settings.get(&MAX_HEADER_LIST_SIZE), Some(&(8 * 1024)));
+}
+
+#[rocket::async_test]
+async fn h2c_prior_knowledge_is_tuned() {
+    let (shutdown, addr) = launch(Config { http2: tuned(), ..Config::debug_default() }).await;
+    let settings = rocket::tokio::task::spawn_blocking(move || {
+        server_settings(common::connect(addr))
+    }).await.unwrap();
+
+    assert_tuned(&settings);
+    shutdown.notify();
+}
+
+#[cfg(feature = "tls")]
+#[rocket::async_test]
+async fn alpn_h2_is_tuned() {
+    use std::io::Cursor;
+    use std::sync::Arc;
+
+    use rocket::config::TlsConfig;
+    use rocket::http::tls::rustls::{self, ClientConfig, ClientConnection, ServerName};
+    use rocket::http::tls::util::load_ca_certs;
+
+    const PRIVATE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../../examples/tls/private");
+
+    let tls = TlsConfig::from_paths(
+        format!("{}/rsa_sha256_cert.pem", PRIVATE),
+        format!("{}/rsa_sha256_key.pem", PRIVATE),
+    );
+
+    let config = Config { tls: Some(tls), http2: tuned(), ..Config::debug_default() };
+    let (shutdown, addr) = launch(config).await;
+    let settings = rocket::tokio::task::spawn_blocking(move || {
+        let ca = std::fs::read(format!("{}/ca_cert.pem", PRIVATE)).unwrap();
+        let mut config = ClientConfig::builder()
+            .with_safe_defaults()
+            .with_root_certificates(load_ca_certs(&mut Cursor::new(ca)).unwrap())
+            .with_no_client_auth();
+
+        config.alpn_protocols = vec![b"h2".to_vec()];
+        let name = ServerName::try_from("localhost").unwrap();
+        let mut conn = ClientConnection::new(Arc::new(config), name).unwrap();
+        let mut sock = common::connect(addr);
+        let settings = server_settings(rustls::Stream::new(&mut conn, &mut sock));
+        assert_eq!(conn.alpn_protocol(), Some(&b"h2"[..]));
+        settings
+    }).await.unwrap();
+
+    assert_tuned(&settings);
+    shutdown.notify();
+}
+
+#[rocket::async_test]
+async fn invalid_settings_fail_ignite() {
+    let invalid = [
+        Http2 { max_frame_size: Some(1.kibibytes()), ..Default::default() },
+        Http2 { max_frame_size: Some(16.mebibytes()), ..Default::default() },
+        Http2 { initial_stream_window_size: Some(2.gibibytes()), ..Default::default() },
+        Http2 {
+            adaptive_window: true,
+            initial_connection_window_size: Some(1.mebibytes()),
+            ..Default::default()
+        },
+        Http2 { keep_alive_timeout: 0, ..Default::default() },
+    ];
+
+    for http2 in invalid.iter().cloned() {
+        let config = Config { http2, ..Config::debug_default() };
+        let error = rocket::custom(config).ignite().await.unwrap_err();
+        assert!(matches!(error.kind(), ErrorKind::Config(e) if e.path.contains(&"http2".into())));
+    }
+
+    let valid = Http2 { max_frame_size: Some(16.kibibytes()), ..tuned() };
+    let config = Config { http2: valid, ..Config::debug_default() };
+    assert!(rocket::custom(config).ignite().await.is_ok());
+}
@@ -0,0 +1,104 @@
+#![cfg(feature = "json")]
+
+#[macro_use] extern crate rocket;
+
+use rocket::{Config, Build, Rocket};
+use rocket::data::{Limits, ToByteUnit};
+use rocket::futures::stream::{self, StreamExt};
+use rocket::http::{ContentType, Status};
+use rocket::local::blocking::Client;
+use rocket::response::stream::JsonLines;
+use rocket::serde::{Serialize, Deserialize, json::{Error, Lines}};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(crate = "rocket::serde")]
+struct Record {
+    id: usize,
+}
+
+#[get("/records")]
+fn records() -> JsonLines![Record] {
+    JsonLines(stream::iter(0..3).map(|id| Record { id }))
+}
+
+#[get("/generated")]
+fn generated() -> JsonLines![&'static str] {
+    JsonLines! {
+        yield "a";
+        yield "b\nc";
+    }
+}
+
+#[post("/sum", data = "<records>")]
+async fn sum(mut records: Lines<'_, Record>) -> String {
+    let mut sum = 0;
+    while let Some(record) = records.next().await {
+        match record {
+            Ok(record) => sum += record.id,
+            Err(Error::Parse(line, _)) => return format!("{} then parse error: {}", sum, line),
+            Err(Error::Io(e)) => return format!("{} then io error: {}", sum, e),
+        }
+    }
+
+    sum.to_string()
+}
+
+#[post("/echo", data = "<records>")]
+fn echo(records: Lines<'_, Record>) -> JsonLines![Record + '_] {
+    JsonLines(records.filter_map(|r| async move { r.ok() }))
+}
+
+fn rocket() -> Rocket<Build> {
+    let config = Config {
+        limits: Limits::default()
+            .limit("ndjson", 72.bytes())
+            .limit("ndjson/line", 16.bytes()),
+        ..Config::debug_default()
+    };
+
+    rocket::custom(config).mount("/", routes![records, generated, sum, echo])
+}
+
+#[test]
+fn json_lines_responder() {
+    let client = Client::debug(rocket()).unwrap();
+    let response = client.get("/records").dispatch();
+    assert_eq!(response.content_type(), Some(ContentType::NDJSON));
+    assert_eq!(response.into_string().unwrap(), "{\"id\":0}\n{\"id\":1}\n{\"id\":2}\n");
+
+    let response = client.get("/generated").dispatch();
+    assert_eq!(response.into_string().unwrap(), "\"a\"\n\"b\\nc\"\n");
+}
+
+#[test]
+fn lines_guard() {
+    let client = Client::debug(rocket()).unwrap();
+    let sum = |body: &str| client.post("/sum").body(body).dispatch().into_string().unwrap();
+
+    assert_eq!(sum(""), "0");
+    assert_eq!(sum("{\"id\":1}\n{\"id\":2}\n"), "3");
+    assert_eq!(sum("{\"id\":1}\r\n\n  \r\n{\"id\":2}"), "3");
+    assert_eq!(sum("{\"id\":1}\n{\"id\":\"two\"}\n{\"id\":3}"),
+        "1 then parse error: {\"id\":\"two\"}");
+    assert_eq!(sum("{\"id\":1}\n{ \"id\": 2          }\n"), "1 then io error: line limit exceeded");
+
+    let response = client.post("/sum").body(b"{\"id\":1}\n\xff\n").dispatch();
+    assert_eq!(response.into_string().unwrap(),
+        "1 then io error: invalid utf-8 sequence of 1 bytes from index 0");
+
+    let body = "{\"id\":1}\n".repeat(8);
+    assert_eq!(sum(&body), "8");
+    assert_eq!(sum(&(body + "{\"id\":1}\n")), "8 then io error: data limit exceeded");
+}
+
+#[test]
+fn lines_round_trip() {
+    let client = Client::debug(rocket()).unwrap();
+    let response = client.post("/echo")
+        .header(ContentType::NDJSON)
+        .body("{\"id\":4}\n{\"id\":5}\n")
+        .dispatch();
+
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(response.into_string().unwrap(), "{\"id\":4}\n{\"id\":5}\n");
+}
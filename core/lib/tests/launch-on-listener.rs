@@ -0,0 +1,74 @@
+#[macro_use] extern crate rocket;
+
+mod common;
+
+use std::io;
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use rocket::listener::{Listener, TcpListener};
+use rocket::tokio::io::{duplex, DuplexStream};
+use rocket::tokio::sync::mpsc;
+
+/// A listener that yields in-memory streams sent through a channel.
+struct ChannelListener(mpsc::UnboundedReceiver<DuplexStream>);
+
+impl Listener for ChannelListener {
+    type Connection = DuplexStream;
+
+    fn local_addr(&self) -> Option<SocketAddr> {
+        None
+    }
+
+    fn poll_accept(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>
+    ) -> Poll<io::Result<Self::Connection>> {
+        self.0.poll_recv(cx).map(|stream| stream.ok_or_else(|| io::ErrorKind::BrokenPipe.into()))
+    }
+}
+
+#[get("/")]
+fn index(remote: Option<SocketAddr>) -> String {
+    format!("remote: {:?}", remote)
+}
+
+#[rocket::async_test]
+async fn launch_on_in_memory_listener() {
+    let (conn_tx, conn_rx) = mpsc::unbounded_channel();
+    let (rocket, liftoff) = common::notify_liftoff(rocket::build().mount("/", routes![index]));
+    let server = rocket::tokio::spawn(rocket.launch_on(ChannelListener(conn_rx)));
+    let liftoff = liftoff.await.expect("liftoff fairing ran");
+    assert!(liftoff.addresses.is_empty());
+
+    for _ in 0..2 {
+        let (client, server_end) = duplex(4096);
+        conn_tx.send(server_end).unwrap();
+        let response = common::exchange(client, common::get_request("localhost", "/").as_bytes()).await;
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+        assert!(response.ends_with("\r\n\r\nremote: None"));
+    }
+
+    liftoff.shutdown.notify();
+    assert!(server.await.unwrap().is_ok());
+}
+
+#[rocket::async_test]
+async fn launch_on_prebound_tcp_listener() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    let (rocket, liftoff) = common::notify_liftoff(rocket::build().mount("/", routes![index]));
+    let server = rocket::tokio::spawn(rocket.launch_on(listener));
+    let liftoff = liftoff.await.unwrap();
+    assert_eq!(SocketAddr::new(liftoff.config.address, liftoff.config.port), addr);
+    assert_eq!(liftoff.addresses, [addr]);
+
+    let response = common::request(addr, &common::get_request("localhost", "/")).await;
+    assert!(response.starts_with("HTTP/1.1 200 OK"));
+    assert!(response.contains("remote: Some(127.0.0.1:"));
+
+    liftoff.shutdown.notify();
+    assert!(server.await.unwrap().is_ok());
+}
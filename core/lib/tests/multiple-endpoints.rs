@@ -0,0 +1,207 @@
+#[macro_use] extern crate rocket;
+
+mod common;
+
+use std::net::{Ipv4Addr, SocketAddr};
+
+use rocket::config::{Config, Endpoint};
+use rocket::{Rocket, Build, Shutdown};
+
+#[get("/")]
+fn index() -> &'static str {
+    "Hello, world!"
+}
+
+async fn get(addr: SocketAddr, host: &str, uri: &str) -> String {
+    common::request(addr, &common::get_request(host, uri)).await
+}
+
+async fn launch(rocket: Rocket<Build>) -> (Shutdown, Vec<SocketAddr>) {
+    let liftoff = common::launch(rocket.mount("/", routes![index])).await;
+    (liftoff.shutdown, liftoff.addresses)
+}
+
+#[rocket::async_test]
+async fn serves_on_every_endpoint() {
+    let (shutdown, addresses) = launch(rocket::custom(Config {
+        endpoints: vec![
+            Endpoint::new(Ipv4Addr::LOCALHOST, 0),
+            Endpoint::new(Ipv4Addr::LOCALHOST, 0),
+        ],
+        ..Config::debug_default()
+    })).await;
+
+    assert_eq!(addresses.len(), 2);
+    assert_ne!(addresses[0], addresses[1]);
+    for addr in addresses {
+        let response = get(addr, "localhost", "/").await;
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+        assert!(response.ends_with("\r\n\r\nHello, world!"));
+    }
+
+    shutdown.notify();
+}
+
+#[rocket::async_test]
+async fn single_address_is_reported() {
+    let (shutdown, addresses) = launch(rocket::custom(Config {
+        port: 0,
+        ..Config::debug_default()
+    })).await;
+
+    assert_eq!(addresses.len(), 1);
+    assert!(get(addresses[0], "localhost", "/").await.starts_with("HTTP/1.1 200 OK"));
+    shutdown.notify();
+}
+
+#[rocket::async_test]
+async fn https_redirect_requires_tls_endpoint() {
+    let rocket = rocket::custom(Config {
+        endpoints: vec![Endpoint::new(Ipv4Addr::LOCALHOST, 0).with_https_redirect(true)],
+        ..Config::debug_default()
+    });
+
+    let error = rocket.launch().await.unwrap_err();
+    assert!(matches!(error.kind(), rocket::error::ErrorKind::Io(_)));
+}
+
+#[cfg(feature = "tls")]
+fn tls_config() -> rocket::config::TlsConfig {
+    let private = concat!(env!("CARGO_MANIFEST_DIR"), "/../../examples/tls/private");
+    rocket::config::TlsConfig::from_paths(
+        format!("{}/rsa_sha256_cert.pem", private),
+        format!("{}/rsa_sha256_key.pem", private),
+    )
+}
+
+#[cfg(feature = "tls")]
+#[rocket::async_test]
+async fn redirects_plaintext_to_https() {
+    let tls = tls_config();
+    let (shutdown, addresses) = launch(rocket::custom(Config {
+        endpoints: vec![
+            Endpoint::new(Ipv4Addr::LOCALHOST, 0).with_https_redirect(true),
+            Endpoint::new(Ipv4Addr::LOCALHOST, 0).with_tls(tls),
+        ],
+        ..Config::debug_default()
+    })).await;
+
+    let https_port = addresses[1].port();
+    let response = get(addresses[0], "example.com:8000", "/a/b?c=d").await;
+    assert!(response.starts_with("HTTP/1.1 308 Permanent Redirect"), "{}", response);
+    let location = format!("location: https://example.com:{}/a/b?c=d\r\n", https_port);
+    assert!(response.to_lowercase().contains(&location), "{}", response);
+
+    let response = get(addresses[0], "[::1]", "/").await;
+    let location = format!("location: https://[::1]:{}/\r\n", https_port);
+    assert!(response.to_lowercase().contains(&location), "{}", response);
+
+    shutdown.notify();
+}
+
+#[cfg(feature = "tls")]
+#[rocket::async_test]
+async fn https_redirect_runs_connection_fairings() {
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use rocket::fairing::AdHoc;
+
+    let connections = Arc::new(AtomicUsize::new(0));
+    let counter = connections.clone();
+    let rocket = rocket::custom(Config {
+        endpoints: vec![
+            Endpoint::new(Ipv4Addr::LOCALHOST, 0).with_https_redirect(true),
+            Endpoint::new(Ipv4Addr::LOCALHOST, 0).with_tls(tls_config()),
+        ],
+        ..Config::debug_default()
+    }).attach(AdHoc::on_connect("Count", move |_| {
+        counter.fetch_add(1, Ordering::SeqCst);
+        Box::pin(async { Ok(()) })
+    }));
+
+    let (shutdown, addresses) = launch(rocket).await;
+    let response = get(addresses[0], "localhost", "/").await;
+    assert!(response.starts_with("HTTP/1.1 308 Permanent Redirect"), "{}", response);
+    assert_eq!(connections.load(Ordering::SeqCst), 1);
+    shutdown.notify();
+}
+
+#[cfg(feature = "tls")]
+#[rocket::async_test]
+async fn https_redirect_on_tls_endpoint_is_rejected() {
+    let rocket = rocket::custom(Config {
+        endpoints: vec![
+            Endpoint::new(Ipv4Addr::LOCALHOST, 0).with_tls(tls_config()).with_https_redirect(true),
+        ],
+        ..Config::debug_default()
+    });
+
+    let error = rocket.ignite().await.unwrap_err();
+    assert!(matches!(error.kind(), rocket::error::ErrorKind::Config(e) if e.path == ["endpoints"]));
+}
+
+#[cfg(feature = "tls")]
+#[rocket::async_test]
+async fn https_redirect_shares_connection_limit_and_ids() {
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    use rocket::fairing::AdHoc;
+    use rocket::tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use rocket::tokio::net::TcpStream;
+
+    let ids = Arc::new(Mutex::new(vec![]));
+    let seen = ids.clone();
+    let rocket = rocket::custom(Config {
+        endpoints: vec![
+            Endpoint::new(Ipv4Addr::LOCALHOST, 0).with_https_redirect(true),
+            Endpoint::new(Ipv4Addr::LOCALHOST, 0),
+            Endpoint::new(Ipv4Addr::LOCALHOST, 0).with_tls(tls_config()),
+        ],
+        max_connections: 1,
+        ..Config::debug_default()
+    }).attach(AdHoc::on_connect("Record IDs", move |info| {
+        seen.lock().unwrap().push(info.id());
+        Box::pin(async { Ok(()) })
+    }));
+
+    let (shutdown, addresses) = launch(rocket).await;
+    let (redirect, plain) = (addresses[0], addresses[1]);
+
+    // Hold a connection to the redirect listener open: it counts toward the
+    // limit shared with every other listener.
+    let mut held = TcpStream::connect(redirect).await.unwrap();
+    held.write_all(b"GET / HTTP/1.1\r\nHost: localhost\r\n\r\n").await.unwrap();
+    let mut response = vec![];
+    while !response.ends_with(b"\r\n\r\n") {
+        let mut buf = [0; 1024];
+        let n = held.read(&mut buf).await.unwrap();
+        assert_ne!(n, 0, "connection closed");
+        response.extend_from_slice(&buf[..n]);
+    }
+
+    let response = get(plain, "localhost", "/").await;
+    assert!(response.starts_with("HTTP/1.1 503 Service Unavailable"), "{}", response);
+
+    drop(held);
+    let mut served = false;
+    for _ in 0..50 {
+        if get(plain, "localhost", "/").await.starts_with("HTTP/1.1 200 OK") {
+            served = true;
+            break;
+        }
+
+        rocket::tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+
+    assert!(served, "connection was not released");
+
+    let mut ids = ids.lock().unwrap().clone();
+    let count = ids.len();
+    ids.sort();
+    ids.dedup();
+    assert!(count >= 2);
+    assert_eq!(ids.len(), count, "connection IDs collide");
+    shutdown.notify();
+}
@@ -0,0 +1,172 @@
+#![cfg(all(feature = "json", feature = "msgpack"))]
+
+#[macro_use] extern crate rocket;
+
+use rocket::http::{ContentType, Header, Status};
+use rocket::local::blocking::{Client, LocalResponse};
+use rocket::serde::{Serialize, Deserialize, Negotiated, NegotiatedError};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(crate = "rocket::serde")]
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+#[get("/point")]
+fn point() -> Negotiated<Point> {
+    Negotiated(Point { x: 1, y: -2 })
+}
+
+#[post("/point", data = "<point>")]
+fn echo(point: Negotiated<Point>) -> Negotiated<Point> {
+    point
+}
+
+#[post("/error", data = "<point>")]
+fn error(point: Result<Negotiated<Point>, NegotiatedError<'_>>) -> &'static str {
+    match point {
+        Ok(_) => "ok",
+        Err(NegotiatedError::Unsupported(None)) => "missing",
+        Err(NegotiatedError::Unsupported(Some(_))) => "unsupported",
+        Err(NegotiatedError::Json(_)) => "json",
+        Err(NegotiatedError::MsgPack(_)) => "msgpack",
+        #[cfg(feature = "cbor")]
+        Err(NegotiatedError::Cbor(_)) => "cbor",
+    }
+}
+
+fn client() -> Client {
+    Client::debug_with(routes![point, echo, error]).unwrap()
+}
+
+fn get<'c>(client: &'c Client, accept: Option<&'static str>) -> LocalResponse<'c> {
+    let mut request = client.get("/point");
+    if let Some(accept) = accept {
+        request.add_header(Header::new("Accept", accept));
+    }
+
+    request.dispatch()
+}
+
+#[test]
+fn negotiates_response_format() {
+    let client = client();
+    let cases = [
+        (None, ContentType::JSON),
+        (Some("*/*"), ContentType::JSON),
+        (Some("application/*"), ContentType::JSON),
+        (Some("application/json"), ContentType::JSON),
+        (Some("application/msgpack"), ContentType::MsgPack),
+        (Some("text/html, application/msgpack;q=0.1"), ContentType::MsgPack),
+        (Some("application/json;q=0.5, application/msgpack"), ContentType::MsgPack),
+        (Some("application/msgpack;q=0.5, application/json;q=0.5"), ContentType::JSON),
+        (Some("application/json;q=0, */*"), ContentType::MsgPack),
+        (Some("application/*;q=0.2, application/json;q=0.1"), ContentType::MsgPack),
+        (Some("not a valid accept header"), ContentType::JSON),
+    ];
+
+    for (accept, content_type) in cases {
+        let response = get(&client, accept);
+        assert_eq!(response.status(), Status::Ok, "{:?}", accept);
+        assert_eq!(response.content_type(), Some(content_type.clone()), "{:?}", accept);
+        assert_eq!(response.headers().get_one("Vary"), Some("Accept"));
+
+        let expected = Some(Point { x: 1, y: -2 });
+        if content_type == ContentType::JSON {
+            assert_eq!(response.into_json::<Point>(), expected);
+        } else {
+            assert_eq!(response.into_msgpack::<Point>(), expected);
+        }
+    }
+}
+
+#[test]
+fn responds_not_acceptable() {
+    let client = client();
+    let cases = [
+        "text/html",
+        "text/*, image/png",
+        "application/json;q=0, application/msgpack;q=0",
+    ];
+
+    for accept in cases {
+        let response = get(&client, Some(accept));
+        assert_eq!(response.status(), Status::NotAcceptable, "{}", accept);
+    }
+}
+
+#[test]
+fn accepts_enabled_formats() {
+    let client = client();
+    let point = Point { x: 7, y: 3 };
+
+    let response = client.post("/point").json(&point).dispatch();
+    assert_eq!(response.into_json::<Point>(), Some(Point { x: 7, y: 3 }));
+
+    let response = client.post("/point")
+        .msgpack(&point)
+        .header(Header::new("Accept", "application/msgpack"))
+        .dispatch();
+
+    assert_eq!(response.into_msgpack::<Point>(), Some(Point { x: 7, y: 3 }));
+
+    let response = client.post("/point").body(r#"{ "x": 1, "y": 2 }"#).dispatch();
+    assert_eq!(response.status(), Status::UnsupportedMediaType);
+
+    let response = client.post("/point")
+        .header(ContentType::Plain)
+        .body(r#"{ "x": 1, "y": 2 }"#)
+        .dispatch();
+
+    assert_eq!(response.status(), Status::UnsupportedMediaType);
+
+    let response = client.post("/point")
+        .header(ContentType::JSON)
+        .body(r#"{ "x": "one" }"#)
+        .dispatch();
+
+    assert_eq!(response.status(), Status::UnprocessableEntity);
+}
+
+#[test]
+fn reports_errors() {
+    let client = client();
+    let cases = [
+        (None, "missing"),
+        (Some(ContentType::Plain), "unsupported"),
+        (Some(ContentType::JSON), "json"),
+        (Some(ContentType::MsgPack), "msgpack"),
+    ];
+
+    for (content_type, expected) in cases {
+        let mut request = client.post("/error").body("{");
+        if let Some(content_type) = content_type {
+            request.add_header(content_type);
+        }
+
+        assert_eq!(request.dispatch().into_string().unwrap(), expected);
+    }
+}
+
+#[cfg(feature = "cbor")]
+#[test]
+fn negotiates_cbor() {
+    let client = client();
+    let response = get(&client, Some("application/cbor"));
+    assert_eq!(response.content_type(), Some(ContentType::CBOR));
+    assert_eq!(response.into_cbor::<Point>(), Some(Point { x: 1, y: -2 }));
+
+    let response = get(&client, Some("application/msgpack;q=0.5, application/cbor"));
+    assert_eq!(response.content_type(), Some(ContentType::CBOR));
+
+    let response = client.post("/point")
+        .cbor(&Point { x: 7, y: 3 })
+        .header(Header::new("Accept", "application/cbor"))
+        .dispatch();
+
+    assert_eq!(response.into_cbor::<Point>(), Some(Point { x: 7, y: 3 }));
+
+    let response = client.post("/error").header(ContentType::CBOR).body("{").dispatch();
+    assert_eq!(response.into_string().unwrap(), "cbor");
+}
@@ -0,0 +1,110 @@
+use std::path::Path;
+
+use rocket::fs::{relative, FileServer, Options};
+use rocket::http::{ContentType, Header, Status};
+use rocket::local::blocking::{Client, LocalResponse};
+
+fn root() -> &'static Path {
+    Path::new(relative!("tests/precompressed"))
+}
+
+fn client() -> Client {
+    let rocket = rocket::build()
+        .mount("/on", FileServer::new(root(), Options::default() | Options::Precompressed))
+        .mount("/off", FileServer::from(root()));
+
+    Client::debug(rocket).unwrap()
+}
+
+fn get<'c>(client: &'c Client, uri: &'static str, accept: &'static str) -> LocalResponse<'c> {
+    let response = client.get(uri)
+        .header(Header::new("Accept-Encoding", accept))
+        .dispatch();
+
+    assert_eq!(response.status(), Status::Ok);
+    response
+}
+
+fn contents(path: &str) -> Vec<u8> {
+    std::fs::read(root().join(path)).unwrap()
+}
+
+fn encoding<'a>(response: &'a LocalResponse<'_>) -> Option<&'a str> {
+    response.headers().get_one("Content-Encoding")
+}
+
+#[test]
+fn serves_preferred_variant() {
+    let client = client();
+    let cases = [
+        ("br", Some("br"), "app.js.br"),
+        ("gzip", Some("gzip"), "app.js.gz"),
+        ("x-gzip", Some("gzip"), "app.js.gz"),
+        ("gzip, br", Some("br"), "app.js.br"),
+        ("gzip;q=1, br;q=0.5", Some("gzip"), "app.js.gz"),
+        ("*", Some("br"), "app.js.br"),
+        ("br;q=0, *", Some("gzip"), "app.js.gz"),
+        ("deflate, zstd", None, "app.js"),
+        ("gzip;q=0", None, "app.js"),
+        ("", None, "app.js"),
+    ];
+
+    for (accept, expected, file) in cases {
+        let response = get(&client, "/on/app.js", accept);
+        assert_eq!(encoding(&response), expected, "{}", accept);
+        assert_eq!(response.content_type(), Some(ContentType::JavaScript));
+        assert_eq!(response.headers().get_one("Vary"), Some("Accept-Encoding"));
+        assert_eq!(response.into_bytes().unwrap(), contents(file));
+    }
+}
+
+#[test]
+fn falls_back_to_available_variants() {
+    let client = client();
+    let response = get(&client, "/on/style.css", "br, gzip");
+    assert_eq!(encoding(&response), Some("gzip"));
+    assert_eq!(response.content_type(), Some(ContentType::CSS));
+    assert_eq!(response.into_bytes().unwrap(), contents("style.css.gz"));
+
+    let response = get(&client, "/on/style.css", "br");
+    assert_eq!(encoding(&response), None);
+    assert_eq!(response.into_bytes().unwrap(), contents("style.css"));
+
+    let response = get(&client, "/on/plain/readme.txt", "br, gzip");
+    assert_eq!(encoding(&response), None);
+    assert_eq!(response.content_type(), Some(ContentType::Text));
+    assert_eq!(response.into_bytes().unwrap(), contents("plain/readme.txt"));
+}
+
+#[test]
+fn variants_can_be_requested_directly() {
+    let client = client();
+    let response = get(&client, "/on/app.js.gz", "gzip");
+    assert_eq!(encoding(&response), None);
+    assert_eq!(response.into_bytes().unwrap(), contents("app.js.gz"));
+}
+
+#[test]
+fn variants_are_ignored_by_default() {
+    let client = client();
+    let response = get(&client, "/off/app.js", "br, gzip");
+    assert_eq!(encoding(&response), None);
+    assert_eq!(response.headers().get_one("Vary"), None);
+    assert_eq!(response.into_bytes().unwrap(), contents("app.js"));
+}
+
+#[test]
+fn variants_have_their_own_validators() {
+    let client = client();
+    let plain = get(&client, "/on/app.js", "").headers().get_one("ETag").unwrap().to_string();
+    let br = get(&client, "/on/app.js", "br").headers().get_one("ETag").unwrap().to_string();
+    assert_ne!(plain, br);
+    assert!(br.ends_with("-br\""));
+
+    let response = client.get("/on/app.js")
+        .header(Header::new("Accept-Encoding", "br"))
+        .header(Header::new("If-None-Match", br))
+        .dispatch();
+
+    assert_eq!(response.status(), Status::NotModified);
+}
@@ -0,0 +1,145 @@
+#[macro_use] extern crate rocket;
+
+use rocket::{Config, Build, Rocket};
+use rocket::form::Form;
+use rocket::http::{Accept, ContentType, Header, Status};
+use rocket::local::blocking::{Client, LocalResponse};
+use rocket::response::Problem;
+
+#[derive(FromForm)]
+struct Signup<'r> {
+    #[field(validate = len(3..))]
+    name: &'r str,
+    age: u8,
+}
+
+#[post("/signup", data = "<form>")]
+fn signup(form: Form<Signup<'_>>) -> String {
+    format!("{} ({})", form.name, form.age)
+}
+
+#[get("/credit")]
+fn credit() -> Result<&'static str, Problem> {
+    Err(Problem::new(Status::Forbidden)
+        .with_type("https://example.com/probs/out-of-credit")
+        .with_title("You do not have enough credit.")
+        .with_detail("Your current balance is 30, but that costs 50.")
+        .with_instance("/account/12345/msgs/abc")
+        .with_extension("balance", 30)
+        .with_extension("accounts", ["/account/12345", "/account/67890"]))
+}
+
+fn rocket(problem_details: bool) -> Rocket<Build> {
+    let config = Config { problem_details, ..Config::debug_default() };
+    rocket::custom(config).mount("/", routes![signup, credit])
+}
+
+fn assert_problem(response: &LocalResponse<'_>, status: Status) {
+    assert_eq!(response.status(), status);
+    assert_eq!(response.content_type(), Some(ContentType::new("application", "problem+json")));
+}
+
+#[test]
+fn problem_responder() {
+    let client = Client::debug(rocket(false)).unwrap();
+    let response = client.get("/credit").dispatch();
+    assert_problem(&response, Status::Forbidden);
+    assert_eq!(response.into_string().unwrap(), concat!(
+        r#"{"type":"https://example.com/probs/out-of-credit","#,
+        r#""title":"You do not have enough credit.","status":403,"#,
+        r#""detail":"Your current balance is 30, but that costs 50.","#,
+        r#""instance":"/account/12345/msgs/abc","balance":30,"#,
+        r#""accounts":["/account/12345","/account/67890"]}"#,
+    ));
+}
+
+#[test]
+fn default_catcher_emits_problems() {
+    let client = Client::debug(rocket(true)).unwrap();
+    let response = client.get("/missing")
+        .header(Accept::JSON)
+        .header(Header::new("X-Request-ID", "req-1"))
+        .dispatch();
+
+    assert_problem(&response, Status::NotFound);
+    assert_eq!(response.into_string().unwrap(), concat!(
+        r#"{"title":"Not Found","status":404,"#,
+        r#""detail":"The requested resource could not be found.","request_id":"req-1"}"#,
+    ));
+
+    let problem_json = Header::new("Accept", "application/problem+json");
+    let response = client.get("/missing").header(problem_json).dispatch();
+    assert_problem(&response, Status::NotFound);
+
+    let response = client.get("/missing").header(Accept::HTML).dispatch();
+    assert_eq!(response.content_type(), Some(ContentType::HTML));
+
+    let client = Client::debug(rocket(false)).unwrap();
+    let response = client.get("/missing").header(Accept::JSON).dispatch();
+    assert_eq!(response.content_type(), Some(ContentType::JSON));
+}
+
+#[test]
+fn form_errors_are_extensions() {
+    let client = Client::debug(rocket(true)).unwrap();
+    let response = client.post("/signup")
+        .header(ContentType::Form)
+        .header(Accept::JSON)
+        .header(Header::new("X-Request-ID", "req-2"))
+        .body("name=ab&age=many")
+        .dispatch();
+
+    assert_problem(&response, Status::UnprocessableEntity);
+    assert_eq!(response.into_string().unwrap(), concat!(
+        r#"{"title":"Unprocessable Entity","status":422,"#,
+        r#""detail":"The request's form data is invalid.","errors":["#,
+        r#"{"message":"invalid integer: invalid digit found in string","#,
+        r#""name":"age","value":"many"},"#,
+        r#"{"message":"expected at least 3","name":"name"}],"request_id":"req-2"}"#,
+    ));
+
+    let response = client.post("/signup")
+        .header(ContentType::Form)
+        .header(Accept::JSON)
+        .body("name=bob&age=30")
+        .dispatch();
+
+    assert_eq!(response.into_string().unwrap(), "bob (30)");
+}
+
+#[cfg(feature = "json")]
+#[derive(rocket::serde::Deserialize)]
+#[serde(crate = "rocket::serde")]
+struct Point {
+    x: i32,
+}
+
+#[cfg(feature = "json")]
+#[post("/point", data = "<point>")]
+fn point(point: rocket::serde::json::Json<Point>) -> String {
+    point.x.to_string()
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn json_errors_are_extensions() {
+    let client = Client::debug(rocket(true).mount("/", routes![point])).unwrap();
+    let response = client.post("/point")
+        .header(ContentType::JSON)
+        .header(Accept::JSON)
+        .body("{\n  \"x\": \"one\"\n}")
+        .dispatch();
+
+    assert_problem(&response, Status::UnprocessableEntity);
+    let json = response.into_string().unwrap();
+    assert!(json.starts_with(r#"{"title":"Unprocessable Entity","status":422,"detail":"#));
+    assert!(json.contains(r#""line":2,"column":12,"request_id":"#));
+
+    let response = client.post("/point")
+        .header(ContentType::JSON)
+        .header(Accept::JSON)
+        .body("{ x")
+        .dispatch();
+
+    assert_problem(&response, Status::BadRequest);
+}
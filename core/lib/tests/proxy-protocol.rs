@@ -0,0 +1,175 @@
+#[macro_use] extern crate rocket;
+
+mod common;
+
+use std::net::{IpAddr, SocketAddr};
+
+use rocket::config::{Config, ProxyProtocol};
+use rocket::listener::ProxyHeader;
+use rocket::tokio::net::TcpStream;
+use rocket::Shutdown;
+
+#[get("/")]
+fn remote(remote: SocketAddr) -> String {
+    remote.to_string()
+}
+
+#[get("/client")]
+fn client(ip: IpAddr) -> String {
+    ip.to_string()
+}
+
+#[get("/authority")]
+fn authority(header: &ProxyHeader) -> String {
+    format!("v{} {}", header.version(), header.authority().unwrap_or("none"))
+}
+
+async fn launch(trusted: &str) -> (Shutdown, SocketAddr) {
+    launch_with(trusted, Config::debug_default()).await
+}
+
+async fn launch_with(trusted: &str, config: Config) -> (Shutdown, SocketAddr) {
+    let proxy = ProxyProtocol::trust([trusted.parse().unwrap()]);
+    let rocket = rocket::custom(Config { proxy_protocol: Some(proxy), ..config })
+        .mount("/", routes![remote, client, authority]);
+
+    common::launch_ephemeral(rocket).await
+}
+
+async fn get(addr: SocketAddr, header: &[u8], uri: &str) -> String {
+    let stream = TcpStream::connect(addr).await.unwrap();
+    let request = common::get_request("localhost", uri);
+    common::exchange(stream, &[header, request.as_bytes()].concat()).await
+}
+
+fn v2_header(authority: &str) -> Vec<u8> {
+    let mut header = b"\r\n\r\n\0\r\nQUIT\n".to_vec();
+    header.extend_from_slice(&[0x21, 0x11]);
+    header.extend_from_slice(&(12 + 3 + authority.len() as u16).to_be_bytes());
+    header.extend_from_slice(&[192, 0, 2, 7, 10, 0, 0, 1]);
+    header.extend_from_slice(&4242u16.to_be_bytes());
+    header.extend_from_slice(&443u16.to_be_bytes());
+    header.push(ProxyHeader::AUTHORITY);
+    header.extend_from_slice(&(authority.len() as u16).to_be_bytes());
+    header.extend_from_slice(authority.as_bytes());
+    header
+}
+
+#[rocket::async_test]
+async fn v1_header_sets_remote() {
+    let (shutdown, addr) = launch("127.0.0.0/8").await;
+
+    let header = b"PROXY TCP4 192.0.2.1 10.0.0.1 56324 443\r\n";
+    let response = get(addr, header, "/").await;
+    assert!(response.ends_with("\r\n\r\n192.0.2.1:56324"), "{}", response);
+
+    let response = get(addr, header, "/client").await;
+    assert!(response.ends_with("\r\n\r\n192.0.2.1"), "{}", response);
+
+    let response = get(addr, header, "/authority").await;
+    assert!(response.ends_with("\r\n\r\nv1 none"), "{}", response);
+
+    let header = b"PROXY TCP6 2001:db8::1 2001:db8::2 8080 443\r\n";
+    let response = get(addr, header, "/").await;
+    assert!(response.ends_with("\r\n\r\n[2001:db8::1]:8080"), "{}", response);
+
+    shutdown.notify();
+}
+
+#[rocket::async_test]
+async fn v2_header_sets_remote_and_tlvs() {
+    let (shutdown, addr) = launch("127.0.0.1/32").await;
+
+    let header = v2_header("example.com");
+    let response = get(addr, &header, "/").await;
+    assert!(response.ends_with("\r\n\r\n192.0.2.7:4242"), "{}", response);
+
+    let response = get(addr, &header, "/authority").await;
+    assert!(response.ends_with("\r\n\r\nv2 example.com"), "{}", response);
+
+    shutdown.notify();
+}
+
+#[rocket::async_test]
+async fn trusted_peer_without_header_is_rejected() {
+    let (shutdown, addr) = launch("127.0.0.0/8").await;
+
+    let response = get(addr, b"", "/").await;
+    assert!(response.is_empty(), "{}", response);
+
+    let response = get(addr, b"PROXY NONSENSE\r\n", "/").await;
+    assert!(response.is_empty(), "{}", response);
+
+    shutdown.notify();
+}
+
+#[rocket::async_test]
+async fn untrusted_peer_is_served_directly() {
+    let (shutdown, addr) = launch("10.0.0.0/8").await;
+
+    let response = get(addr, b"", "/client").await;
+    assert!(response.ends_with("\r\n\r\n127.0.0.1"), "{}", response);
+
+    let response = get(addr, b"", "/authority").await;
+    assert!(response.starts_with("HTTP/1.1 404"), "{}", response);
+
+    shutdown.notify();
+}
+
+#[rocket::async_test]
+async fn header_timeout_closes_silent_connections() {
+    let config = Config { header_timeout: 1, ..Config::debug_default() };
+    let (shutdown, addr) = launch_with("127.0.0.0/8", config).await;
+
+    // Never send the header: the server must hang up rather than wait.
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+    assert_eq!(common::read_to_end(&mut stream).await, "");
+
+    shutdown.notify();
+}
+
+#[cfg(feature = "tls")]
+#[rocket::async_test]
+async fn v2_header_precedes_tls_handshake() {
+    use std::io::{Cursor, Write};
+    use std::sync::Arc;
+
+    use rocket::config::TlsConfig;
+    use rocket::http::tls::rustls::{self, ClientConfig, ClientConnection, ServerName};
+    use rocket::http::tls::util::load_ca_certs;
+
+    const PRIVATE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../../examples/tls/private");
+
+    let tls = TlsConfig::from_paths(
+        format!("{}/rsa_sha256_cert.pem", PRIVATE),
+        format!("{}/rsa_sha256_key.pem", PRIVATE),
+    );
+
+    let config = Config { tls: Some(tls), ..Config::debug_default() };
+    let (shutdown, addr) = launch_with("127.0.0.0/8", config).await;
+
+    let request = move |uri: &'static str| rocket::tokio::task::spawn_blocking(move || {
+        let ca = std::fs::read(format!("{}/ca_cert.pem", PRIVATE)).unwrap();
+        let config = ClientConfig::builder()
+            .with_safe_defaults()
+            .with_root_certificates(load_ca_certs(&mut Cursor::new(ca)).unwrap())
+            .with_no_client_auth();
+
+        let name = ServerName::try_from("localhost").unwrap();
+        let mut conn = ClientConnection::new(Arc::new(config), name).unwrap();
+        let mut sock = common::connect(addr);
+
+        // The PROXY header is sent in the clear, before the handshake.
+        sock.write_all(&v2_header("example.com")).unwrap();
+        let stream = rustls::Stream::new(&mut conn, &mut sock);
+        common::exchange_blocking(stream, common::get_request("localhost", uri).as_bytes())
+    });
+
+    let response = request("/").await.unwrap();
+    assert!(response.ends_with("\r\n\r\n192.0.2.7:4242"), "{}", response);
+
+    let response = request("/authority").await.unwrap();
+    assert!(response.ends_with("\r\n\r\nv2 example.com"), "{}", response);
+
+    shutdown.notify();
+}
@@ -0,0 +1,191 @@
+#[macro_use] extern crate rocket;
+
+use std::io::Cursor;
+
+use rocket::fs::{relative, FileServer};
+use rocket::http::{ContentType, Header, Status};
+use rocket::local::blocking::{Client, LocalResponse};
+use rocket::response::Ranged;
+
+const DIGITS: &str = "0123456789abcdefghijklmnopqrstuvwxyz";
+
+#[derive(Responder)]
+struct Tagged(Ranged<Cursor<&'static str>>, Header<'static>, Header<'static>);
+
+#[get("/digits")]
+fn digits() -> (ContentType, Ranged<Cursor<&'static str>>) {
+    (ContentType::Plain, Ranged(Cursor::new(DIGITS)))
+}
+
+#[get("/offset")]
+fn offset() -> Ranged<Cursor<&'static str>> {
+    let mut cursor = Cursor::new(DIGITS);
+    cursor.set_position(10);
+    Ranged(cursor)
+}
+
+#[get("/tagged")]
+fn tagged() -> Tagged {
+    Tagged(
+        Ranged(Cursor::new(DIGITS)),
+        Header::new("ETag", "\"v1\""),
+        Header::new("Last-Modified", "Wed, 21 Oct 2015 07:28:00 GMT"),
+    )
+}
+
+#[get("/plain")]
+fn plain() -> &'static str {
+    DIGITS
+}
+
+fn client() -> Client {
+    let rocket = rocket::build()
+        .mount("/", routes![digits, offset, tagged, plain])
+        .mount("/static", FileServer::from(relative!("tests/static")));
+
+    Client::debug(rocket).unwrap()
+}
+
+fn get<'c>(client: &'c Client, uri: &'static str, range: &'static str) -> LocalResponse<'c> {
+    client.get(uri).header(Header::new("Range", range)).dispatch()
+}
+
+fn header<'a>(response: &'a LocalResponse<'_>, name: &str) -> Option<&'a str> {
+    response.headers().get_one(name)
+}
+
+#[test]
+fn full_responses_advertise_ranges() {
+    let client = client();
+    let response = client.get("/digits").dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(header(&response, "Accept-Ranges"), Some("bytes"));
+    assert_eq!(response.into_string().unwrap(), DIGITS);
+
+    let response = client.get("/plain").dispatch();
+    assert_eq!(header(&response, "Accept-Ranges"), None);
+
+    let response = client.get("/static/index.html").dispatch();
+    assert_eq!(header(&response, "Accept-Ranges"), Some("bytes"));
+}
+
+#[test]
+fn single_ranges() {
+    let client = client();
+    let cases = [
+        ("bytes=0-3", "bytes 0-3/36", "0123"),
+        ("bytes=10-", "bytes 10-35/36", &DIGITS[10..]),
+        ("bytes=-4", "bytes 32-35/36", "wxyz"),
+        ("bytes=30-100", "bytes 30-35/36", "uvwxyz"),
+        ("bytes=-100", "bytes 0-35/36", DIGITS),
+        ("bytes=0-1, 1-3", "bytes 0-3/36", "0123"),
+    ];
+
+    for (range, content_range, body) in cases {
+        let response = get(&client, "/digits", range);
+        assert_eq!(response.status(), Status::PartialContent, "{}", range);
+        assert_eq!(header(&response, "Content-Range"), Some(content_range));
+        assert_eq!(response.content_type(), Some(ContentType::Plain));
+        assert_eq!(response.into_string().unwrap(), body);
+    }
+}
+
+#[test]
+fn ranges_are_relative_to_the_initial_position() {
+    let client = client();
+    let response = get(&client, "/offset", "bytes=0-3");
+    assert_eq!(header(&response, "Content-Range"), Some("bytes 0-3/26"));
+    assert_eq!(response.into_string().unwrap(), "abcd");
+}
+
+#[test]
+fn multiple_ranges() {
+    let client = client();
+    let response = get(&client, "/digits", "bytes=-2,0-1,10-12");
+    assert_eq!(response.status(), Status::PartialContent);
+    assert!(header(&response, "Content-Range").is_none());
+
+    let content_type = response.content_type().unwrap();
+    assert_eq!(content_type.top(), "multipart");
+    assert_eq!(content_type.sub(), "byteranges");
+    let boundary = content_type.param("boundary").unwrap().to_string();
+
+    let expected = format!(
+        "--{b}\r\nContent-Type: text/plain; charset=utf-8\r\nContent-Range: bytes 0-1/36\r\n\r\n01\
+        \r\n--{b}\r\nContent-Type: text/plain; charset=utf-8\r\nContent-Range: bytes 10-12/36\r\n\r\nabc\
+        \r\n--{b}\r\nContent-Type: text/plain; charset=utf-8\r\nContent-Range: bytes 34-35/36\r\n\r\nyz\
+        \r\n--{b}--\r\n",
+        b = boundary
+    );
+
+    assert_eq!(response.into_string().unwrap(), expected);
+}
+
+#[test]
+fn unsatisfiable_ranges() {
+    let client = client();
+    for range in ["bytes=36-", "bytes=100-200", "bytes=-0", "bytes=40-50, 36-"] {
+        let response = get(&client, "/digits", range);
+        assert_eq!(response.status(), Status::RangeNotSatisfiable, "{}", range);
+        assert_eq!(header(&response, "Content-Range"), Some("bytes */36"));
+    }
+}
+
+#[test]
+fn invalid_or_ignored_ranges() {
+    let client = client();
+    for range in ["bytes=5-1", "items=0-1", "bytes=a-", "0-1", "bytes="] {
+        let response = get(&client, "/digits", range);
+        assert_eq!(response.status(), Status::Ok, "{}", range);
+        assert_eq!(response.into_string().unwrap(), DIGITS);
+    }
+
+    let response = get(&client, "/plain", "bytes=0-1");
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(response.into_string().unwrap(), DIGITS);
+
+    let too_many = vec!["0-0"; 17].join(",");
+    let response = client.get("/digits")
+        .header(Header::new("Range", format!("bytes={}", too_many)))
+        .dispatch();
+
+    assert_eq!(response.status(), Status::Ok);
+
+    let response = client.head("/digits")
+        .header(Header::new("Range", "bytes=0-1"))
+        .dispatch();
+
+    assert_eq!(response.status(), Status::Ok);
+    assert!(response.into_string().unwrap_or_default().is_empty());
+}
+
+#[test]
+fn if_range() {
+    let client = client();
+    let cases = [
+        ("\"v1\"", Status::PartialContent),
+        ("Wed, 21 Oct 2015 07:28:00 GMT", Status::PartialContent),
+        ("\"v2\"", Status::Ok),
+        ("W/\"v1\"", Status::Ok),
+        ("Thu, 22 Oct 2015 07:28:00 GMT", Status::Ok),
+    ];
+
+    for (if_range, status) in cases {
+        let response = client.get("/tagged")
+            .header(Header::new("Range", "bytes=0-3"))
+            .header(Header::new("If-Range", if_range))
+            .dispatch();
+
+        assert_eq!(response.status(), status, "{}", if_range);
+    }
+}
+
+#[test]
+fn file_server_ranges() {
+    let client = client();
+    let response = get(&client, "/static/index.html", "bytes=5-10");
+    assert_eq!(response.status(), Status::PartialContent);
+    assert_eq!(header(&response, "Content-Range"), Some("bytes 5-10/30"));
+    assert_eq!(response.content_type(), Some(ContentType::HTML));
+    assert_eq!(response.into_string().unwrap(), "a file");
+}
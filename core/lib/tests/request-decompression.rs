@@ -0,0 +1,214 @@
+#![cfg(all(feature = "compression", feature = "json"))]
+
+#[macro_use] extern crate rocket;
+
+use async_compression::tokio::bufread::{BrotliEncoder, GzipEncoder, ZlibEncoder, ZstdEncoder};
+use rocket::{Config, Request};
+use rocket::data::{Limits, ToByteUnit};
+use rocket::form::Form;
+use rocket::fs::TempFile;
+use rocket::http::{ContentType, Header, Status};
+use rocket::local::asynchronous::Client;
+use rocket::request::{self, FromRequest};
+use rocket::serde::json::Json;
+use rocket::tokio::io::AsyncReadExt;
+
+/// The request's `Content-Encoding`, if any, as seen by handlers.
+struct Coding(Option<String>);
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for Coding {
+    type Error = ();
+
+    async fn from_request(req: &'r Request<'_>) -> request::Outcome<Self, ()> {
+        let coding = req.headers().get_one("Content-Encoding").map(|s| s.to_string());
+        request::Outcome::Success(Coding(coding))
+    }
+}
+
+#[post("/string", data = "<body>")]
+fn string(coding: Coding, body: String) -> String {
+    format!("{:?}: {}", coding.0, body)
+}
+
+#[post("/bytes", data = "<body>")]
+fn bytes(body: Vec<u8>) -> Vec<u8> {
+    body
+}
+
+#[post("/json", data = "<body>")]
+fn json(body: Json<Vec<String>>) -> String {
+    body.join(",")
+}
+
+#[derive(FromForm)]
+struct Message<'r> {
+    name: &'r str,
+    text: &'r str,
+}
+
+#[post("/form", data = "<form>")]
+fn form(form: Form<Message<'_>>) -> String {
+    format!("{}: {}", form.name, form.text)
+}
+
+#[post("/file", data = "<file>")]
+async fn file(file: TempFile<'_>) -> String {
+    let mut contents = String::new();
+    let mut reader = file.open().await.unwrap();
+    reader.read_to_string(&mut contents).await.unwrap();
+    contents
+}
+
+async fn encode(coding: &str, data: &[u8]) -> Vec<u8> {
+    let mut encoded = vec![];
+    match coding {
+        "gzip" => GzipEncoder::new(data).read_to_end(&mut encoded).await,
+        "deflate" => ZlibEncoder::new(data).read_to_end(&mut encoded).await,
+        "br" => BrotliEncoder::new(data).read_to_end(&mut encoded).await,
+        "zstd" => ZstdEncoder::new(data).read_to_end(&mut encoded).await,
+        _ => panic!("unknown coding: {}", coding),
+    }.unwrap();
+
+    encoded
+}
+
+async fn client(decompress: bool) -> Client {
+    let limits = Limits::default()
+        .limit("string", 1.kibibytes())
+        .limit("json", 1.kibibytes());
+
+    let config = Config { decompress, limits, ..Config::debug_default() };
+    let rocket = rocket::custom(config)
+        .mount("/", routes![string, bytes, json, form, file]);
+
+    Client::debug(rocket).await.unwrap()
+}
+
+#[rocket::async_test]
+async fn bodies_are_not_decoded_by_default() {
+    let client = client(false).await;
+    let body = encode("gzip", b"hello").await;
+    let response = client.post("/bytes")
+        .header(Header::new("Content-Encoding", "gzip"))
+        .body(&body)
+        .dispatch().await;
+
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(response.into_bytes().await.unwrap(), body);
+
+    let response = client.post("/bytes")
+        .header(Header::new("Content-Encoding", "compress"))
+        .body("hello")
+        .dispatch().await;
+
+    assert_eq!(response.status(), Status::Ok);
+}
+
+#[rocket::async_test]
+async fn bodies_are_decoded() {
+    let client = client(true).await;
+    for coding in ["gzip", "deflate", "br", "zstd"] {
+        let response = client.post("/string")
+            .header(Header::new("Content-Encoding", coding))
+            .body(encode(coding, b"hello").await)
+            .dispatch().await;
+
+        assert_eq!(response.status(), Status::Ok, "{}", coding);
+        assert_eq!(response.into_string().await.unwrap(), "None: hello");
+    }
+
+    let response = client.post("/string")
+        .header(Header::new("Content-Encoding", "identity"))
+        .body("hello")
+        .dispatch().await;
+
+    assert_eq!(response.into_string().await.unwrap(), "Some(\"identity\"): hello");
+
+    let response = client.post("/string")
+        .body("hello")
+        .dispatch().await;
+
+    assert_eq!(response.into_string().await.unwrap(), "None: hello");
+}
+
+#[rocket::async_test]
+async fn stacked_codings_are_decoded() {
+    let client = client(true).await;
+    let body = encode("br", &encode("gzip", b"hello").await).await;
+    let response = client.post("/string")
+        .header(Header::new("Content-Encoding", "gzip, br"))
+        .body(body)
+        .dispatch().await;
+
+    assert_eq!(response.into_string().await.unwrap(), "None: hello");
+}
+
+#[rocket::async_test]
+async fn data_guards_see_decoded_bodies() {
+    let client = client(true).await;
+    let response = client.post("/json")
+        .header(ContentType::JSON)
+        .header(Header::new("Content-Encoding", "gzip"))
+        .body(encode("gzip", br#"["a", "b", "c"]"#).await)
+        .dispatch().await;
+
+    assert_eq!(response.into_string().await.unwrap(), "a,b,c");
+
+    let response = client.post("/form")
+        .header(ContentType::Form)
+        .header(Header::new("Content-Encoding", "br"))
+        .body(encode("br", b"name=Bob&text=hi+there").await)
+        .dispatch().await;
+
+    assert_eq!(response.into_string().await.unwrap(), "Bob: hi there");
+
+    let csv = "a,b,c\n".repeat(1000);
+    let response = client.post("/file")
+        .header(ContentType::CSV)
+        .header(Header::new("Content-Encoding", "zstd"))
+        .body(encode("zstd", csv.as_bytes()).await)
+        .dispatch().await;
+
+    assert_eq!(response.into_string().await.unwrap(), csv);
+}
+
+#[rocket::async_test]
+async fn limits_apply_to_decoded_size() {
+    let client = client(true).await;
+    let json = format!("[{}\"a\"]", "\"a\",".repeat(16 * 1024));
+    let bomb = encode("gzip", json.as_bytes()).await;
+    assert!(bomb.len() < 1024);
+
+    let response = client.post("/json")
+        .header(ContentType::JSON)
+        .header(Header::new("Content-Encoding", "gzip"))
+        .body(bomb)
+        .dispatch().await;
+
+    assert_eq!(response.status(), Status::PayloadTooLarge);
+}
+
+#[rocket::async_test]
+async fn unsupported_codings_are_rejected() {
+    let client = client(true).await;
+    for coding in ["compress", "gzip, compress", "x-unknown"] {
+        let response = client.post("/string")
+            .header(Header::new("Content-Encoding", coding))
+            .body("hello")
+            .dispatch().await;
+
+        assert_eq!(response.status(), Status::UnsupportedMediaType, "{}", coding);
+    }
+}
+
+#[rocket::async_test]
+async fn corrupt_bodies_fail() {
+    let client = client(true).await;
+    let response = client.post("/string")
+        .header(Header::new("Content-Encoding", "gzip"))
+        .body("definitely not gzip")
+        .dispatch().await;
+
+    assert_eq!(response.status(), Status::BadRequest);
+}
@@ -0,0 +1,195 @@
+#![cfg(unix)]
+
+#[macro_use] extern crate rocket;
+
+mod common;
+
+use std::io::{Read, Write};
+use std::os::raw::c_int;
+use std::os::unix::io::{AsRawFd, RawFd};
+use std::os::unix::process::CommandExt;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use rocket::Shutdown;
+use rocket::config::Config;
+
+// Set in the child processes spawned by `activate()`.
+const CHILD_VAR: &str = "ROCKET_TEST_SOCKET_ACTIVATION_CHILD";
+
+// Both are `1` on every supported Unix.
+const F_GETFD: c_int = 1;
+const FD_CLOEXEC: c_int = 1;
+
+extern "C" {
+    fn dup2(oldfd: RawFd, newfd: RawFd) -> RawFd;
+    fn fcntl(fd: RawFd, cmd: c_int, ...) -> c_int;
+}
+
+#[get("/")]
+fn index() -> &'static str {
+    "activated"
+}
+
+#[get("/inherited")]
+fn inherited() -> String {
+    // The sockets were adopted at launch, so they can't be adopted again.
+    let adopted = rocket::http::private::activated_listeners().unwrap();
+    let cloexec = |fd| unsafe { fcntl(fd, F_GETFD) } & FD_CLOEXEC != 0;
+    format!("{} {} {}", adopted.len(), cloexec(3), cloexec(4))
+}
+
+#[get("/shutdown")]
+fn shutdown(shutdown: Shutdown) -> &'static str {
+    shutdown.notify();
+    "bye"
+}
+
+fn get<S: Read + Write>(stream: S, uri: &str) -> String {
+    common::exchange_blocking(stream, common::get_request("localhost", uri).as_bytes())
+}
+
+// The child may fail to serve; time out instead of hanging if it does.
+fn connect_unix(path: &Path) -> std::os::unix::net::UnixStream {
+    let stream = std::os::unix::net::UnixStream::connect(path).unwrap();
+    stream.set_read_timeout(Some(common::TIMEOUT)).unwrap();
+    stream
+}
+
+/// Runs in the child: serves on the sockets passed by the parent.
+#[rocket::async_test]
+async fn activated_child() {
+    if std::env::var_os(CHILD_VAR).is_none() {
+        return;
+    }
+
+    let rocket = rocket::custom(Config { socket_activation: true, ..Config::debug_default() })
+        .mount("/", routes![index, inherited, shutdown]);
+
+    rocket.launch().await.expect("clean shutdown");
+}
+
+/// Runs in the child: fails to launch on the non-listening socket passed.
+#[rocket::async_test]
+async fn rejecting_child() {
+    if std::env::var_os(CHILD_VAR).is_none() {
+        return;
+    }
+
+    let rocket = rocket::custom(Config { socket_activation: true, ..Config::debug_default() });
+    let error = rocket.launch().await.unwrap_err();
+    assert!(matches!(error.kind(), rocket::error::ErrorKind::Bind(_)));
+}
+
+/// Like a service manager, spawns the child test `test` with `fds` passed as
+/// fds 3 and up. `LISTEN_PID` must be the child's PID, which the shell knows
+/// before `exec`ing.
+fn activate(test: &str, fds: &[RawFd], names: &str) -> std::process::Child {
+    let fds = fds.to_vec();
+    let mut command = Command::new("/bin/sh");
+    command.arg("-c")
+        .arg(r#"LISTEN_PID=$$; export LISTEN_PID; exec "$0" "$@""#)
+        .arg(std::env::current_exe().unwrap())
+        .args(["--exact", test, "--nocapture"])
+        .env(CHILD_VAR, "1")
+        .env("LISTEN_FDS", fds.len().to_string())
+        .env("LISTEN_FDNAMES", names)
+        // Fail, rather than silently bind, if the sockets aren't adopted.
+        .env("ROCKET_PORT", "1")
+        .stdout(Stdio::null());
+
+    unsafe {
+        command.pre_exec(move || {
+            // `dup2` clears close-on-exec for the new descriptors unless they
+            // equal the old ones, so move them out of the way first.
+            for (i, &fd) in fds.iter().enumerate() {
+                if dup2(fd, 100 + i as RawFd) < 0 {
+                    return Err(std::io::Error::last_os_error());
+                }
+            }
+
+            for i in 0..fds.len() as RawFd {
+                if dup2(100 + i, 3 + i) < 0 {
+                    return Err(std::io::Error::last_os_error());
+                }
+            }
+
+            Ok(())
+        });
+    }
+
+    command.spawn().unwrap()
+}
+
+#[test]
+fn serves_on_activated_sockets() {
+    let tcp = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = tcp.local_addr().unwrap();
+    let path = std::env::temp_dir().join(format!("rocket-activated-{}.sock", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let unix = std::os::unix::net::UnixListener::bind(&path).unwrap();
+
+    let fds = [tcp.as_raw_fd(), unix.as_raw_fd()];
+    let mut child = activate("activated_child", &fds, "web:local");
+
+    // The sockets are already listening, so connections queue until the
+    // child accepts them.
+    let response = get(common::connect(addr), "/");
+    assert!(response.starts_with("HTTP/1.1 200 OK"), "{}", response);
+    assert!(response.ends_with("\r\n\r\nactivated"));
+
+    let response = get(connect_unix(&path), "/");
+    assert!(response.ends_with("\r\n\r\nactivated"));
+
+    let response = get(common::connect(addr), "/inherited");
+    assert!(response.ends_with("\r\n\r\n0 true true"), "{}", response);
+
+    get(common::connect(addr), "/shutdown");
+    assert!(child.wait().unwrap().success());
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn rejects_non_listening_sockets() {
+    let udp = std::net::UdpSocket::bind("127.0.0.1:0").unwrap();
+    let mut child = activate("rejecting_child", &[udp.as_raw_fd()], "");
+    assert!(child.wait().unwrap().success());
+}
+
+#[rocket::async_test]
+async fn falls_back_to_binding_without_sockets() {
+    let liftoff = common::launch(rocket::custom(Config {
+        socket_activation: true,
+        port: 0,
+        ..Config::debug_default()
+    })).await;
+
+    assert!(!liftoff.config.socket_activation);
+    assert_eq!(liftoff.addresses.len(), 1);
+    liftoff.shutdown.notify();
+}
+
+#[rocket::async_test]
+async fn rejects_socket_activation_with_other_listeners() {
+    use std::net::Ipv4Addr;
+    use rocket::config::{Endpoint, UnixConfig};
+
+    let rocket = rocket::custom(Config {
+        socket_activation: true,
+        unix: Some(UnixConfig::from_path("/tmp/rocket-activation-conflict.sock")),
+        ..Config::debug_default()
+    });
+
+    let error = rocket.ignite().await.unwrap_err();
+    let path = ["socket_activation"];
+    assert!(matches!(error.kind(), rocket::error::ErrorKind::Config(e) if e.path == path));
+
+    let rocket = rocket::custom(Config {
+        socket_activation: true,
+        endpoints: vec![Endpoint::new(Ipv4Addr::LOCALHOST, 0).with_https_redirect(true)],
+        ..Config::debug_default()
+    });
+
+    let error = rocket.ignite().await.unwrap_err();
+    assert!(matches!(error.kind(), rocket::error::ErrorKind::Config(e) if e.path == path));
+}
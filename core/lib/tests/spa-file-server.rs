@@ -0,0 +1,84 @@
+#[macro_use] extern crate rocket;
+
+use rocket::http::Status;
+use rocket::local::blocking::Client;
+use rocket::fs::{embed, relative, EmbeddedFileServer, FileServer, Options};
+
+const INDEX: &str = include_str!("static/index.html");
+
+#[get("/hello")]
+fn hello() -> &'static str {
+    "Hello from the API!"
+}
+
+#[get("/fallback", rank = 20)]
+fn fallback() -> &'static str {
+    "unreachable"
+}
+
+fn client() -> Client {
+    let spa = Options::Index | Options::Spa;
+    let rocket = rocket::build()
+        .mount("/app/api", routes![hello])
+        .mount("/app", routes![fallback])
+        .mount("/app", FileServer::spa(relative!("tests/static")).rank(11))
+        .mount("/embedded", EmbeddedFileServer::new(embed!("tests/static"), spa))
+        .mount("/file", FileServer::new(relative!("tests/static/index.html"),
+            Options::IndexFile | Options::Spa));
+
+    Client::debug(rocket).unwrap()
+}
+
+fn assert_index(client: &Client, uri: &str) {
+    let response = client.get(uri).dispatch();
+    assert_eq!(response.status(), Status::Ok, "{}", uri);
+    assert_eq!(response.into_string().unwrap(), INDEX, "{}", uri);
+}
+
+fn assert_missing(client: &Client, uri: &str) {
+    let response = client.get(uri).dispatch();
+    assert_eq!(response.status(), Status::NotFound, "{}", uri);
+}
+
+#[test]
+fn client_routes_serve_index() {
+    let client = client();
+    for prefix in ["/app", "/embedded", "/file"] {
+        for path in ["/", "/users", "/users/42", "/users/42/", "/a/b/c", "/inner/x"] {
+            assert_index(&client, &format!("{}{}", prefix, path));
+        }
+    }
+
+    // Routes with a higher rank than the file server's are never reached.
+    assert_index(&client, "/app/fallback");
+}
+
+#[test]
+fn assets_are_not_rewritten() {
+    let client = client();
+    for prefix in ["/app", "/embedded", "/file"] {
+        for path in ["/app.js", "/img/missing.png", "/assets/logo", "/assets/", "/v1.2/x.json"] {
+            assert_missing(&client, &format!("{}{}", prefix, path));
+        }
+    }
+}
+
+#[test]
+fn files_and_routes_take_precedence() {
+    let client = client();
+    let response = client.get("/app/api/hello").dispatch();
+    assert_eq!(response.into_string().unwrap(), "Hello from the API!");
+
+    let response = client.get("/app/inner/goodbye").dispatch();
+    assert_eq!(response.into_string().unwrap(), include_str!("static/inner/goodbye"));
+
+    let response = client.get("/embedded/other/hello.txt").dispatch();
+    assert_eq!(response.into_string().unwrap(), "Hi!\n");
+
+    let response = client.get("/app/inner/").dispatch();
+    assert_eq!(response.into_string().unwrap(), include_str!("static/inner/index.html"));
+
+    let response = client.head("/app/users/42").dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    assert!(response.into_string().unwrap_or_default().is_empty());
+}
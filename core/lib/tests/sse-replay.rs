@@ -0,0 +1,137 @@
+#[macro_use] extern crate rocket;
+
+use rocket::State;
+use rocket::futures::stream::{self, StreamExt};
+use rocket::http::Header;
+use rocket::local::blocking::Client;
+use rocket::response::stream::{Event, EventStream, LastEventId, ReplayBuffer};
+
+#[get("/last")]
+fn last(last: Option<LastEventId<'_>>) -> String {
+    last.map(|id| id.to_string()).unwrap_or_else(|| "none".into())
+}
+
+#[get("/events/<room>")]
+fn events<'r>(
+    room: &str,
+    last: Option<LastEventId<'_>>,
+    replay: &'r State<ReplayBuffer>,
+) -> EventStream![Event + 'r] {
+    // Pretend the most recently pushed event is also received live.
+    let live = vec![Event::data("c").id("2"), Event::data("d").id("live")];
+    EventStream::from(replay.resume(room, last.as_ref(), stream::iter(live))).heartbeat(None)
+}
+
+fn client() -> Client {
+    let replay = ReplayBuffer::new(2).with_id_prefix("");
+    for data in ["a", "b", "c"] {
+        replay.push("lobby", Event::data(data));
+    }
+
+    let rocket = rocket::build().manage(replay).mount("/", routes![last, events]);
+    Client::debug(rocket).unwrap()
+}
+
+#[test]
+fn last_event_id_guard() {
+    let client = client();
+    let response = client.get("/last").header(Header::new("Last-Event-ID", "42")).dispatch();
+    assert_eq!(response.into_string().unwrap(), "42");
+
+    let response = client.get("/last").header(Header::new("Last-Event-ID", "")).dispatch();
+    assert_eq!(response.into_string().unwrap(), "none");
+
+    let response = client.get("/last").dispatch();
+    assert_eq!(response.into_string().unwrap(), "none");
+}
+
+#[test]
+fn replay_buffer_is_bounded() {
+    let replay = ReplayBuffer::new(2).with_id_prefix("");
+    let events: Vec<_> = (0..3).map(|i| replay.push("a", Event::data(i.to_string()))).collect();
+    assert_eq!(events[0], Event::data("0").id("0"));
+    replay.push("b", Event::data("other").id("x"));
+
+    let missed = |last: &str| replay.replay("a", &LastEventId::from(last));
+    assert_eq!(missed("1"), vec![Event::data("2").id("2")]);
+    assert_eq!(missed("2"), vec![]);
+    assert_eq!(missed("0"), vec![Event::data("1").id("1"), Event::data("2").id("2")]);
+    assert_eq!(missed("unknown"), missed("0"));
+    assert_eq!(replay.replay("c", &LastEventId::from("0")), vec![]);
+
+    let unbuffered = ReplayBuffer::new(0).with_id_prefix("");
+    assert_eq!(unbuffered.push("a", Event::data("x")), Event::data("x").id("0"));
+    assert_eq!(unbuffered.replay("a", &LastEventId::from("unknown")), vec![]);
+}
+
+#[test]
+fn replay_buffer_ids_are_unique_to_buffers() {
+    // A buffer before and after an application restart.
+    let before = ReplayBuffer::new(4);
+    let old = (0..3).map(|i| before.push("a", Event::data(i.to_string()))).last().unwrap();
+
+    let after = ReplayBuffer::new(4);
+    let new: Vec<_> = (0..3).map(|i| after.push("a", Event::data(i.to_string()))).collect();
+    assert_ne!(before.id_prefix(), after.id_prefix());
+    assert!(!new.contains(&old));
+
+    // A client that last received `old` missed every event recorded since.
+    let id = format!("{}2", before.id_prefix());
+    assert_eq!(after.replay("a", &LastEventId::from(&*id)), new);
+}
+
+#[test]
+fn replay_buffer_evicts_channels() {
+    let replay = ReplayBuffer::new(4).with_max_channels(2);
+    let all = |channel: &str| replay.replay(channel, &LastEventId::from("none"));
+
+    replay.push("a", Event::data("a").id("a"));
+    replay.push("b", Event::data("b").id("b"));
+    replay.push("a", Event::data("a2").id("a2"));
+
+    // `b` was least recently pushed to, so a new channel evicts it.
+    replay.push("c", Event::data("c").id("c"));
+    assert_eq!(all("a").len(), 2);
+    assert!(all("b").is_empty());
+    assert_eq!(all("c"), vec![Event::data("c").id("c")]);
+
+    assert!(replay.remove("a"));
+    assert!(!replay.remove("a"));
+    assert!(all("a").is_empty());
+
+    replay.push("d", Event::data("d").id("d"));
+    assert_eq!(all("c").len(), 1);
+
+    replay.clear();
+    assert!(all("c").is_empty() && all("d").is_empty());
+
+    let unbuffered = ReplayBuffer::new(4).with_max_channels(0);
+    unbuffered.push("a", Event::data("a"));
+    assert!(unbuffered.replay("a", &LastEventId::from("none")).is_empty());
+}
+
+#[test]
+fn resumes_event_streams() {
+    let client = client();
+    let response = client.get("/events/lobby").dispatch();
+    assert_eq!(response.into_string().unwrap(), "id:2\ndata:c\n\nid:live\ndata:d\n\n");
+
+    let response = client.get("/events/lobby")
+        .header(Header::new("Last-Event-ID", "1"))
+        .dispatch();
+
+    assert_eq!(response.into_string().unwrap(), "id:2\ndata:c\n\nid:live\ndata:d\n\n");
+
+    let response = client.get("/events/lobby")
+        .header(Header::new("Last-Event-ID", "0"))
+        .dispatch();
+
+    assert_eq!(response.into_string().unwrap(),
+        "id:1\ndata:b\n\nid:2\ndata:c\n\nid:live\ndata:d\n\n");
+
+    let response = client.get("/events/elsewhere")
+        .header(Header::new("Last-Event-ID", "0"))
+        .dispatch();
+
+    assert_eq!(response.into_string().unwrap(), "id:2\ndata:c\n\nid:live\ndata:d\n\n");
+}
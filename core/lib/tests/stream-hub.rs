@@ -0,0 +1,116 @@
+#[macro_use] extern crate rocket;
+
+use rocket::{State, Shutdown};
+use rocket::futures::StreamExt;
+use rocket::local::asynchronous::Client;
+use rocket::response::stream::{Event, EventStream, Hub, Lag, Subscription, TextStream};
+
+#[get("/text/<topic>")]
+fn text(topic: &str, hub: &State<Hub<String>>, end: Shutdown) -> TextStream<Subscription<String>> {
+    TextStream::from(hub.subscribe(topic, end))
+}
+
+#[get("/events/<topic>")]
+fn events(
+    topic: &str,
+    hub: &State<Hub<Event>>,
+    end: Shutdown,
+) -> EventStream<Subscription<Event>> {
+    EventStream::from(hub.subscribe(topic, end)).heartbeat(None)
+}
+
+async fn client() -> Client {
+    let rocket = rocket::build()
+        .manage(Hub::<String>::new(16))
+        .manage(Hub::<Event>::new(16))
+        .mount("/", routes![text, events]);
+
+    Client::debug(rocket).await.unwrap()
+}
+
+#[async_test]
+async fn streams_published_values() {
+    let client = client().await;
+    let hub = client.rocket().state::<Hub<String>>().unwrap();
+
+    let news = client.get("/text/news").dispatch().await;
+    let sports = client.get("/text/sports").dispatch().await;
+    assert_eq!(hub.subscribers("news"), 1);
+
+    assert_eq!(hub.publish("news", "hello, ".into()), 1);
+    assert_eq!(hub.publish("sports", "goal!".into()), 1);
+    assert_eq!(hub.publish("news", "world".into()), 1);
+    assert_eq!(hub.publish("weather", "rain".into()), 0);
+
+    hub.close("news");
+    hub.close("sports");
+    assert_eq!(news.into_string().await.unwrap(), "hello, world");
+    assert_eq!(sports.into_string().await.unwrap(), "goal!");
+
+    let hub = client.rocket().state::<Hub<Event>>().unwrap();
+    let events = client.get("/events/chat").dispatch().await;
+    hub.publish("chat", Event::data("hi").id("1"));
+    hub.close("chat");
+    assert_eq!(events.into_string().await.unwrap(), "id:1\ndata:hi\n\n");
+}
+
+#[async_test]
+async fn removes_unsubscribed_topics() {
+    let client = client().await;
+    let hub = Hub::<usize>::new(4);
+
+    let subscription = hub.subscribe("a", client.rocket().shutdown());
+    assert_eq!(hub.subscribers("a"), 1);
+    assert_eq!(hub.publish("a", 1), 1);
+
+    // The topic is removed with its last subscription, not on publish.
+    let other = hub.subscribe("a", client.rocket().shutdown());
+    let b = hub.subscribe("b", client.rocket().shutdown());
+    assert_eq!(hub.topics(), 2);
+
+    drop(subscription);
+    assert_eq!((hub.topics(), hub.subscribers("a")), (2, 1));
+
+    drop(other);
+    assert_eq!((hub.topics(), hub.subscribers("a")), (1, 0));
+    assert_eq!(hub.publish("a", 2), 0);
+
+    // Ended subscriptions are removed when dropped, too.
+    hub.close("b");
+    assert_eq!(hub.topics(), 0);
+    assert!(b.collect::<Vec<_>>().await.is_empty());
+    assert_eq!(hub.topics(), 0);
+
+    let mut subscription = hub.subscribe("a", client.rocket().shutdown());
+    hub.publish("a", 3);
+    hub.close("a");
+    assert_eq!(subscription.next().await, Some(3));
+    assert_eq!(subscription.next().await, None);
+}
+
+#[async_test]
+async fn handles_lagging_subscribers() {
+    let client = client().await;
+
+    let hub = Hub::<usize>::new(2);
+    let subscription = hub.subscribe("a", client.rocket().shutdown());
+    (0..5).for_each(|i| { hub.publish("a", i); });
+    hub.close("a");
+    assert_eq!(subscription.collect::<Vec<_>>().await, vec![3, 4]);
+
+    let hub = Hub::<usize>::new(2).lag(Lag::Disconnect);
+    let mut subscription = hub.subscribe("a", client.rocket().shutdown());
+    hub.publish("a", 0);
+    assert_eq!(subscription.next().await, Some(0));
+    (1..5).for_each(|i| { hub.publish("a", i); });
+    assert_eq!(subscription.next().await, None);
+}
+
+#[async_test]
+async fn ends_on_shutdown() {
+    let client = client().await;
+    let hub = Hub::<usize>::new(2);
+    let mut subscription = hub.subscribe("a", client.rocket().shutdown());
+    client.rocket().shutdown().notify();
+    assert_eq!(subscription.next().await, None);
+}
@@ -0,0 +1,173 @@
+#![cfg(feature = "tls")]
+
+#[macro_use] extern crate rocket;
+
+mod common;
+
+use std::io::Cursor;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use rocket::config::{Config, TlsConfig, SniCertificate};
+use rocket::http::tls::rustls::{self, Certificate, ClientConfig, ClientConnection, ServerName};
+use rocket::http::tls::util::{load_certs, load_ca_certs};
+use rocket::tls::TlsReloader;
+use rocket::Shutdown;
+
+const PRIVATE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../../examples/tls/private");
+
+#[get("/")]
+fn index() -> &'static str {
+    "Hello, TLS!"
+}
+
+#[post("/reload")]
+fn reload(reloader: TlsReloader) -> &'static str {
+    match reloader.reload() {
+        Ok(()) => "reloaded",
+        Err(_) => "failed",
+    }
+}
+
+fn private(file: &str) -> PathBuf {
+    Path::new(PRIVATE).join(file)
+}
+
+fn cert(file: &str) -> Certificate {
+    let pem = std::fs::read(private(file)).unwrap();
+    load_certs(&mut Cursor::new(pem)).unwrap().remove(0)
+}
+
+/// Connects to `addr` with SNI `localhost`, requests `uri`, and returns the
+/// server's leaf certificate and the response.
+fn request(addr: SocketAddr, method: &str, uri: &str) -> (Certificate, String) {
+    let ca = std::fs::read(private("ca_cert.pem")).unwrap();
+    let config = ClientConfig::builder()
+        .with_safe_defaults()
+        .with_root_certificates(load_ca_certs(&mut Cursor::new(ca)).unwrap())
+        .with_no_client_auth();
+
+    let name = ServerName::try_from("localhost").unwrap();
+    let mut conn = ClientConnection::new(Arc::new(config), name).unwrap();
+    let mut sock = common::connect(addr);
+
+    let stream = rustls::Stream::new(&mut conn, &mut sock);
+    let request = format!("{} {} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n",
+        method, uri);
+
+    let response = common::exchange_blocking(stream, request.as_bytes());
+    let leaf = conn.peer_certificates().unwrap()[0].clone();
+    (leaf, response)
+}
+
+async fn get(addr: SocketAddr) -> Certificate {
+    rocket::tokio::task::spawn_blocking(move || {
+        let (leaf, response) = request(addr, "GET", "/");
+        assert!(response.ends_with("\r\n\r\nHello, TLS!"), "{}", response);
+        leaf
+    }).await.unwrap()
+}
+
+async fn launch(tls: TlsConfig) -> (Shutdown, SocketAddr) {
+    let rocket = rocket::custom(Config { tls: Some(tls), ..Config::debug_default() })
+        .mount("/", routes![index, reload]);
+
+    common::launch_ephemeral(rocket).await
+}
+
+/// Copies the example `name` certificate and key to `certs` and `key`.
+fn install(name: &str, key_suffix: &str, certs: &Path, key: &Path) {
+    std::fs::copy(private(&format!("{}_cert.pem", name)), certs).unwrap();
+    std::fs::copy(private(&format!("{}_key{}.pem", name, key_suffix)), key).unwrap();
+}
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("rocket-{}-{}", name, std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[rocket::async_test]
+async fn serves_certificate_by_sni() {
+    let ecdsa = SniCertificate::from_paths(
+        private("ecdsa_nistp256_sha256_cert.pem"),
+        private("ecdsa_nistp256_sha256_key_pkcs8.pem"),
+    );
+
+    let tls = TlsConfig::from_paths(private("rsa_sha256_cert.pem"), private("rsa_sha256_key.pem"));
+    let (shutdown, addr) = launch(tls.clone()).await;
+    assert_eq!(get(addr).await, cert("rsa_sha256_cert.pem"));
+    shutdown.notify();
+
+    let (shutdown, addr) = launch(tls.clone().with_sni("LocalHost", ecdsa.clone())).await;
+    assert_eq!(get(addr).await, cert("ecdsa_nistp256_sha256_cert.pem"));
+    shutdown.notify();
+
+    let (shutdown, addr) = launch(tls.with_sni("*.localhost", ecdsa)).await;
+    assert_eq!(get(addr).await, cert("rsa_sha256_cert.pem"));
+    shutdown.notify();
+}
+
+#[rocket::async_test]
+async fn reloads_on_request() {
+    let dir = temp_dir("tls-reload");
+    let (certs, key) = (dir.join("cert.pem"), dir.join("key.pem"));
+    install("rsa_sha256", "", &certs, &key);
+
+    let (shutdown, addr) = launch(TlsConfig::from_paths(&certs, &key)).await;
+    assert_eq!(get(addr).await, cert("rsa_sha256_cert.pem"));
+
+    // A failed reload keeps the previous certificates.
+    std::fs::write(&key, "not a key").unwrap();
+    let (_, response) = rocket::tokio::task::spawn_blocking(move || {
+        request(addr, "POST", "/reload")
+    }).await.unwrap();
+
+    assert!(response.ends_with("failed"), "{}", response);
+    assert_eq!(get(addr).await, cert("rsa_sha256_cert.pem"));
+
+    install("ed25519", "", &certs, &key);
+    let (leaf, response) = rocket::tokio::task::spawn_blocking(move || {
+        request(addr, "POST", "/reload")
+    }).await.unwrap();
+
+    // The connection that triggered the reload keeps its certificate.
+    assert!(response.ends_with("reloaded"), "{}", response);
+    assert_eq!(leaf, cert("rsa_sha256_cert.pem"));
+    assert_eq!(get(addr).await, cert("ed25519_cert.pem"));
+
+    shutdown.notify();
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[rocket::async_test]
+async fn reloads_on_file_change() {
+    let dir = temp_dir("tls-watch");
+    let (certs, key) = (dir.join("cert.pem"), dir.join("key.pem"));
+    install("rsa_sha256", "", &certs, &key);
+
+    let tls = TlsConfig::from_paths(&certs, &key).with_reload_interval(1);
+    let (shutdown, addr) = launch(tls).await;
+    assert_eq!(get(addr).await, cert("rsa_sha256_cert.pem"));
+
+    // Ensure the modification time changes even on coarse filesystems.
+    rocket::tokio::time::sleep(Duration::from_millis(1100)).await;
+    install("ecdsa_nistp256_sha256", "_pkcs8", &certs, &key);
+
+    let expected = cert("ecdsa_nistp256_sha256_cert.pem");
+    let mut reloaded = false;
+    for _ in 0..50 {
+        if get(addr).await == expected {
+            reloaded = true;
+            break;
+        }
+
+        rocket::tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+
+    assert!(reloaded);
+    shutdown.notify();
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,163 @@
+#![cfg(unix)]
+
+#[macro_use] extern crate rocket;
+
+mod common;
+
+use std::os::unix::fs::{MetadataExt, PermissionsExt};
+use std::path::{Path, PathBuf};
+
+use rocket::config::{Config, UnixConfig};
+use rocket::request::PeerCredentials;
+use rocket::tokio::net::UnixStream;
+
+#[get("/")]
+fn uid(credentials: PeerCredentials) -> String {
+    credentials.uid().to_string()
+}
+
+#[get("/remote")]
+fn remote(remote: Option<std::net::SocketAddr>) -> String {
+    format!("{:?}", remote)
+}
+
+fn socket_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("rocket-{}-{}.sock", name, std::process::id()))
+}
+
+async fn get(path: &Path, uri: &str) -> String {
+    let stream = UnixStream::connect(path).await.unwrap();
+    common::exchange(stream, common::get_request("localhost", uri).as_bytes()).await
+}
+
+async fn launch(unix: UnixConfig) -> rocket::Shutdown {
+    let rocket = rocket::custom(Config { unix: Some(unix), ..Config::debug_default() })
+        .mount("/", routes![uid, remote]);
+
+    common::launch(rocket).await.shutdown
+}
+
+#[rocket::async_test]
+async fn serves_on_unix_socket_with_credentials() {
+    let path = socket_path("creds");
+    let shutdown = launch(UnixConfig::from_path(&path).with_mode(0o600)).await;
+
+    let metadata = std::fs::metadata(&path).unwrap();
+    assert_eq!(metadata.permissions().mode() & 0o777, 0o600);
+
+    let response = get(&path, "/").await;
+    assert!(response.starts_with("HTTP/1.1 200 OK"));
+    assert!(response.ends_with(&format!("\r\n\r\n{}", metadata.uid())));
+
+    let response = get(&path, "/remote").await;
+    assert!(response.ends_with("\r\n\r\nNone"));
+
+    shutdown.notify();
+}
+
+#[rocket::async_test]
+async fn removes_stale_socket() {
+    let path = socket_path("stale");
+    drop(std::os::unix::net::UnixListener::bind(&path).unwrap());
+    assert!(path.exists());
+
+    let shutdown = launch(UnixConfig::from_path(&path)).await;
+    assert!(get(&path, "/").await.starts_with("HTTP/1.1 200 OK"));
+    shutdown.notify();
+}
+
+#[rocket::async_test]
+async fn does_not_remove_stale_socket_without_reuse() {
+    let path = socket_path("noreuse");
+    drop(std::os::unix::net::UnixListener::bind(&path).unwrap());
+
+    let rocket = rocket::custom(Config {
+        unix: Some(UnixConfig::from_path(&path).with_reuse(false)),
+        ..Config::debug_default()
+    });
+
+    let error = rocket.launch().await.unwrap_err();
+    assert!(matches!(error.kind(), rocket::error::ErrorKind::Bind(_)));
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[rocket::async_test]
+async fn sets_socket_ownership() {
+    // A file we create belongs to a group we can assign the socket to.
+    let path = socket_path("owner");
+    std::fs::write(&path, "").unwrap();
+    let gid = std::fs::metadata(&path).unwrap().gid();
+    std::fs::remove_file(&path).unwrap();
+
+    let shutdown = launch(UnixConfig::from_path(&path).with_group(gid)).await;
+    assert_eq!(std::fs::metadata(&path).unwrap().gid(), gid);
+    shutdown.notify();
+}
+
+#[rocket::async_test]
+async fn rejects_unix_with_endpoints() {
+    use std::net::Ipv4Addr;
+    use rocket::config::Endpoint;
+
+    let rocket = rocket::custom(Config {
+        unix: Some(UnixConfig::from_path(socket_path("endpoints"))),
+        endpoints: vec![Endpoint::new(Ipv4Addr::LOCALHOST, 0)],
+        ..Config::debug_default()
+    });
+
+    let error = rocket.ignite().await.unwrap_err();
+    assert!(matches!(error.kind(), rocket::error::ErrorKind::Config(e) if e.path == ["unix"]));
+}
+
+#[rocket::async_test]
+async fn socket_is_never_reachable_with_umask_permissions() {
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    let path = socket_path("mode");
+    let stop = Arc::new(AtomicBool::new(false));
+
+    // Record the mode of the socket file the moment it appears.
+    let watcher = std::thread::spawn({
+        let (path, stop) = (path.clone(), stop.clone());
+        move || loop {
+            let stopped = stop.load(Ordering::Acquire);
+            if let Ok(metadata) = std::fs::symlink_metadata(&path) {
+                return Some(metadata.permissions().mode() & 0o777);
+            }
+
+            if stopped {
+                return None;
+            }
+        }
+    });
+
+    let shutdown = launch(UnixConfig::from_path(&path).with_mode(0o600)).await;
+    stop.store(true, Ordering::Release);
+    assert_eq!(watcher.join().unwrap(), Some(0o600));
+    assert!(get(&path, "/").await.starts_with("HTTP/1.1 200 OK"));
+
+    // The private directory the socket was bound in is removed.
+    let prefix = format!(".rocket-{}-", std::process::id());
+    let leftover = std::fs::read_dir(path.parent().unwrap()).unwrap()
+        .filter_map(|entry| entry.ok())
+        .any(|entry| entry.file_name().to_string_lossy().starts_with(&prefix));
+
+    assert!(!leftover);
+    shutdown.notify();
+}
+
+#[rocket::async_test]
+async fn does_not_replace_live_socket_with_mode() {
+    let path = socket_path("live");
+    let _live = std::os::unix::net::UnixListener::bind(&path).unwrap();
+
+    let rocket = rocket::custom(Config {
+        unix: Some(UnixConfig::from_path(&path).with_mode(0o600)),
+        ..Config::debug_default()
+    });
+
+    let error = rocket.launch().await.unwrap_err();
+    assert!(matches!(error.kind(), rocket::error::ErrorKind::Bind(_)));
+    std::fs::remove_file(&path).unwrap();
+}
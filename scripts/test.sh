@@ -131,7 +131,9 @@ function test_core() {
     http2
     json
     msgpack
+    cbor
     uuid
+    compression
   )
 
   echo ":: Building and checking core [no features]..."